@@ -0,0 +1,929 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use tokio::task;
+
+use crate::bookmarks::path_to_file_uri;
+use crate::{file_types, recent, terminal};
+
+const ICON_SIZES: &[&str] = &["scalable", "256x256", "128x128", "64x64", "48x48", "32x32"];
+const ICON_EXTENSIONS: &[&str] = &["svg", "png", "xpm"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Application {
+    id: String,
+    name: String,
+    icon: Option<String>,
+    icon_path: Option<String>,
+    is_default: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplicationChoices {
+    mime_type: String,
+    applications: Vec<Application>,
+}
+
+struct DesktopEntry {
+    path: PathBuf,
+    name: String,
+    exec: String,
+    icon: Option<String>,
+    mime_types: Vec<String>,
+    terminal: bool,
+}
+
+#[derive(Default)]
+struct Associations {
+    defaults: HashMap<String, Vec<String>>,
+    added: HashMap<String, Vec<String>>,
+    removed: HashMap<String, HashSet<String>>,
+}
+
+type IniGroups = Vec<(String, Vec<(String, String)>)>;
+
+fn env_paths(variable: &str, fallback: &str) -> Vec<PathBuf> {
+    std::env::var(variable)
+        .ok()
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| fallback.to_string())
+        .split(':')
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// `$XDG_DATA_HOME` followed by `$XDG_DATA_DIRS`, highest priority first.
+fn data_dirs() -> Vec<PathBuf> {
+    dirs::data_dir()
+        .into_iter()
+        .chain(env_paths("XDG_DATA_DIRS", "/usr/local/share:/usr/share"))
+        .collect()
+}
+
+fn config_dirs() -> Vec<PathBuf> {
+    dirs::config_dir()
+        .into_iter()
+        .chain(env_paths("XDG_CONFIG_DIRS", "/etc/xdg"))
+        .collect()
+}
+
+/// Candidate locale suffixes such as `de_DE` and `de` for localized keys.
+fn locales() -> Vec<String> {
+    let value = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|variable| std::env::var(variable).ok())
+        .find(|value| !value.is_empty())
+        .unwrap_or_default();
+
+    let locale = value
+        .split(['.', '@'])
+        .next()
+        .unwrap_or_default()
+        .to_string();
+
+    if locale.is_empty() || locale == "C" || locale == "POSIX" {
+        return Vec::new();
+    }
+
+    let mut locales = vec![locale.clone()];
+    if let Some((language, _)) = locale.split_once('_') {
+        locales.push(language.to_string());
+    }
+    locales
+}
+
+fn parse_ini(contents: &str) -> IniGroups {
+    let mut groups: IniGroups = Vec::new();
+
+    for line in contents.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(group) = line
+            .strip_prefix('[')
+            .and_then(|line| line.strip_suffix(']'))
+        {
+            groups.push((group.to_string(), Vec::new()));
+        } else if let (Some((key, value)), Some((_, entries))) =
+            (line.split_once('='), groups.last_mut())
+        {
+            entries.push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+
+    groups
+}
+
+fn unescape_desktop_string(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('s') => result.push(' '),
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('r') => result.push('\r'),
+            Some('\\') => result.push('\\'),
+            Some(other) => {
+                result.push('\\');
+                result.push(other);
+            }
+            None => result.push('\\'),
+        }
+    }
+
+    result
+}
+
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(';')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn parse_desktop_entry(path: &Path, locales: &[String]) -> Option<DesktopEntry> {
+    let contents = std::fs::read_to_string(path).ok()?;
+    let groups = parse_ini(&contents);
+    let (_, entries) = groups.iter().find(|(group, _)| group == "Desktop Entry")?;
+
+    let get = |key: &str| {
+        entries
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value.as_str())
+    };
+    let localized = |key: &str| {
+        locales
+            .iter()
+            .find_map(|locale| get(&format!("{}[{}]", key, locale)))
+            .or_else(|| get(key))
+    };
+
+    if get("Type") != Some("Application") || get("Hidden") == Some("true") {
+        return None;
+    }
+
+    Some(DesktopEntry {
+        path: path.to_path_buf(),
+        name: unescape_desktop_string(localized("Name")?),
+        exec: unescape_desktop_string(get("Exec")?),
+        icon: get("Icon").map(unescape_desktop_string),
+        mime_types: get("MimeType").map(split_list).unwrap_or_default(),
+        terminal: get("Terminal") == Some("true"),
+    })
+}
+
+fn collect_desktop_files(
+    directory: &Path,
+    prefix: &str,
+    locales: &[String],
+    entries: &mut HashMap<String, DesktopEntry>,
+) {
+    let Ok(children) = std::fs::read_dir(directory) else {
+        return;
+    };
+
+    for child in children.flatten() {
+        let path = child.path();
+        let name = child.file_name().to_string_lossy().to_string();
+
+        if path.is_dir() {
+            collect_desktop_files(&path, &format!("{}{}-", prefix, name), locales, entries);
+        } else if name.ends_with(".desktop") {
+            let id = format!("{}{}", prefix, name);
+
+            // Entries in higher-priority directories shadow later ones.
+            if entries.contains_key(&id) {
+                continue;
+            }
+
+            if let Some(entry) = parse_desktop_entry(&path, locales) {
+                entries.insert(id, entry);
+            }
+        }
+    }
+}
+
+/// Installed applications keyed by desktop file ID, e.g. `org.gnome.Nautilus.desktop`.
+fn load_desktop_entries() -> HashMap<String, DesktopEntry> {
+    let locales = locales();
+    let mut entries = HashMap::new();
+
+    for directory in data_dirs() {
+        collect_desktop_files(&directory.join("applications"), "", &locales, &mut entries);
+    }
+
+    entries
+}
+
+/// `mimeapps.list` files in precedence order, desktop-specific ones first.
+fn mimeapps_files() -> Vec<PathBuf> {
+    let desktops = std::env::var("XDG_CURRENT_DESKTOP")
+        .unwrap_or_default()
+        .split(':')
+        .filter(|desktop| !desktop.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>();
+
+    let names = desktops
+        .iter()
+        .map(|desktop| format!("{}-mimeapps.list", desktop))
+        .chain(std::iter::once("mimeapps.list".to_string()))
+        .collect::<Vec<_>>();
+
+    let directories = config_dirs().into_iter().chain(
+        data_dirs()
+            .into_iter()
+            .map(|directory| directory.join("applications")),
+    );
+
+    directories
+        .flat_map(|directory| {
+            names
+                .iter()
+                .map(move |name| directory.join(name))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+fn load_associations() -> Associations {
+    let mut associations = Associations::default();
+
+    for path in mimeapps_files() {
+        let Ok(contents) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+
+        for (group, entries) in parse_ini(&contents) {
+            for (mime_type, value) in entries {
+                let ids = split_list(&value);
+
+                match group.as_str() {
+                    "Default Applications" => associations
+                        .defaults
+                        .entry(mime_type)
+                        .or_default()
+                        .extend(ids),
+                    "Added Associations" => {
+                        associations.added.entry(mime_type).or_default().extend(ids)
+                    }
+                    "Removed Associations" => associations
+                        .removed
+                        .entry(mime_type)
+                        .or_default()
+                        .extend(ids),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    associations
+}
+
+/// The MIME type itself followed by the broader types applications commonly
+/// register for instead.
+fn mime_chain(mime_type: &str) -> Vec<String> {
+    let mut chain = vec![mime_type.to_string()];
+
+    if mime_type.starts_with("text/") && mime_type != "text/plain" {
+        chain.push("text/plain".to_string());
+    }
+
+    if mime_type != "inode/directory" && mime_type != "application/octet-stream" {
+        chain.push("application/octet-stream".to_string());
+    }
+
+    chain
+}
+
+fn resolve_icon(icon: &str) -> Option<PathBuf> {
+    let path = Path::new(icon);
+    if path.is_absolute() {
+        return path.is_file().then(|| path.to_path_buf());
+    }
+
+    for directory in data_dirs() {
+        for size in ICON_SIZES {
+            for extension in ICON_EXTENSIONS {
+                let candidate = directory
+                    .join("icons/hicolor")
+                    .join(size)
+                    .join("apps")
+                    .join(format!("{}.{}", icon, extension));
+                if candidate.is_file() {
+                    return Some(candidate);
+                }
+            }
+        }
+
+        for extension in ICON_EXTENSIONS {
+            let candidate = directory
+                .join("pixmaps")
+                .join(format!("{}.{}", icon, extension));
+            if candidate.is_file() {
+                return Some(candidate);
+            }
+        }
+    }
+
+    None
+}
+
+fn applications_for(mime_type: &str) -> Vec<Application> {
+    let entries = load_desktop_entries();
+    let associations = load_associations();
+    let chain = mime_chain(mime_type);
+
+    let mut ordered: Vec<(String, bool)> = Vec::new();
+
+    let default = chain.iter().find_map(|mime_type| {
+        associations
+            .defaults
+            .get(mime_type)?
+            .iter()
+            .find(|id| entries.contains_key(*id))
+            .cloned()
+    });
+    ordered.extend(default.map(|id| (id, true)));
+
+    for mime_type in &chain {
+        let removed = associations.removed.get(mime_type);
+        let is_removed = |id: &String| removed.is_some_and(|removed| removed.contains(id));
+
+        if let Some(added) = associations.added.get(mime_type) {
+            ordered.extend(
+                added
+                    .iter()
+                    .filter(|id| !is_removed(id))
+                    .map(|id| (id.clone(), false)),
+            );
+        }
+
+        let mut registered = entries
+            .iter()
+            .filter(|(id, entry)| entry.mime_types.contains(mime_type) && !is_removed(id))
+            .map(|(id, entry)| (entry.name.to_lowercase(), id.clone()))
+            .collect::<Vec<_>>();
+        registered.sort();
+        ordered.extend(registered.into_iter().map(|(_, id)| (id, false)));
+    }
+
+    let mut seen = HashSet::new();
+
+    ordered
+        .into_iter()
+        .filter(|(id, _)| seen.insert(id.clone()))
+        .filter_map(|(id, is_default)| {
+            let entry = entries.get(&id)?;
+
+            Some(Application {
+                name: entry.name.clone(),
+                icon: entry.icon.clone(),
+                icon_path: entry
+                    .icon
+                    .as_deref()
+                    .and_then(resolve_icon)
+                    .map(|path| path.to_string_lossy().to_string()),
+                is_default,
+                id,
+            })
+        })
+        .collect()
+}
+
+pub(crate) async fn mime_type_of(path: &Path) -> String {
+    if path.is_dir() {
+        return "inode/directory".to_string();
+    }
+
+    file_types::effective_mime_type(path)
+        .await
+        .unwrap_or_else(|| "application/octet-stream".to_string())
+}
+
+/// Lists applications able to open `path`: the default first, then
+/// user-added associations, then everything registering the MIME type.
+#[tauri::command]
+pub async fn list_applications_for(path: String) -> Result<ApplicationChoices, String> {
+    let file_path = Path::new(&path);
+
+    if !file_path.exists() {
+        return Err(format!("Item does not exist: {}", path));
+    }
+
+    let mime_type = mime_type_of(file_path).await;
+
+    let lookup = mime_type.clone();
+    let applications = task::spawn_blocking(move || applications_for(&lookup))
+        .await
+        .map_err(|e| format!("Failed to list applications: {}", e))?;
+
+    Ok(ApplicationChoices {
+        mime_type,
+        applications,
+    })
+}
+
+/// Splits an `Exec` value into arguments following the desktop entry
+/// quoting rules.
+fn tokenize_exec(exec: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut current: Option<String> = None;
+    let mut chars = exec.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            ' ' | '\t' => tokens.extend(current.take()),
+            '"' => {
+                let token = current.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(escaped @ ('"' | '`' | '$' | '\\')) => token.push(escaped),
+                            Some(other) => {
+                                token.push('\\');
+                                token.push(other);
+                            }
+                            None => return Err("Invalid Exec line".to_string()),
+                        },
+                        Some(other) => token.push(other),
+                        None => return Err("Invalid Exec line".to_string()),
+                    }
+                }
+            }
+            _ => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+
+    tokens.extend(current);
+    Ok(tokens)
+}
+
+/// Expands the field codes in an entry's `Exec` line for `paths`. `%f`/`%u`
+/// take the first file, `%F`/`%U` all of them as separate arguments, and
+/// deprecated codes are dropped. When the line has no file code at all the
+/// files are appended, as GLib does.
+fn expand_exec(entry: &DesktopEntry, paths: &[PathBuf]) -> Result<Vec<OsString>, String> {
+    let uris = paths
+        .iter()
+        .map(|path| OsString::from(path_to_file_uri(path)))
+        .collect::<Vec<_>>();
+    let files = paths.iter().map(OsString::from).collect::<Vec<_>>();
+
+    let mut arguments = Vec::new();
+    let mut used_files = false;
+
+    for token in tokenize_exec(&entry.exec)? {
+        match token.as_str() {
+            "%F" | "%U" => {
+                arguments.extend(if token == "%F" { &files } else { &uris }.iter().cloned());
+                used_files = true;
+                continue;
+            }
+            "%i" => {
+                if let Some(icon) = &entry.icon {
+                    arguments.push(OsString::from("--icon"));
+                    arguments.push(OsString::from(icon));
+                }
+                continue;
+            }
+            _ => {}
+        }
+
+        let mut argument = OsString::new();
+        let mut chars = token.chars();
+
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                argument.push(c.to_string());
+                continue;
+            }
+
+            match chars.next() {
+                Some('f' | 'F') => {
+                    argument.push(files.first().cloned().unwrap_or_default());
+                    used_files = true;
+                }
+                Some('u' | 'U') => {
+                    argument.push(uris.first().cloned().unwrap_or_default());
+                    used_files = true;
+                }
+                Some('c') => argument.push(&entry.name),
+                Some('k') => argument.push(entry.path.as_os_str()),
+                Some('%') => argument.push("%"),
+                _ => {}
+            }
+        }
+
+        if !argument.is_empty() {
+            arguments.push(argument);
+        }
+    }
+
+    if !used_files {
+        arguments.extend(files);
+    }
+
+    if arguments.is_empty() {
+        return Err("Invalid Exec line".to_string());
+    }
+
+    Ok(arguments)
+}
+
+/// Starts `arguments` detached from the app, reaping it in the background.
+pub(crate) fn spawn_detached(arguments: &[OsString], working_dir: &Path) -> Result<(), String> {
+    let mut child = Command::new(&arguments[0])
+        .args(&arguments[1..])
+        .current_dir(working_dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to launch application: {}", e))?;
+
+    std::thread::spawn(move || {
+        let _ = child.wait();
+    });
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn open_with(path: String, desktop_id: String) -> Result<(), String> {
+    let file_path = PathBuf::from(&path);
+
+    if !file_path.exists() {
+        return Err(format!("Item does not exist: {}", path));
+    }
+
+    let target = file_path.clone();
+    task::spawn_blocking(move || {
+        let entries = load_desktop_entries();
+        let entry = entries
+            .get(&desktop_id)
+            .ok_or_else(|| format!("Application not found: {}", desktop_id))?;
+
+        let mut arguments = expand_exec(entry, std::slice::from_ref(&target))?;
+        if entry.terminal {
+            arguments = terminal::in_terminal(&arguments)?;
+        }
+
+        let working_dir = target
+            .parent()
+            .filter(|parent| parent.is_dir())
+            .map(Path::to_path_buf)
+            .or_else(dirs::home_dir)
+            .unwrap_or_else(|| PathBuf::from("/"));
+
+        spawn_detached(&arguments, &working_dir)
+    })
+    .await
+    .map_err(|e| format!("Failed to open file: {}", e))??;
+
+    if file_path.is_file() {
+        recent::record_recent(&file_path).await?;
+    }
+
+    Ok(())
+}
+
+/// Sets `key=value` in `group`, adding the group or key when missing and
+/// leaving every other line untouched.
+fn set_ini_value(contents: &str, group: &str, key: &str, value: &str) -> String {
+    let mut lines = contents.lines().map(str::to_string).collect::<Vec<_>>();
+    let header = format!("[{}]", group);
+    let entry = format!("{}={}", key, value);
+
+    let Some(start) = lines.iter().position(|line| line.trim() == header) else {
+        if lines.last().is_some_and(|line| !line.trim().is_empty()) {
+            lines.push(String::new());
+        }
+        lines.push(header);
+        lines.push(entry);
+        return lines.join("\n") + "\n";
+    };
+
+    let end = lines[start + 1..]
+        .iter()
+        .position(|line| line.trim_start().starts_with('['))
+        .map(|offset| start + 1 + offset)
+        .unwrap_or(lines.len());
+
+    let existing = (start + 1..end).find(|&index| {
+        lines[index]
+            .split_once('=')
+            .is_some_and(|(name, _)| name.trim() == key)
+    });
+
+    match existing {
+        Some(index) => lines[index] = entry,
+        None => {
+            let last = (start + 1..end)
+                .rev()
+                .find(|&index| !lines[index].trim().is_empty())
+                .unwrap_or(start);
+            lines.insert(last + 1, entry);
+        }
+    }
+
+    lines.join("\n") + "\n"
+}
+
+fn ini_value(contents: &str, group: &str, key: &str) -> Option<String> {
+    parse_ini(contents)
+        .into_iter()
+        .filter(|(name, _)| name == group)
+        .flat_map(|(_, entries)| entries)
+        .find(|(name, _)| name == key)
+        .map(|(_, value)| value)
+}
+
+/// Whether `mime_type` is a plain `type/subtype` pair that can be written
+/// as a key in `mimeapps.list`.
+fn is_valid_mime_type(mime_type: &str) -> bool {
+    let is_name = |name: &str| {
+        !name.is_empty()
+            && name.chars().all(|c| {
+                c.is_ascii_alphanumeric()
+                    || matches!(c, '!' | '#' | '$' | '&' | '-' | '^' | '_' | '.' | '+')
+            })
+    };
+
+    mime_type
+        .split_once('/')
+        .is_some_and(|(kind, subtype)| is_name(kind) && is_name(subtype))
+}
+
+/// Makes `desktop_id` the default for `mime_type` in the user's
+/// `mimeapps.list`, the same file `xdg-mime default` edits.
+#[tauri::command]
+pub async fn set_default_application(mime_type: String, desktop_id: String) -> Result<(), String> {
+    if !is_valid_mime_type(&mime_type) {
+        return Err(format!("Invalid MIME type: {}", mime_type));
+    }
+
+    let installed = {
+        let desktop_id = desktop_id.clone();
+        task::spawn_blocking(move || load_desktop_entries().contains_key(&desktop_id))
+            .await
+            .map_err(|e| format!("Failed to set default application: {}", e))?
+    };
+
+    if !installed {
+        return Err(format!("Application not found: {}", desktop_id));
+    }
+
+    let path = dirs::config_dir()
+        .map(|dir| dir.join("mimeapps.list"))
+        .ok_or_else(|| "Failed to get configuration directory".to_string())?;
+    // Dotfile managers often link the file in; replace what the link points
+    // to rather than the link itself.
+    let path = tokio::fs::canonicalize(&path).await.unwrap_or(path);
+
+    let contents = match tokio::fs::read_to_string(&path).await {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("Failed to read mimeapps.list: {}", e)),
+    };
+
+    let mut added = vec![desktop_id.clone()];
+    if let Some(existing) = ini_value(&contents, "Added Associations", &mime_type) {
+        added.extend(
+            split_list(&existing)
+                .into_iter()
+                .filter(|id| id != &desktop_id),
+        );
+    }
+
+    let contents = set_ini_value(
+        &contents,
+        "Default Applications",
+        &mime_type,
+        &format!("{};", desktop_id),
+    );
+    let contents = set_ini_value(
+        &contents,
+        "Added Associations",
+        &mime_type,
+        &format!("{};", added.join(";")),
+    );
+
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create configuration directory: {}", e))?;
+    }
+
+    // Written beside the real file and renamed over it, so a crash cannot
+    // leave other applications' associations truncated.
+    let temporary = path.with_extension("list.tmp");
+
+    tokio::fs::write(&temporary, contents)
+        .await
+        .map_err(|e| format!("Failed to write mimeapps.list: {}", e))?;
+
+    tokio::fs::rename(&temporary, &path)
+        .await
+        .map_err(|e| format!("Failed to write mimeapps.list: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(exec: &str) -> DesktopEntry {
+        DesktopEntry {
+            path: PathBuf::from("/usr/share/applications/viewer.desktop"),
+            name: "Viewer".to_string(),
+            exec: exec.to_string(),
+            icon: Some("viewer".to_string()),
+            mime_types: Vec::new(),
+            terminal: false,
+        }
+    }
+
+    fn expand(exec: &str, paths: &[&str]) -> Vec<String> {
+        let paths = paths.iter().map(PathBuf::from).collect::<Vec<_>>();
+
+        expand_exec(&entry(exec), &paths)
+            .unwrap()
+            .into_iter()
+            .map(|argument| argument.to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn exec_lines_split_on_unquoted_whitespace() {
+        assert_eq!(
+            tokenize_exec("viewer  --new-window\t%U").unwrap(),
+            vec!["viewer", "--new-window", "%U"]
+        );
+    }
+
+    #[test]
+    fn quoted_arguments_keep_spaces_and_unescape() {
+        assert_eq!(
+            tokenize_exec(r#""/opt/My App/run" --title="a \"b\" \$c \\d" x"y"z"#).unwrap(),
+            vec!["/opt/My App/run", r#"--title=a "b" $c \d"#, "xyz"]
+        );
+        assert_eq!(tokenize_exec(r#"run "\n""#).unwrap(), vec!["run", r"\n"]);
+        assert_eq!(tokenize_exec(r#"run """#).unwrap(), vec!["run", ""]);
+    }
+
+    #[test]
+    fn unterminated_quotes_are_rejected() {
+        assert!(tokenize_exec(r#"run "open"#).is_err());
+        assert!(tokenize_exec(r#"run "open\"#).is_err());
+    }
+
+    #[test]
+    fn single_file_codes_take_the_first_path() {
+        assert_eq!(
+            expand("viewer %f", &["/tmp/a b.png", "/tmp/c.png"]),
+            vec!["viewer", "/tmp/a b.png"]
+        );
+        assert_eq!(
+            expand("viewer --open=%u", &["/tmp/a b.png"]),
+            vec!["viewer", "--open=file:///tmp/a%20b.png"]
+        );
+    }
+
+    #[test]
+    fn list_codes_expand_to_separate_arguments() {
+        assert_eq!(
+            expand("viewer %F", &["/tmp/a.png", "/tmp/b.png"]),
+            vec!["viewer", "/tmp/a.png", "/tmp/b.png"]
+        );
+        assert_eq!(
+            expand("viewer %U", &["/tmp/a.png", "/tmp/b.png"]),
+            vec!["viewer", "file:///tmp/a.png", "file:///tmp/b.png"]
+        );
+    }
+
+    #[test]
+    fn other_codes_expand_or_are_dropped() {
+        assert_eq!(
+            expand("viewer %i --name=%c %k %d %% %f", &["/tmp/a.png"]),
+            vec![
+                "viewer",
+                "--icon",
+                "viewer",
+                "--name=Viewer",
+                "/usr/share/applications/viewer.desktop",
+                "%",
+                "/tmp/a.png",
+            ]
+        );
+    }
+
+    #[test]
+    fn files_are_appended_without_a_file_code() {
+        assert_eq!(
+            expand("viewer", &["/tmp/a.png"]),
+            vec!["viewer", "/tmp/a.png"]
+        );
+    }
+
+    #[test]
+    fn empty_exec_lines_are_rejected() {
+        assert!(expand_exec(&entry(""), &[]).is_err());
+        assert!(expand_exec(&entry("%f"), &[]).is_err());
+    }
+
+    #[test]
+    fn ini_values_replace_existing_keys_in_place() {
+        let contents = "[Default Applications]\ntext/plain=old.desktop;\nimage/png=viewer.desktop;\n\n[Added Associations]\ntext/plain=old.desktop;\n";
+
+        assert_eq!(
+            set_ini_value(contents, "Default Applications", "text/plain", "new.desktop;"),
+            "[Default Applications]\ntext/plain=new.desktop;\nimage/png=viewer.desktop;\n\n[Added Associations]\ntext/plain=old.desktop;\n"
+        );
+    }
+
+    #[test]
+    fn ini_values_are_added_after_the_last_key_of_their_group() {
+        let contents = "# comment\n[Default Applications]\nimage/png=viewer.desktop;\n\n[Added Associations]\n";
+
+        assert_eq!(
+            set_ini_value(contents, "Default Applications", "text/plain", "editor.desktop;"),
+            "# comment\n[Default Applications]\nimage/png=viewer.desktop;\ntext/plain=editor.desktop;\n\n[Added Associations]\n"
+        );
+        assert_eq!(
+            set_ini_value(contents, "Added Associations", "text/plain", "editor.desktop;"),
+            "# comment\n[Default Applications]\nimage/png=viewer.desktop;\n\n[Added Associations]\ntext/plain=editor.desktop;\n"
+        );
+    }
+
+    #[test]
+    fn missing_ini_groups_are_appended() {
+        assert_eq!(
+            set_ini_value("", "Default Applications", "text/plain", "editor.desktop;"),
+            "[Default Applications]\ntext/plain=editor.desktop;\n"
+        );
+        assert_eq!(
+            set_ini_value(
+                "[Other]\nkey=value",
+                "Default Applications",
+                "text/plain",
+                "editor.desktop;"
+            ),
+            "[Other]\nkey=value\n\n[Default Applications]\ntext/plain=editor.desktop;\n"
+        );
+    }
+
+    #[test]
+    fn ini_keys_match_exactly() {
+        let contents = "[Default Applications]\ntext/plain-extra=other.desktop;\n";
+
+        assert_eq!(
+            set_ini_value(
+                contents,
+                "Default Applications",
+                "text/plain",
+                "editor.desktop;"
+            ),
+            "[Default Applications]\ntext/plain-extra=other.desktop;\ntext/plain=editor.desktop;\n"
+        );
+    }
+
+    #[test]
+    fn mime_types_must_be_a_type_and_subtype() {
+        assert!(is_valid_mime_type("text/plain"));
+        assert!(is_valid_mime_type(
+            "application/vnd.oasis.opendocument.text"
+        ));
+        assert!(is_valid_mime_type("x-scheme-handler/https"));
+        assert!(is_valid_mime_type("image/svg+xml"));
+
+        for invalid in [
+            "text",
+            "text/",
+            "/plain",
+            "text/plain/x",
+            "text/pl=ain",
+            "text/plain]",
+            "[text/plain",
+            "text/plain\n[x]",
+            "text/ plain",
+        ] {
+            assert!(!is_valid_mime_type(invalid), "{}", invalid);
+        }
+    }
+}
@@ -0,0 +1,1572 @@
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{self, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+use tokio::task;
+
+use crate::file_types;
+use crate::jobs::{self, JobRequest};
+use crate::FileEntry;
+
+const ARCHIVE_SEPARATOR: char = '!';
+const MAX_CACHED_INDEXES: usize = 16;
+const MAX_LINK_DEPTH: usize = 8;
+const COPY_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// Limits that keep a crafted archive (such as a zip bomb) from exhausting
+/// memory while indexing or filling the disk while extracting.
+const MAX_ARCHIVE_ENTRIES: usize = 1_000_000;
+const MAX_EXTRACTED_BYTES: u64 = 64 * 1024 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveFormat {
+    Zip,
+    Tar,
+    TarGz,
+    TarXz,
+    TarZst,
+}
+
+impl ArchiveFormat {
+    pub(crate) fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_string_lossy().to_lowercase();
+
+        if name.ends_with(".zip") {
+            Some(ArchiveFormat::Zip)
+        } else if name.ends_with(".tar") {
+            Some(ArchiveFormat::Tar)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(ArchiveFormat::TarGz)
+        } else if name.ends_with(".tar.xz") || name.ends_with(".txz") {
+            Some(ArchiveFormat::TarXz)
+        } else if name.ends_with(".tar.zst") || name.ends_with(".tzst") {
+            Some(ArchiveFormat::TarZst)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    Skip,
+    Overwrite,
+    Rename,
+    Fail,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MemberKind {
+    File,
+    Directory,
+    Symlink,
+    HardLink,
+}
+
+#[derive(Debug, Clone)]
+struct ArchiveMember {
+    components: Vec<Vec<u8>>,
+    kind: MemberKind,
+    size: u64,
+    modified: Option<i64>,
+    mode: Option<u32>,
+    link_target: Option<Vec<u8>>,
+    ordinal: Option<usize>,
+}
+
+type ArchiveIndex = BTreeMap<String, ArchiveMember>;
+
+struct CachedIndex {
+    modified: Option<SystemTime>,
+    size: u64,
+    index: Arc<ArchiveIndex>,
+}
+
+/// Splits a virtual path such as `/home/me/a.zip!/dir/file.txt` into the
+/// archive on disk and the member path inside it.
+pub(crate) fn split_archive_path(path: &str) -> Option<(PathBuf, String)> {
+    path.match_indices(ARCHIVE_SEPARATOR)
+        .find_map(|(index, _)| {
+            let (archive, rest) = (&path[..index], &path[index + 1..]);
+
+            if !rest.is_empty() && !rest.starts_with('/') {
+                return None;
+            }
+
+            let archive = PathBuf::from(archive);
+            if ArchiveFormat::from_path(&archive).is_none() || !archive.is_file() {
+                return None;
+            }
+
+            Some((archive, rest.trim_matches('/').to_string()))
+        })
+}
+
+fn normalize_components(name: &[u8]) -> Option<Vec<Vec<u8>>> {
+    let mut components = Vec::new();
+
+    for component in name.split(|&byte| byte == b'/' || byte == b'\\') {
+        match component {
+            b"" | b"." => continue,
+            b".." => return None,
+            _ if component.contains(&0) => return None,
+            _ => components.push(component.to_vec()),
+        }
+    }
+
+    (!components.is_empty()).then_some(components)
+}
+
+/// Joins member name components into the key used in virtual paths.
+/// Components that are UTF-8 without a `%` are kept as they are; in the rest
+/// `%` and invalid bytes are percent-escaped, so no two names share a key.
+fn member_key(components: &[Vec<u8>]) -> String {
+    components
+        .iter()
+        .map(|component| key_component(component))
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+pub(crate) fn key_component(component: &[u8]) -> Cow<'_, str> {
+    if let Ok(name) = std::str::from_utf8(component) {
+        if !name.contains('%') {
+            return Cow::Borrowed(name);
+        }
+    }
+
+    let mut escaped = String::with_capacity(component.len());
+
+    for chunk in component.utf8_chunks() {
+        for c in chunk.valid().chars() {
+            match c {
+                '%' => escaped.push_str("%25"),
+                _ => escaped.push(c),
+            }
+        }
+
+        for byte in chunk.invalid() {
+            let _ = write!(escaped, "%{:02X}", byte);
+        }
+    }
+
+    Cow::Owned(escaped)
+}
+
+#[cfg(unix)]
+fn component_path(component: &[u8]) -> PathBuf {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    PathBuf::from(OsStr::from_bytes(component))
+}
+
+#[cfg(not(unix))]
+fn component_path(component: &[u8]) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(component).replace(':', "_"))
+}
+
+fn zip_timestamp(date: zip::DateTime) -> Option<i64> {
+    chrono::NaiveDate::from_ymd_opt(date.year() as i32, date.month() as u32, date.day() as u32)?
+        .and_hms_opt(
+            date.hour() as u32,
+            date.minute() as u32,
+            date.second() as u32,
+        )
+        .map(|date| date.and_utc().timestamp())
+}
+
+fn open_zip(archive: &Path) -> Result<zip::ZipArchive<BufReader<File>>, String> {
+    let file = File::open(archive).map_err(|e| format!("Failed to open archive: {}", e))?;
+    zip::ZipArchive::new(BufReader::new(file)).map_err(|e| format!("Failed to read archive: {}", e))
+}
+
+fn open_tar(archive: &Path, kind: ArchiveFormat) -> Result<tar::Archive<Box<dyn Read>>, String> {
+    let file =
+        BufReader::new(File::open(archive).map_err(|e| format!("Failed to open archive: {}", e))?);
+
+    let reader: Box<dyn Read> = match kind {
+        ArchiveFormat::Tar => Box::new(file),
+        ArchiveFormat::TarGz => Box::new(flate2::bufread::MultiGzDecoder::new(file)),
+        ArchiveFormat::TarXz => Box::new(xz2::bufread::XzDecoder::new_multi_decoder(file)),
+        ArchiveFormat::TarZst => Box::new(
+            zstd::stream::read::Decoder::with_buffer(file)
+                .map_err(|e| format!("Failed to read archive: {}", e))?,
+        ),
+        ArchiveFormat::Zip => return Err("Not a tar archive".to_string()),
+    };
+
+    Ok(tar::Archive::new(reader))
+}
+
+fn tar_member_kind(entry_type: tar::EntryType) -> Option<MemberKind> {
+    match entry_type {
+        tar::EntryType::Regular | tar::EntryType::Continuous | tar::EntryType::GNUSparse => {
+            Some(MemberKind::File)
+        }
+        tar::EntryType::Directory => Some(MemberKind::Directory),
+        tar::EntryType::Symlink => Some(MemberKind::Symlink),
+        tar::EntryType::Link => Some(MemberKind::HardLink),
+        _ => None,
+    }
+}
+
+fn too_many_entries() -> String {
+    format!("Archive has more than {} entries", MAX_ARCHIVE_ENTRIES)
+}
+
+fn index_zip(archive: &Path) -> Result<Vec<ArchiveMember>, String> {
+    let mut zip = open_zip(archive)?;
+    let mut members = Vec::new();
+
+    if zip.len() > MAX_ARCHIVE_ENTRIES {
+        return Err(too_many_entries());
+    }
+
+    for ordinal in 0..zip.len() {
+        let entry = zip
+            .by_index_raw(ordinal)
+            .map_err(|e| format!("Failed to read archive: {}", e))?;
+
+        let Some(components) = normalize_components(entry.name().as_bytes()) else {
+            continue;
+        };
+
+        let kind = if entry.is_dir() {
+            MemberKind::Directory
+        } else if entry.is_symlink() {
+            MemberKind::Symlink
+        } else {
+            MemberKind::File
+        };
+
+        members.push(ArchiveMember {
+            components,
+            kind,
+            size: entry.size(),
+            modified: entry.last_modified().and_then(zip_timestamp),
+            mode: entry.unix_mode().map(|mode| mode & 0o7777),
+            link_target: None,
+            ordinal: Some(ordinal),
+        });
+    }
+
+    Ok(members)
+}
+
+fn index_tar(archive: &Path, kind: ArchiveFormat) -> Result<Vec<ArchiveMember>, String> {
+    let mut tar = open_tar(archive, kind)?;
+    let mut members = Vec::new();
+
+    let entries = tar
+        .entries()
+        .map_err(|e| format!("Failed to read archive: {}", e))?;
+
+    for (ordinal, entry) in entries.enumerate() {
+        if ordinal >= MAX_ARCHIVE_ENTRIES {
+            return Err(too_many_entries());
+        }
+
+        let entry = entry.map_err(|e| format!("Failed to read archive: {}", e))?;
+        let header = entry.header();
+
+        let Some(kind) = tar_member_kind(header.entry_type()) else {
+            continue;
+        };
+
+        let Some(components) = normalize_components(&entry.path_bytes()) else {
+            continue;
+        };
+
+        members.push(ArchiveMember {
+            components,
+            kind,
+            size: if kind == MemberKind::File {
+                entry.size()
+            } else {
+                0
+            },
+            modified: header.mtime().ok().map(|mtime| mtime as i64),
+            mode: header.mode().ok().map(|mode| mode & 0o7777),
+            link_target: entry.link_name_bytes().map(|target| target.to_vec()),
+            ordinal: Some(ordinal),
+        });
+    }
+
+    Ok(members)
+}
+
+fn build_index(archive: &Path, kind: ArchiveFormat) -> Result<ArchiveIndex, String> {
+    let members = match kind {
+        ArchiveFormat::Zip => index_zip(archive)?,
+        _ => index_tar(archive, kind)?,
+    };
+
+    let mut index = ArchiveIndex::new();
+
+    for member in members {
+        for depth in 1..member.components.len() {
+            let parent = &member.components[..depth];
+            index
+                .entry(member_key(parent))
+                .or_insert_with(|| ArchiveMember {
+                    components: parent.to_vec(),
+                    kind: MemberKind::Directory,
+                    size: 0,
+                    modified: None,
+                    mode: None,
+                    link_target: None,
+                    ordinal: None,
+                });
+        }
+
+        index.insert(member_key(&member.components), member);
+    }
+
+    Ok(index)
+}
+
+fn load_index(archive: &Path) -> Result<(ArchiveFormat, Arc<ArchiveIndex>), String> {
+    static INDEXES: OnceLock<Mutex<HashMap<PathBuf, CachedIndex>>> = OnceLock::new();
+
+    let kind = ArchiveFormat::from_path(archive)
+        .ok_or_else(|| format!("Unsupported archive: {}", archive.display()))?;
+
+    let metadata = std::fs::metadata(archive)
+        .map_err(|_| format!("Archive does not exist: {}", archive.display()))?;
+    let modified = metadata.modified().ok();
+
+    let indexes = INDEXES.get_or_init(|| Mutex::new(HashMap::new()));
+
+    if let Some(cached) = indexes.lock().unwrap().get(archive) {
+        if cached.modified == modified && cached.size == metadata.len() {
+            return Ok((kind, cached.index.clone()));
+        }
+    }
+
+    let index = Arc::new(build_index(archive, kind)?);
+
+    let mut indexes = indexes.lock().unwrap();
+    if indexes.len() >= MAX_CACHED_INDEXES {
+        indexes.clear();
+    }
+    indexes.insert(
+        archive.to_path_buf(),
+        CachedIndex {
+            modified,
+            size: metadata.len(),
+            index: index.clone(),
+        },
+    );
+
+    Ok((kind, index))
+}
+
+fn parent_key(key: &str) -> &str {
+    key.rsplit_once('/').map(|(parent, _)| parent).unwrap_or("")
+}
+
+fn is_within(key: &str, inner: &str) -> bool {
+    inner.is_empty()
+        || key == inner
+        || (key.starts_with(inner) && key.as_bytes().get(inner.len()) == Some(&b'/'))
+}
+
+fn is_directory_root(index: &ArchiveIndex, inner: &str) -> bool {
+    inner.is_empty() || index.get(inner).map(|member| member.kind) == Some(MemberKind::Directory)
+}
+
+/// Only directories carry their descendants along; a non-directory member
+/// (such as a symlink that other entries are nested under) stands alone.
+fn in_selection(key: &str, inner: &str, root_is_directory: bool) -> bool {
+    if root_is_directory {
+        is_within(key, inner)
+    } else {
+        key == inner
+    }
+}
+
+fn link_outside_archive(key: &str) -> String {
+    format!("Link points outside the archive: {}", key)
+}
+
+fn resolve_link(index: &ArchiveIndex, key: &str) -> Result<String, String> {
+    let mut key = key.to_string();
+
+    for _ in 0..MAX_LINK_DEPTH {
+        let member = index
+            .get(&key)
+            .ok_or_else(|| format!("Item does not exist in archive: {}", key))?;
+
+        let (mut components, target) = match (member.kind, &member.link_target) {
+            (MemberKind::Symlink, Some(target)) if target.first() == Some(&b'/') => {
+                return Err(link_outside_archive(&key));
+            }
+            (MemberKind::Symlink, Some(target)) => (
+                member.components[..member.components.len() - 1].to_vec(),
+                target,
+            ),
+            (MemberKind::HardLink, Some(target)) => (Vec::new(), target),
+            _ => return Ok(key),
+        };
+
+        for component in target.split(|&byte| byte == b'/') {
+            match component {
+                b"" | b"." => {}
+                b".." => {
+                    if components.pop().is_none() {
+                        return Err(link_outside_archive(&key));
+                    }
+                }
+                _ => components.push(component.to_vec()),
+            }
+        }
+
+        key = member_key(&components);
+    }
+
+    Err("Too many levels of symbolic links in archive".to_string())
+}
+
+fn archive_entry(
+    archive: &Path,
+    key: &str,
+    member: &ArchiveMember,
+    item_count: Option<u32>,
+) -> FileEntry {
+    let name = member
+        .components
+        .last()
+        .map(|component| String::from_utf8_lossy(component).to_string())
+        .unwrap_or_default();
+    let is_directory = member.kind == MemberKind::Directory;
+
+    let (mime_type, category) = if is_directory {
+        (None, None)
+    } else {
+        let mime_type = file_types::mime_from_extension(Path::new(&name));
+        let category =
+            file_types::category_for(mime_type.as_deref(), Path::new(&name), member.mode);
+        (mime_type, Some(category))
+    };
+
+    FileEntry {
+        name,
+        path: format!("{}{}/{}", archive.display(), ARCHIVE_SEPARATOR, key),
+        is_directory,
+        size: (!is_directory).then_some(member.size),
+        modified: member.modified.map(|modified| modified.to_string()),
+        item_count,
+        mode: member.mode,
+        owner: None,
+        group: None,
+        mime_type,
+        category,
+    }
+}
+
+fn list_members_blocking(archive: &Path, inner: &str) -> Result<Vec<FileEntry>, String> {
+    let (_, index) = load_index(archive)?;
+
+    if !inner.is_empty() {
+        match index.get(inner) {
+            Some(member) if member.kind == MemberKind::Directory => {}
+            Some(_) => return Err("Path is not a directory".to_string()),
+            None => return Err("Directory does not exist".to_string()),
+        }
+    }
+
+    let mut child_counts = HashMap::<&str, u32>::new();
+    for key in index.keys() {
+        *child_counts.entry(parent_key(key)).or_default() += 1;
+    }
+
+    Ok(index
+        .iter()
+        .filter(|(key, _)| parent_key(key) == inner)
+        .map(|(key, member)| {
+            let item_count = (member.kind == MemberKind::Directory)
+                .then(|| child_counts.get(key.as_str()).copied().unwrap_or(0));
+            archive_entry(archive, key, member, item_count)
+        })
+        .collect())
+}
+
+pub(crate) async fn list_members(
+    archive: PathBuf,
+    inner: String,
+) -> Result<Vec<FileEntry>, String> {
+    task::spawn_blocking(move || list_members_blocking(&archive, &inner))
+        .await
+        .map_err(|e| format!("Failed to read archive: {}", e))?
+}
+
+fn read_range(reader: &mut dyn Read, offset: u64, limit: u64) -> io::Result<Vec<u8>> {
+    io::copy(&mut reader.take(offset), &mut io::sink())?;
+
+    let mut bytes = Vec::new();
+    reader.take(limit).read_to_end(&mut bytes)?;
+
+    Ok(bytes)
+}
+
+fn read_member_blocking(
+    archive: &Path,
+    inner: &str,
+    offset: u64,
+    limit: u64,
+) -> Result<(Vec<u8>, u64), String> {
+    let (kind, index) = load_index(archive)?;
+    let key = resolve_link(&index, inner)?;
+    let member = &index[&key];
+
+    if member.kind == MemberKind::Directory {
+        return Err("Path is a directory".to_string());
+    }
+
+    let ordinal = member
+        .ordinal
+        .ok_or_else(|| format!("Item does not exist in archive: {}", inner))?;
+
+    let bytes = match kind {
+        ArchiveFormat::Zip => {
+            let mut zip = open_zip(archive)?;
+            let mut entry = zip
+                .by_index(ordinal)
+                .map_err(|e| format!("Failed to read archive: {}", e))?;
+            read_range(&mut entry, offset, limit)
+        }
+        _ => {
+            let mut tar = open_tar(archive, kind)?;
+            let mut entry = tar
+                .entries()
+                .map_err(|e| format!("Failed to read archive: {}", e))?
+                .nth(ordinal)
+                .ok_or_else(|| format!("Item does not exist in archive: {}", inner))?
+                .map_err(|e| format!("Failed to read archive: {}", e))?;
+            read_range(&mut entry, offset, limit)
+        }
+    }
+    .map_err(|e| format!("Failed to read file: {}", e))?;
+
+    Ok((bytes, member.size))
+}
+
+/// Reads up to `limit` bytes of an archive member starting at `offset` and
+/// returns them along with the member's uncompressed size.
+pub(crate) async fn read_member(
+    archive: PathBuf,
+    inner: String,
+    offset: u64,
+    limit: u64,
+) -> Result<(Vec<u8>, u64), String> {
+    task::spawn_blocking(move || read_member_blocking(&archive, &inner, offset, limit))
+        .await
+        .map_err(|e| format!("Failed to read archive: {}", e))?
+}
+
+/// Returns the uncompressed size and item count of a member and everything
+/// below it.
+pub(crate) fn member_totals(archive: &Path, inner: &str) -> Result<(u64, u64), String> {
+    let (_, index) = load_index(archive)?;
+
+    if !inner.is_empty() && !index.contains_key(inner) {
+        return Err(format!("Item does not exist in archive: {}", inner));
+    }
+
+    let root_is_directory = is_directory_root(&index, inner);
+
+    Ok(index
+        .iter()
+        .filter(|(key, _)| in_selection(key, inner, root_is_directory))
+        .fold((0, 0), |(bytes, items), (_, member)| {
+            (bytes + member.size, items + 1)
+        }))
+}
+
+/// Joins `relative` onto `destination`, creating intermediate directories and
+/// refusing to descend through anything that isn't a real directory so a
+/// crafted archive cannot write outside the destination via symlinks.
+fn prepare_output(destination: &Path, relative: &[Vec<u8>]) -> Result<PathBuf, String> {
+    let mut current = destination.to_path_buf();
+
+    for (position, component) in relative.iter().enumerate() {
+        current.push(component_path(component));
+
+        if position + 1 == relative.len() {
+            break;
+        }
+
+        match std::fs::symlink_metadata(&current) {
+            Ok(metadata) if metadata.is_dir() => {}
+            Ok(_) => {
+                return Err(format!(
+                    "Refusing to extract through non-directory: {}",
+                    current.display()
+                ))
+            }
+            Err(_) => std::fs::create_dir(&current)
+                .map_err(|e| format!("Failed to create directory: {}", e))?,
+        }
+    }
+
+    Ok(current)
+}
+
+/// Resolves a previously extracted regular file without following symlinks.
+fn contained_file(destination: &Path, relative: &[Vec<u8>]) -> Option<PathBuf> {
+    let mut current = destination.to_path_buf();
+
+    for (position, component) in relative.iter().enumerate() {
+        current.push(component_path(component));
+
+        let metadata = std::fs::symlink_metadata(&current).ok()?;
+        let expected = if position + 1 == relative.len() {
+            metadata.is_file()
+        } else {
+            metadata.is_dir()
+        };
+
+        if !expected {
+            return None;
+        }
+    }
+
+    Some(current)
+}
+
+fn remove_existing(path: &Path) -> Result<(), String> {
+    match std::fs::symlink_metadata(path) {
+        Ok(metadata) if !metadata.is_dir() => {
+            std::fs::remove_file(path).map_err(|e| format!("Failed to replace file: {}", e))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(unix)]
+fn apply_mode(path: &Path, mode: Option<u32>) {
+    use std::os::unix::fs::PermissionsExt;
+
+    if let Some(mode) = mode {
+        let _ = std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode & 0o777));
+    }
+}
+
+#[cfg(not(unix))]
+fn apply_mode(_path: &Path, _mode: Option<u32>) {}
+
+#[cfg(unix)]
+fn create_symlink(target: &[u8], link: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(component_path(target), link)
+}
+
+#[cfg(not(unix))]
+fn create_symlink(_target: &[u8], _link: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Symbolic links are not supported on this platform",
+    ))
+}
+
+/// Copies a member out, refusing to write more than its recorded `size`: zip
+/// readers stop at the end of the compressed stream, not at the size the
+/// archive claims.
+fn write_member<E: From<String>>(
+    reader: &mut dyn Read,
+    output: &Path,
+    size: u64,
+    modified: Option<i64>,
+    progress: &mut impl FnMut(&Path, u64, u64) -> Result<(), E>,
+) -> Result<(), E> {
+    remove_existing(output)?;
+
+    let mut file = File::create(output).map_err(|e| format!("Failed to create file: {}", e))?;
+    let mut buffer = vec![0u8; COPY_BUFFER_SIZE];
+    let mut written = 0u64;
+
+    loop {
+        let read = reader
+            .read(&mut buffer)
+            .map_err(|e| format!("Failed to read archive: {}", e))?;
+
+        if read == 0 {
+            break;
+        }
+
+        written += read as u64;
+        if written > size {
+            return Err(format!(
+                "Archive member is larger than its recorded size: {}",
+                output.display()
+            )
+            .into());
+        }
+
+        file.write_all(&buffer[..read])
+            .map_err(|e| format!("Failed to write file: {}", e))?;
+        progress(output, read as u64, 0)?;
+    }
+
+    if let Some(modified) = modified.filter(|modified| *modified >= 0) {
+        let _ = file.set_modified(UNIX_EPOCH + Duration::from_secs(modified as u64));
+    }
+
+    Ok(())
+}
+
+/// Picks a free sibling name such as `report (2).pdf` for `path`.
+fn unique_path(path: &Path) -> PathBuf {
+    let parent = path.parent().unwrap_or(Path::new(""));
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|extension| format!(".{}", extension.to_string_lossy()))
+        .unwrap_or_default();
+
+    (1..)
+        .map(|n| parent.join(format!("{} ({}){}", stem, n, extension)))
+        .find(|candidate| candidate.symlink_metadata().is_err())
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+fn resolve_conflict(output: PathBuf, policy: ConflictPolicy) -> Result<Option<PathBuf>, String> {
+    if output.symlink_metadata().is_err() {
+        return Ok(Some(output));
+    }
+
+    match policy {
+        ConflictPolicy::Overwrite => Ok(Some(output)),
+        ConflictPolicy::Skip => Ok(None),
+        ConflictPolicy::Rename => Ok(Some(unique_path(&output))),
+        ConflictPolicy::Fail => Err(format!("Item already exists: {}", output.display())),
+    }
+}
+
+struct Selection<'a> {
+    inner: &'a str,
+    depth: usize,
+    root_is_directory: bool,
+    destination: &'a Path,
+    on_conflict: ConflictPolicy,
+    directories: Vec<(PathBuf, Option<u32>)>,
+}
+
+impl Selection<'_> {
+    fn relative<'m>(&self, components: &'m [Vec<u8>]) -> Option<&'m [Vec<u8>]> {
+        let key = member_key(components);
+        in_selection(&key, self.inner, self.root_is_directory).then(|| &components[self.depth..])
+    }
+
+    fn output(&self, relative: &[Vec<u8>]) -> Result<PathBuf, String> {
+        if relative.is_empty() {
+            Ok(self.destination.to_path_buf())
+        } else {
+            prepare_output(self.destination, relative)
+        }
+    }
+
+    /// Like `output`, but applies the conflict policy; `None` means skip.
+    fn file_output(&self, relative: &[Vec<u8>]) -> Result<Option<PathBuf>, String> {
+        resolve_conflict(self.output(relative)?, self.on_conflict)
+    }
+}
+
+/// Extracts the member at `inner` (or the whole archive when empty) to
+/// `destination`, reporting `(path, bytes, items)` deltas through `progress`.
+pub(crate) fn extract_members<E: From<String>>(
+    archive: &Path,
+    inner: &str,
+    destination: &Path,
+    on_conflict: ConflictPolicy,
+    mut progress: impl FnMut(&Path, u64, u64) -> Result<(), E>,
+) -> Result<(), E> {
+    let (format, index) = load_index(archive)?;
+
+    let root_is_directory = is_directory_root(&index, inner);
+
+    let expanded = index
+        .iter()
+        .filter(|(key, _)| in_selection(key, inner, root_is_directory))
+        .try_fold(0u64, |total, (_, member)| total.checked_add(member.size));
+
+    if expanded.is_none_or(|total| total > MAX_EXTRACTED_BYTES) {
+        return Err(format!(
+            "Archive expands to more than {} GiB",
+            MAX_EXTRACTED_BYTES >> 30
+        )
+        .into());
+    }
+
+    let mut selection = Selection {
+        inner,
+        depth: if inner.is_empty() {
+            0
+        } else {
+            inner.split('/').count()
+        },
+        root_is_directory,
+        destination,
+        on_conflict,
+        directories: Vec::new(),
+    };
+
+    if root_is_directory {
+        std::fs::create_dir_all(destination)
+            .map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+
+    for (key, member) in index.iter() {
+        let Some(relative) = selection.relative(&member.components) else {
+            continue;
+        };
+
+        if member.kind == MemberKind::Directory {
+            let output = selection.output(relative)?;
+            if !std::fs::symlink_metadata(&output).is_ok_and(|metadata| metadata.is_dir()) {
+                std::fs::create_dir(&output)
+                    .map_err(|e| format!("Failed to create directory: {}", e))?;
+            }
+            selection.directories.push((output, member.mode));
+            progress(Path::new(key), 0, 1)?;
+        }
+    }
+
+    match format {
+        ArchiveFormat::Zip => {
+            let mut zip = open_zip(archive)?;
+
+            for member in index.values() {
+                let (Some(ordinal), Some(relative)) =
+                    (member.ordinal, selection.relative(&member.components))
+                else {
+                    continue;
+                };
+
+                if member.kind == MemberKind::Directory {
+                    continue;
+                }
+
+                let Some(output) = selection.file_output(relative)? else {
+                    progress(destination, member.size, 1)?;
+                    continue;
+                };
+
+                let mut entry = zip
+                    .by_index(ordinal)
+                    .map_err(|e| format!("Failed to read archive: {}", e))?;
+
+                if member.kind == MemberKind::Symlink {
+                    let mut target = Vec::new();
+                    entry
+                        .read_to_end(&mut target)
+                        .map_err(|e| format!("Failed to read archive: {}", e))?;
+                    remove_existing(&output)?;
+                    create_symlink(&target, &output)
+                        .map_err(|e| format!("Failed to create symbolic link: {}", e))?;
+                } else {
+                    write_member(
+                        &mut entry,
+                        &output,
+                        member.size,
+                        member.modified,
+                        &mut progress,
+                    )?;
+                    apply_mode(&output, member.mode);
+                }
+
+                progress(&output, 0, 1)?;
+            }
+        }
+        _ => {
+            let mut tar = open_tar(archive, format)?;
+            let entries = tar
+                .entries()
+                .map_err(|e| format!("Failed to read archive: {}", e))?;
+
+            for entry in entries {
+                let mut entry = entry.map_err(|e| format!("Failed to read archive: {}", e))?;
+
+                let Some(member_kind) = tar_member_kind(entry.header().entry_type()) else {
+                    continue;
+                };
+
+                if member_kind == MemberKind::Directory {
+                    continue;
+                }
+
+                let Some(components) = normalize_components(&entry.path_bytes()) else {
+                    continue;
+                };
+
+                let Some(relative) = selection.relative(&components) else {
+                    continue;
+                };
+
+                let Some(output) = selection.file_output(relative)? else {
+                    let size = if member_kind == MemberKind::File {
+                        entry.size()
+                    } else {
+                        0
+                    };
+                    progress(destination, size, 1)?;
+                    continue;
+                };
+
+                let mode = entry.header().mode().ok();
+                let modified = entry.header().mtime().ok().map(|mtime| mtime as i64);
+                let link_target = entry.link_name_bytes().map(|target| target.to_vec());
+
+                match (member_kind, link_target) {
+                    (MemberKind::Symlink, Some(target)) => {
+                        remove_existing(&output)?;
+                        create_symlink(&target, &output)
+                            .map_err(|e| format!("Failed to create symbolic link: {}", e))?;
+                    }
+                    (MemberKind::HardLink, Some(target)) => {
+                        let source = normalize_components(&target).and_then(|target| {
+                            selection
+                                .relative(&target)
+                                .and_then(|relative| contained_file(destination, relative))
+                        });
+
+                        if let Some(source) = source {
+                            remove_existing(&output)?;
+                            std::fs::hard_link(&source, &output)
+                                .or_else(|_| std::fs::copy(&source, &output).map(|_| ()))
+                                .map_err(|e| format!("Failed to create hard link: {}", e))?;
+                        }
+                    }
+                    _ => {
+                        let size = entry.size();
+                        write_member(&mut entry, &output, size, modified, &mut progress)?;
+                        apply_mode(&output, mode);
+                    }
+                }
+
+                progress(&output, 0, 1)?;
+            }
+        }
+    }
+
+    for (directory, mode) in selection.directories.iter().rev() {
+        apply_mode(directory, *mode);
+    }
+
+    Ok(())
+}
+
+enum ArchiveWriter {
+    Plain(io::BufWriter<File>),
+    Gz(flate2::write::GzEncoder<io::BufWriter<File>>),
+    Xz(xz2::write::XzEncoder<io::BufWriter<File>>),
+    Zst(zstd::Encoder<'static, io::BufWriter<File>>),
+}
+
+impl ArchiveWriter {
+    fn new(file: File, format: ArchiveFormat, level: Option<u32>) -> io::Result<Self> {
+        let file = io::BufWriter::new(file);
+
+        Ok(match format {
+            ArchiveFormat::TarGz => ArchiveWriter::Gz(flate2::write::GzEncoder::new(
+                file,
+                flate2::Compression::new(level.unwrap_or(6).min(9)),
+            )),
+            ArchiveFormat::TarXz => {
+                ArchiveWriter::Xz(xz2::write::XzEncoder::new(file, level.unwrap_or(6).min(9)))
+            }
+            ArchiveFormat::TarZst => ArchiveWriter::Zst(zstd::Encoder::new(
+                file,
+                level.unwrap_or(3).clamp(1, 19) as i32,
+            )?),
+            ArchiveFormat::Zip | ArchiveFormat::Tar => ArchiveWriter::Plain(file),
+        })
+    }
+
+    fn finish(self) -> io::Result<()> {
+        let file = match self {
+            ArchiveWriter::Plain(file) => file,
+            ArchiveWriter::Gz(encoder) => encoder.finish()?,
+            ArchiveWriter::Xz(encoder) => encoder.finish()?,
+            ArchiveWriter::Zst(encoder) => encoder.finish()?,
+        };
+
+        file.into_inner().map_err(|e| e.into_error())?.sync_all()
+    }
+}
+
+impl Write for ArchiveWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            ArchiveWriter::Plain(file) => file.write(buf),
+            ArchiveWriter::Gz(encoder) => encoder.write(buf),
+            ArchiveWriter::Xz(encoder) => encoder.write(buf),
+            ArchiveWriter::Zst(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            ArchiveWriter::Plain(file) => file.flush(),
+            ArchiveWriter::Gz(encoder) => encoder.flush(),
+            ArchiveWriter::Xz(encoder) => encoder.flush(),
+            ArchiveWriter::Zst(encoder) => encoder.flush(),
+        }
+    }
+}
+
+/// Forwards reads while reporting each chunk; a progress error (such as a
+/// cancellation) is stashed so it can be surfaced instead of the I/O error.
+struct ProgressReader<'a, R, F, E> {
+    inner: R,
+    path: &'a Path,
+    progress: &'a mut F,
+    error: Option<E>,
+}
+
+impl<R: Read, F: FnMut(&Path, u64, u64) -> Result<(), E>, E> Read for ProgressReader<'_, R, F, E> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+
+        if read > 0 {
+            if let Err(e) = (self.progress)(self.path, read as u64, 0) {
+                self.error = Some(e);
+                return Err(io::Error::other("Stopped"));
+            }
+        }
+
+        Ok(read)
+    }
+}
+
+struct SourceItem {
+    path: PathBuf,
+    name: PathBuf,
+    metadata: std::fs::Metadata,
+}
+
+/// Walks `path` without following symlinks, naming each item relative to the
+/// parent of the selected path.
+fn collect_source(path: &Path, name: PathBuf, items: &mut Vec<SourceItem>) -> Result<(), String> {
+    let metadata =
+        std::fs::symlink_metadata(path).map_err(|e| format!("Failed to read metadata: {}", e))?;
+    let is_dir = metadata.is_dir();
+
+    items.push(SourceItem {
+        path: path.to_path_buf(),
+        name: name.clone(),
+        metadata,
+    });
+
+    if is_dir {
+        let mut children = std::fs::read_dir(path)
+            .map_err(|e| format!("Failed to read directory: {}", e))?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name())
+            .collect::<Vec<_>>();
+        children.sort();
+
+        for child in children {
+            collect_source(&path.join(&child), name.join(&child), items)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Zip names are stored as UTF-8, so other names are refused rather than
+/// altered.
+fn zip_name(name: &Path) -> Result<String, String> {
+    name.components()
+        .map(|component| {
+            component.as_os_str().to_str().ok_or_else(|| {
+                format!(
+                    "Cannot store a name that is not UTF-8 in a zip archive: {}",
+                    name.display()
+                )
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(|components| components.join("/"))
+}
+
+fn zip_modified(metadata: &std::fs::Metadata) -> Option<zip::DateTime> {
+    use chrono::{Datelike, Local, Timelike};
+
+    let modified: chrono::DateTime<Local> = metadata.modified().ok()?.into();
+
+    zip::DateTime::from_date_and_time(
+        u16::try_from(modified.year()).ok()?,
+        modified.month() as u8,
+        modified.day() as u8,
+        modified.hour() as u8,
+        modified.minute() as u8,
+        modified.second() as u8,
+    )
+    .ok()
+}
+
+#[cfg(unix)]
+fn file_mode(metadata: &std::fs::Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+
+    metadata.permissions().mode() & 0o7777
+}
+
+#[cfg(not(unix))]
+fn file_mode(metadata: &std::fs::Metadata) -> u32 {
+    if metadata.is_dir() {
+        0o755
+    } else {
+        0o644
+    }
+}
+
+fn write_zip<E: From<String>>(
+    file: File,
+    items: &[SourceItem],
+    level: Option<u32>,
+    progress: &mut impl FnMut(&Path, u64, u64) -> Result<(), E>,
+) -> Result<(), E> {
+    let mut zip = zip::ZipWriter::new(io::BufWriter::new(file));
+
+    let base_options = match level.map(|level| level.min(9)) {
+        Some(0) => zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored),
+        level => zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated)
+            .compression_level(level.map(i64::from)),
+    };
+
+    for item in items {
+        let name = zip_name(&item.name)?;
+        let mut options = base_options
+            .unix_permissions(file_mode(&item.metadata))
+            .large_file(item.metadata.len() >= u32::MAX as u64);
+
+        if let Some(modified) = zip_modified(&item.metadata) {
+            options = options.last_modified_time(modified);
+        }
+
+        if item.metadata.is_symlink() {
+            let target = std::fs::read_link(&item.path)
+                .map_err(|e| format!("Failed to read link: {}", e))?;
+            let target = target.to_str().ok_or_else(|| {
+                format!(
+                    "Cannot store a link target that is not UTF-8 in a zip archive: {}",
+                    target.display()
+                )
+            })?;
+            zip.add_symlink(name, target, options)
+                .map_err(|e| format!("Failed to write archive: {}", e))?;
+        } else if item.metadata.is_dir() {
+            zip.add_directory(name, options)
+                .map_err(|e| format!("Failed to write archive: {}", e))?;
+        } else {
+            zip.start_file(name, options)
+                .map_err(|e| format!("Failed to write archive: {}", e))?;
+
+            let file = File::open(&item.path).map_err(|e| format!("Failed to open file: {}", e))?;
+            let mut reader = ProgressReader {
+                inner: file,
+                path: &item.path,
+                progress: &mut *progress,
+                error: None,
+            };
+
+            if let Err(e) = io::copy(&mut reader, &mut zip) {
+                return Err(reader
+                    .error
+                    .unwrap_or_else(|| format!("Failed to write archive: {}", e).into()));
+            }
+        }
+
+        progress(&item.path, 0, 1)?;
+    }
+
+    zip.finish()
+        .map_err(|e| format!("Failed to write archive: {}", e))?
+        .into_inner()
+        .map_err(|e| format!("Failed to write archive: {}", e.into_error()))?
+        .sync_all()
+        .map_err(|e| format!("Failed to write archive: {}", e))?;
+
+    Ok(())
+}
+
+fn write_tar<E: From<String>>(
+    file: File,
+    format: ArchiveFormat,
+    items: &[SourceItem],
+    level: Option<u32>,
+    progress: &mut impl FnMut(&Path, u64, u64) -> Result<(), E>,
+) -> Result<(), E> {
+    let writer = ArchiveWriter::new(file, format, level)
+        .map_err(|e| format!("Failed to create archive: {}", e))?;
+    let mut tar = tar::Builder::new(writer);
+
+    for item in items {
+        let mut header = tar::Header::new_gnu();
+        header.set_metadata_in_mode(&item.metadata, tar::HeaderMode::Complete);
+
+        if item.metadata.is_symlink() {
+            let target = std::fs::read_link(&item.path)
+                .map_err(|e| format!("Failed to read link: {}", e))?;
+            tar.append_link(&mut header, &item.name, target)
+                .map_err(|e| format!("Failed to write archive: {}", e))?;
+        } else if item.metadata.is_dir() {
+            tar.append_data(&mut header, &item.name, io::empty())
+                .map_err(|e| format!("Failed to write archive: {}", e))?;
+        } else {
+            let file = File::open(&item.path).map_err(|e| format!("Failed to open file: {}", e))?;
+            let mut reader = ProgressReader {
+                inner: file.take(item.metadata.len()),
+                path: &item.path,
+                progress: &mut *progress,
+                error: None,
+            };
+
+            if let Err(e) = tar.append_data(&mut header, &item.name, &mut reader) {
+                return Err(reader
+                    .error
+                    .unwrap_or_else(|| format!("Failed to write archive: {}", e).into()));
+            }
+        }
+
+        progress(&item.path, 0, 1)?;
+    }
+
+    tar.into_inner()
+        .and_then(ArchiveWriter::finish)
+        .map_err(|e| format!("Failed to write archive: {}", e))?;
+
+    Ok(())
+}
+
+/// Packs `paths` into a new archive at `destination`. The archive is written
+/// to a temporary sibling first so a failed or cancelled run leaves nothing
+/// half-written behind.
+pub(crate) fn create_members<E: From<String>>(
+    paths: &[String],
+    destination: &Path,
+    format: ArchiveFormat,
+    level: Option<u32>,
+    mut progress: impl FnMut(&Path, u64, u64) -> Result<(), E>,
+) -> Result<(), E> {
+    let mut items = Vec::new();
+
+    for path in paths {
+        let path = Path::new(path);
+        let name = path
+            .file_name()
+            .ok_or_else(|| format!("Invalid source path: {}", path.display()))?;
+        collect_source(path, PathBuf::from(name), &mut items)?;
+    }
+
+    let parent = destination
+        .parent()
+        .ok_or_else(|| "Cannot determine parent directory".to_string())?;
+    let temporary = parent.join(format!(".wiregui-archive-{}", uuid::Uuid::new_v4()));
+
+    let file = File::create(&temporary).map_err(|e| format!("Failed to create archive: {}", e))?;
+
+    let written = match format {
+        ArchiveFormat::Zip => write_zip(file, &items, level, &mut progress),
+        _ => write_tar(file, format, &items, level, &mut progress),
+    }
+    .and_then(|()| {
+        std::fs::rename(&temporary, destination)
+            .map_err(|e| format!("Failed to create archive: {}", e).into())
+    });
+
+    if written.is_err() {
+        let _ = std::fs::remove_file(&temporary);
+    }
+
+    written
+}
+
+#[tauri::command]
+pub async fn create_archive(
+    app: AppHandle,
+    paths: Vec<String>,
+    destination: String,
+    format: ArchiveFormat,
+    level: Option<u32>,
+) -> Result<String, String> {
+    jobs::submit_job(
+        app,
+        JobRequest::CreateArchive {
+            paths,
+            destination,
+            format,
+            level,
+        },
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn extract_archive(
+    app: AppHandle,
+    path: String,
+    destination: String,
+    on_conflict: ConflictPolicy,
+) -> Result<String, String> {
+    jobs::submit_job(
+        app,
+        JobRequest::ExtractArchive {
+            archive: path,
+            destination,
+            on_conflict,
+        },
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extract(archive: &Path, destination: &Path) -> Result<(), String> {
+        extract_members(archive, "", destination, ConflictPolicy::Fail, |_, _, _| {
+            Ok::<(), String>(())
+        })
+    }
+
+    fn build_zip(path: &Path, build: impl FnOnce(&mut zip::ZipWriter<File>)) {
+        let mut zip = zip::ZipWriter::new(File::create(path).unwrap());
+        build(&mut zip);
+        zip.finish().unwrap();
+    }
+
+    fn build_tar(path: &Path, build: impl FnOnce(&mut tar::Builder<File>)) {
+        let mut tar = tar::Builder::new(File::create(path).unwrap());
+        build(&mut tar);
+        tar.finish().unwrap();
+    }
+
+    /// Appends an entry with exactly the given name, bypassing the checks
+    /// `tar::Builder` applies to paths.
+    fn append_raw(
+        tar: &mut tar::Builder<File>,
+        entry_type: tar::EntryType,
+        name: &[u8],
+        link: &[u8],
+        data: &[u8],
+    ) {
+        let mut header = tar::Header::new_gnu();
+        header.as_old_mut().name[..name.len()].copy_from_slice(name);
+        header.as_old_mut().linkname[..link.len()].copy_from_slice(link);
+        header.set_entry_type(entry_type);
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        tar.append(&header, data).unwrap();
+    }
+
+    /// Overwrites the uncompressed size recorded for every zip member.
+    fn patch_zip_sizes(path: &Path, size: u32) {
+        let mut bytes = std::fs::read(path).unwrap();
+        let mut offset = 0;
+
+        while let Some(found) = bytes[offset..]
+            .windows(4)
+            .position(|window| window == b"PK\x01\x02")
+        {
+            let start = offset + found;
+            bytes[start + 24..start + 28].copy_from_slice(&size.to_le_bytes());
+            offset = start + 4;
+        }
+
+        std::fs::write(path, bytes).unwrap();
+    }
+
+    #[test]
+    fn links_are_resolved_only_within_the_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("links.tar");
+
+        build_tar(&archive, |tar| {
+            let link = tar::EntryType::Symlink;
+            append_raw(tar, tar::EntryType::Regular, b"dir/file", b"", b"data");
+            append_raw(tar, link, b"dir/sibling", b"file", b"");
+            append_raw(tar, link, b"dir/chain", b"../dir/sibling", b"");
+            append_raw(tar, tar::EntryType::Link, b"hard", b"dir/file", b"");
+            append_raw(tar, link, b"absolute", b"/etc/passwd", b"");
+            append_raw(tar, link, b"dir/climbing", b"../../etc/passwd", b"");
+            append_raw(tar, link, b"loop", b"loop", b"");
+        });
+
+        let index = build_index(&archive, ArchiveFormat::Tar).unwrap();
+
+        for key in ["dir/file", "dir/sibling", "dir/chain", "hard"] {
+            assert_eq!(resolve_link(&index, key).unwrap(), "dir/file", "{}", key);
+        }
+
+        for key in ["absolute", "dir/climbing"] {
+            let error = resolve_link(&index, key).unwrap_err();
+            assert!(error.contains("outside the archive"), "{}", error);
+        }
+
+        assert!(resolve_link(&index, "loop").is_err());
+    }
+
+    #[test]
+    fn zip_entries_cannot_climb_out_of_the_destination() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("hostile.zip");
+        let destination = dir.path().join("out");
+        let absolute = dir.path().join("absolute.txt");
+
+        build_zip(&archive, |zip| {
+            let options = zip::write::SimpleFileOptions::default();
+            for name in [
+                "../escape.txt",
+                "dir/../../escape.txt",
+                "..\\escape.txt",
+                absolute.to_str().unwrap(),
+                "kept.txt",
+            ] {
+                zip.start_file(name, options).unwrap();
+                zip.write_all(b"payload").unwrap();
+            }
+        });
+
+        extract(&archive, &destination).unwrap();
+
+        assert!(!dir.path().join("escape.txt").exists());
+        assert!(!absolute.exists());
+        assert!(destination
+            .join(absolute.strip_prefix("/").unwrap())
+            .is_file());
+        assert!(destination.join("kept.txt").is_file());
+    }
+
+    #[test]
+    fn tar_entries_cannot_climb_out_of_the_destination() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("hostile.tar");
+        let destination = dir.path().join("out");
+        let absolute = dir.path().join("absolute.txt");
+        let secret = dir.path().join("secret.txt");
+        std::fs::write(&secret, "secret").unwrap();
+
+        build_tar(&archive, |tar| {
+            let file = tar::EntryType::Regular;
+            append_raw(tar, file, b"../escape.txt", b"", b"payload");
+            append_raw(tar, file, b"dir/../../escape.txt", b"", b"payload");
+            append_raw(tar, file, absolute.to_str().unwrap().as_bytes(), b"", b"x");
+            append_raw(tar, tar::EntryType::Link, b"hard", b"../secret.txt", b"");
+            append_raw(tar, file, b"kept.txt", b"", b"payload");
+        });
+
+        extract(&archive, &destination).unwrap();
+
+        assert!(!dir.path().join("escape.txt").exists());
+        assert!(!absolute.exists());
+        assert!(destination
+            .join(absolute.strip_prefix("/").unwrap())
+            .is_file());
+        assert!(!destination.join("hard").exists());
+        assert!(destination.join("kept.txt").is_file());
+    }
+
+    #[test]
+    fn zip_symlinks_cannot_redirect_extraction() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("hostile.zip");
+        let destination = dir.path().join("out");
+        let outside = dir.path().join("outside");
+        std::fs::create_dir(&outside).unwrap();
+
+        build_zip(&archive, |zip| {
+            let options = zip::write::SimpleFileOptions::default();
+            zip.add_symlink("link", outside.to_str().unwrap(), options)
+                .unwrap();
+            zip.start_file("link/escape.txt", options).unwrap();
+            zip.write_all(b"payload").unwrap();
+        });
+
+        let error = extract(&archive, &destination).unwrap_err();
+
+        assert!(error.contains("non-directory"), "{}", error);
+        assert!(!outside.join("escape.txt").exists());
+    }
+
+    #[test]
+    fn tar_symlinks_cannot_redirect_extraction() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("hostile.tar");
+        let destination = dir.path().join("out");
+        let outside = dir.path().join("outside");
+        std::fs::create_dir(&outside).unwrap();
+
+        build_tar(&archive, |tar| {
+            append_raw(tar, tar::EntryType::Symlink, b"link", b"../outside", b"");
+            append_raw(
+                tar,
+                tar::EntryType::Regular,
+                b"link/escape.txt",
+                b"",
+                b"payload",
+            );
+        });
+
+        let error = extract(&archive, &destination).unwrap_err();
+
+        assert!(error.contains("non-directory"), "{}", error);
+        assert!(!outside.join("escape.txt").exists());
+    }
+
+    #[test]
+    fn archives_that_expand_too_far_are_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("bomb.zip");
+        let destination = dir.path().join("out");
+
+        build_zip(&archive, |zip| {
+            let options = zip::write::SimpleFileOptions::default()
+                .compression_method(zip::CompressionMethod::Stored);
+            for n in 0..20 {
+                zip.start_file(format!("{}.bin", n), options).unwrap();
+                zip.write_all(b"payload").unwrap();
+            }
+        });
+        patch_zip_sizes(&archive, 0xFFFF_0000);
+
+        let error = extract(&archive, &destination).unwrap_err();
+
+        assert!(error.contains("expands to more than"), "{}", error);
+        assert!(!destination.join("0.bin").exists());
+    }
+
+    #[test]
+    fn members_larger_than_recorded_are_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("bomb.zip");
+        let destination = dir.path().join("out");
+
+        build_zip(&archive, |zip| {
+            zip.start_file("zeros.bin", zip::write::SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(&[0; 100_000]).unwrap();
+        });
+        patch_zip_sizes(&archive, 10);
+
+        let error = extract(&archive, &destination).unwrap_err();
+
+        assert!(error.contains("larger than its recorded size"), "{}", error);
+    }
+}
@@ -0,0 +1,612 @@
+use chrono::{DateTime, Local};
+use regex::{NoExpand, Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+use crate::journal::{self, OperationKind};
+
+/// Widest zero padding a `{n}` token may ask for.
+const MAX_PADDING: usize = 32;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaseTransform {
+    Lower,
+    Upper,
+    Title,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RenameSpec {
+    template: Option<String>,
+    find: Option<String>,
+    #[serde(default)]
+    replace: String,
+    #[serde(default)]
+    use_regex: bool,
+    #[serde(default)]
+    ignore_case: bool,
+    #[serde(default)]
+    include_extension: bool,
+    case: Option<CaseTransform>,
+    extension: Option<String>,
+    start_number: Option<u64>,
+    step: Option<u64>,
+    padding: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenamePair {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RenamePreview {
+    path: String,
+    old_name: String,
+    new_name: String,
+    new_path: String,
+    changed: bool,
+    conflict: Option<String>,
+}
+
+fn split_name(name: &str) -> (&str, Option<&str>) {
+    match name.rfind('.') {
+        Some(index) if index > 0 => (&name[..index], Some(&name[index + 1..])),
+        _ => (name, None),
+    }
+}
+
+fn apply_case(text: &str, case: CaseTransform) -> String {
+    match case {
+        CaseTransform::Lower => text.to_lowercase(),
+        CaseTransform::Upper => text.to_uppercase(),
+        CaseTransform::Title => {
+            let mut result = String::with_capacity(text.len());
+            let mut at_word_start = true;
+
+            for c in text.chars() {
+                if c.is_alphanumeric() {
+                    if at_word_start {
+                        result.extend(c.to_uppercase());
+                    } else {
+                        result.extend(c.to_lowercase());
+                    }
+                    at_word_start = false;
+                } else {
+                    result.push(c);
+                    at_word_start = true;
+                }
+            }
+
+            result
+        }
+    }
+}
+
+fn format_date(modified: Option<DateTime<Local>>, format: &str) -> Result<String, String> {
+    use chrono::format::{Item, StrftimeItems};
+
+    if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+        return Err(format!("Invalid date format: {}", format));
+    }
+
+    Ok(modified
+        .map(|date| date.format(format).to_string())
+        .unwrap_or_default())
+}
+
+struct TemplateContext<'a> {
+    stem: &'a str,
+    extension: Option<&'a str>,
+    number: u64,
+    padding: usize,
+    modified: Option<DateTime<Local>>,
+}
+
+fn expand_template(template: &str, context: &TemplateContext) -> Result<String, String> {
+    let mut result = String::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+
+        let end = rest[start..]
+            .find('}')
+            .map(|end| start + end)
+            .ok_or_else(|| "Unclosed '{' in rename template".to_string())?;
+
+        let token = &rest[start + 1..end];
+        let (name, argument) = match token.split_once(':') {
+            Some((name, argument)) => (name, Some(argument)),
+            None => (token, None),
+        };
+
+        match name {
+            "name" => result.push_str(context.stem),
+            "ext" => result.push_str(context.extension.unwrap_or("")),
+            "n" => {
+                let padding = argument
+                    .map(|width| {
+                        width
+                            .parse::<usize>()
+                            .map_err(|_| format!("Invalid padding: {}", width))
+                    })
+                    .transpose()?
+                    .unwrap_or(context.padding);
+
+                if padding > MAX_PADDING {
+                    return Err(format!(
+                        "Padding cannot be wider than {} digits: {}",
+                        MAX_PADDING, padding
+                    ));
+                }
+
+                result.push_str(&format!("{:0width$}", context.number, width = padding));
+            }
+            "date" => result.push_str(&format_date(
+                context.modified,
+                argument.unwrap_or("%Y-%m-%d"),
+            )?),
+            _ => return Err(format!("Unknown rename token: {{{}}}", token)),
+        }
+
+        rest = &rest[end + 1..];
+    }
+
+    result.push_str(rest);
+
+    Ok(result)
+}
+
+fn build_matcher(spec: &RenameSpec) -> Result<Option<Regex>, String> {
+    let Some(find) = spec.find.as_deref().filter(|find| !find.is_empty()) else {
+        return Ok(None);
+    };
+
+    let pattern = if spec.use_regex {
+        find.to_string()
+    } else {
+        regex::escape(find)
+    };
+
+    RegexBuilder::new(&pattern)
+        .case_insensitive(spec.ignore_case)
+        .build()
+        .map(Some)
+        .map_err(|e| format!("Invalid pattern: {}", e))
+}
+
+fn compute_name(
+    spec: &RenameSpec,
+    matcher: Option<&Regex>,
+    old_name: &str,
+    number: u64,
+    modified: Option<DateTime<Local>>,
+) -> Result<String, String> {
+    let (stem, extension) = split_name(old_name);
+
+    let mut stem = match &spec.template {
+        Some(template) => expand_template(
+            template,
+            &TemplateContext {
+                stem,
+                extension,
+                number,
+                padding: spec.padding.unwrap_or(0),
+                modified,
+            },
+        )?,
+        None => stem.to_string(),
+    };
+    let mut extension = extension.map(str::to_string);
+
+    if let Some(matcher) = matcher {
+        if spec.include_extension {
+            let full = match &extension {
+                Some(extension) => format!("{}.{}", stem, extension),
+                None => stem,
+            };
+            let replaced = replace_all(matcher, &full, &spec.replace, spec.use_regex);
+            let (new_stem, new_extension) = split_name(&replaced);
+            stem = new_stem.to_string();
+            extension = new_extension.map(str::to_string);
+        } else {
+            stem = replace_all(matcher, &stem, &spec.replace, spec.use_regex);
+        }
+    }
+
+    if let Some(case) = spec.case {
+        stem = apply_case(&stem, case);
+        extension = extension.map(|extension| apply_case(&extension, case));
+    }
+
+    if let Some(new_extension) = &spec.extension {
+        let new_extension = new_extension.trim_start_matches('.');
+        extension = if new_extension.is_empty() {
+            None
+        } else {
+            Some(new_extension.to_string())
+        };
+    }
+
+    Ok(match extension {
+        Some(extension) => format!("{}.{}", stem, extension),
+        None => stem,
+    })
+}
+
+fn replace_all(matcher: &Regex, text: &str, replacement: &str, expand: bool) -> String {
+    if expand {
+        matcher.replace_all(text, replacement).into_owned()
+    } else {
+        matcher
+            .replace_all(text, NoExpand(replacement))
+            .into_owned()
+    }
+}
+
+fn validate_name(name: &str) -> Option<String> {
+    if name.is_empty() || name == "." || name == ".." {
+        return Some("Name is empty or reserved".to_string());
+    }
+
+    if name.contains('/') || name.contains('\0') {
+        return Some("Name contains invalid characters".to_string());
+    }
+
+    #[cfg(windows)]
+    if name.contains(['\\', ':', '*', '?', '"', '<', '>', '|']) {
+        return Some("Name contains invalid characters".to_string());
+    }
+
+    None
+}
+
+async fn build_preview(paths: &[String], spec: &RenameSpec) -> Result<Vec<RenamePreview>, String> {
+    let matcher = build_matcher(spec)?;
+    let start = spec.start_number.unwrap_or(1);
+    let step = spec.step.unwrap_or(1);
+
+    let mut previews = Vec::with_capacity(paths.len());
+
+    for (index, path) in paths.iter().enumerate() {
+        let item_path = Path::new(path);
+
+        let metadata = fs::symlink_metadata(item_path)
+            .await
+            .map_err(|_| format!("Item does not exist: {}", path))?;
+
+        let old_name = item_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .ok_or_else(|| format!("Invalid path: {}", path))?;
+
+        let parent = item_path
+            .parent()
+            .ok_or_else(|| "Cannot determine parent directory".to_string())?;
+
+        let modified = metadata.modified().ok().map(DateTime::<Local>::from);
+        let number = step
+            .checked_mul(index as u64)
+            .and_then(|offset| start.checked_add(offset))
+            .ok_or_else(|| "Numbering exceeds the largest supported number".to_string())?;
+        let new_name = compute_name(spec, matcher.as_ref(), &old_name, number, modified)?;
+
+        previews.push(RenamePreview {
+            path: path.clone(),
+            changed: new_name != old_name,
+            conflict: validate_name(&new_name),
+            new_path: parent.join(&new_name).to_string_lossy().to_string(),
+            old_name,
+            new_name,
+        });
+    }
+
+    let sources = paths.iter().map(PathBuf::from).collect::<HashSet<_>>();
+    let mut targets: HashMap<String, usize> = HashMap::new();
+    for preview in &previews {
+        *targets.entry(preview.new_path.clone()).or_default() += 1;
+    }
+
+    for preview in previews
+        .iter_mut()
+        .filter(|preview| preview.conflict.is_none())
+    {
+        if targets.get(&preview.new_path).copied().unwrap_or(0) > 1 {
+            preview.conflict = Some("Another item in this batch gets the same name".to_string());
+        } else if preview.changed
+            && !sources.contains(Path::new(&preview.new_path))
+            && fs::symlink_metadata(&preview.new_path).await.is_ok()
+        {
+            preview.conflict = Some(format!("'{}' already exists", preview.new_name));
+        }
+    }
+
+    Ok(previews)
+}
+
+fn temporary_path(path: &Path) -> PathBuf {
+    path.with_file_name(format!(".wiregui-rename-{}", uuid::Uuid::new_v4()))
+}
+
+pub(crate) async fn apply_renames(renames: &[RenamePair]) -> Result<(), String> {
+    let mut staged: Vec<(&RenamePair, PathBuf)> = Vec::with_capacity(renames.len());
+
+    for pair in renames {
+        let temporary = temporary_path(Path::new(&pair.from));
+
+        if let Err(e) = fs::rename(&pair.from, &temporary).await {
+            for (pair, temporary) in staged.iter().rev() {
+                let _ = fs::rename(temporary, &pair.from).await;
+            }
+            return Err(format!("Failed to rename '{}': {}", pair.from, e));
+        }
+
+        staged.push((pair, temporary));
+    }
+
+    for (index, (pair, temporary)) in staged.iter().enumerate() {
+        if fs::symlink_metadata(&pair.to).await.is_ok() {
+            rollback(&staged, index).await;
+            return Err(format!(
+                "Failed to rename '{}': target already exists",
+                pair.from
+            ));
+        }
+
+        if let Err(e) = fs::rename(temporary, &pair.to).await {
+            rollback(&staged, index).await;
+            return Err(format!("Failed to rename '{}': {}", pair.from, e));
+        }
+    }
+
+    Ok(())
+}
+
+async fn rollback(staged: &[(&RenamePair, PathBuf)], completed: usize) {
+    for (pair, _) in staged[..completed].iter().rev() {
+        let _ = fs::rename(&pair.to, &pair.from).await;
+    }
+
+    for (pair, temporary) in &staged[completed..] {
+        let _ = fs::rename(temporary, &pair.from).await;
+    }
+}
+
+#[tauri::command]
+pub async fn batch_rename(
+    paths: Vec<String>,
+    spec: RenameSpec,
+    dry_run: bool,
+) -> Result<Vec<RenamePreview>, String> {
+    let previews = build_preview(&paths, &spec).await?;
+
+    if dry_run {
+        return Ok(previews);
+    }
+
+    if let Some(preview) = previews.iter().find(|preview| preview.conflict.is_some()) {
+        return Err(format!(
+            "Cannot rename '{}': {}",
+            preview.old_name,
+            preview.conflict.as_deref().unwrap_or_default()
+        ));
+    }
+
+    let renames = previews
+        .iter()
+        .filter(|preview| preview.changed)
+        .map(|preview| RenamePair {
+            from: preview.path.clone(),
+            to: preview.new_path.clone(),
+        })
+        .collect::<Vec<_>>();
+
+    if renames.is_empty() {
+        return Ok(previews);
+    }
+
+    apply_renames(&renames).await?;
+
+    journal::record_operation(OperationKind::BatchRename { renames }).await?;
+
+    Ok(previews)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn spec(value: serde_json::Value) -> RenameSpec {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn rename(value: serde_json::Value, old_name: &str, number: u64) -> Result<String, String> {
+        let spec = spec(value);
+        let matcher = build_matcher(&spec)?;
+        let modified = Local.with_ymd_and_hms(2024, 3, 9, 14, 5, 0).single();
+
+        compute_name(&spec, matcher.as_ref(), old_name, number, modified)
+    }
+
+    #[test]
+    fn templates_expand_name_extension_and_number() {
+        assert_eq!(
+            rename(
+                serde_json::json!({ "template": "{name}-{n}" }),
+                "photo.jpg",
+                7
+            )
+            .unwrap(),
+            "photo-7.jpg"
+        );
+        assert_eq!(
+            rename(
+                serde_json::json!({ "template": "{ext}_{name}" }),
+                "notes.txt",
+                1
+            )
+            .unwrap(),
+            "txt_notes.txt"
+        );
+    }
+
+    #[test]
+    fn numbers_use_the_token_padding_over_the_spec() {
+        let template = serde_json::json!({ "template": "img{n}", "padding": 3 });
+        assert_eq!(rename(template, "a.png", 5).unwrap(), "img005.png");
+
+        let template = serde_json::json!({ "template": "img{n:2}", "padding": 4 });
+        assert_eq!(rename(template, "a.png", 5).unwrap(), "img05.png");
+
+        let template = serde_json::json!({ "template": "img{n:1}" });
+        assert_eq!(rename(template, "a.png", 123).unwrap(), "img123.png");
+    }
+
+    #[test]
+    fn dates_use_the_modification_time() {
+        assert_eq!(
+            rename(
+                serde_json::json!({ "template": "{date}_{name}" }),
+                "a.txt",
+                1
+            )
+            .unwrap(),
+            "2024-03-09_a.txt"
+        );
+        assert_eq!(
+            rename(serde_json::json!({ "template": "{date:%H%M}" }), "a.txt", 1).unwrap(),
+            "1405.txt"
+        );
+    }
+
+    #[test]
+    fn invalid_templates_are_rejected() {
+        for template in ["{name", "{unknown}", "{n:wide}", "{date:%Q}"] {
+            assert!(
+                rename(serde_json::json!({ "template": template }), "a.txt", 1).is_err(),
+                "{}",
+                template
+            );
+        }
+    }
+
+    #[test]
+    fn names_without_an_extension_have_an_empty_ext_token() {
+        assert_eq!(
+            rename(
+                serde_json::json!({ "template": "{name}.{ext}" }),
+                "Makefile",
+                1
+            )
+            .unwrap(),
+            "Makefile."
+        );
+        assert_eq!(
+            rename(serde_json::json!({ "template": "x{name}" }), ".bashrc", 1).unwrap(),
+            "x.bashrc"
+        );
+    }
+
+    #[test]
+    fn find_and_replace_runs_after_the_template() {
+        let value = serde_json::json!({
+            "template": "{name} copy",
+            "find": " ",
+            "replace": "_",
+        });
+
+        assert_eq!(rename(value, "my file.txt", 1).unwrap(), "my_file_copy.txt");
+    }
+
+    #[test]
+    fn plain_replacements_are_not_expanded() {
+        let value = serde_json::json!({ "find": "a", "replace": "$0" });
+        assert_eq!(rename(value, "banana.txt", 1).unwrap(), "b$0n$0n$0.txt");
+
+        let value = serde_json::json!({
+            "find": r"(\d+)",
+            "replace": "n$1",
+            "use_regex": true,
+        });
+        assert_eq!(rename(value, "file12.txt", 1).unwrap(), "filen12.txt");
+    }
+
+    #[test]
+    fn the_extension_is_only_replaced_when_included() {
+        let value = serde_json::json!({ "find": "TXT", "replace": "md", "ignore_case": true });
+        assert_eq!(rename(value, "txt.txt", 1).unwrap(), "md.txt");
+
+        let value = serde_json::json!({
+            "find": "txt",
+            "replace": "md",
+            "include_extension": true,
+        });
+        assert_eq!(rename(value, "txt.txt", 1).unwrap(), "md.md");
+    }
+
+    #[test]
+    fn case_and_extension_changes_apply_last() {
+        let value = serde_json::json!({ "template": "{name} {n}", "case": "title" });
+        assert_eq!(
+            rename(value, "hello WORLD.TXT", 2).unwrap(),
+            "Hello World 2.Txt"
+        );
+
+        let value = serde_json::json!({ "case": "upper", "extension": ".jpeg" });
+        assert_eq!(rename(value, "photo.jpg", 1).unwrap(), "PHOTO.jpeg");
+
+        let value = serde_json::json!({ "extension": "" });
+        assert_eq!(rename(value, "archive.tar", 1).unwrap(), "archive");
+    }
+
+    #[test]
+    fn padding_is_limited() {
+        let template = serde_json::json!({ "template": "{n:32}" });
+        assert_eq!(rename(template, "a", 1).unwrap().len(), 32);
+
+        for value in [
+            serde_json::json!({ "template": "{n:999999999999}" }),
+            serde_json::json!({ "template": "{n:33}" }),
+            serde_json::json!({ "template": "{n}", "padding": 33 }),
+        ] {
+            assert!(rename(value, "a", 1).is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn numbering_that_overflows_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = ["a.txt", "b.txt"].map(|name| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, "").unwrap();
+            path.to_string_lossy().to_string()
+        });
+
+        let last = spec(serde_json::json!({ "template": "{n}", "start_number": u64::MAX - 1 }));
+        assert_eq!(
+            build_preview(&paths, &last).await.unwrap()[1].new_name,
+            format!("{}.txt", u64::MAX)
+        );
+
+        for value in [
+            serde_json::json!({ "template": "{n}", "start_number": u64::MAX }),
+            serde_json::json!({ "template": "{n}", "step": u64::MAX, "start_number": 2 }),
+        ] {
+            assert!(build_preview(&paths, &spec(value)).await.is_err());
+        }
+    }
+
+    #[test]
+    fn reserved_and_invalid_names_are_reported() {
+        assert!(validate_name("").is_some());
+        assert!(validate_name("..").is_some());
+        assert!(validate_name("a/b").is_some());
+        assert!(validate_name("ok name.txt").is_none());
+    }
+}
@@ -0,0 +1,420 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tokio::fs;
+use tokio::sync::Mutex;
+
+use crate::app_config_dir;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bookmark {
+    name: String,
+    path: String,
+    exists: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct StoredBookmark {
+    path: String,
+    name: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct BookmarkStore {
+    /// Mirror bookmarks to the GTK bookmarks file shared with Nautilus and
+    /// GTK file dialogs.
+    gtk_sync: bool,
+    bookmarks: Vec<StoredBookmark>,
+}
+
+/// A line of the GTK bookmarks file. Non-`file://` entries (such as
+/// `sftp://` locations) are kept verbatim.
+enum GtkLine {
+    File(StoredBookmark),
+    Other(String),
+}
+
+fn bookmarks_path() -> Result<PathBuf, String> {
+    Ok(app_config_dir()?.join("bookmarks.json"))
+}
+
+fn gtk_bookmarks_path() -> Result<PathBuf, String> {
+    dirs::config_dir()
+        .map(|dir| dir.join("gtk-3.0").join("bookmarks"))
+        .ok_or_else(|| "Failed to get configuration directory".to_string())
+}
+
+fn get_store() -> &'static Mutex<BookmarkStore> {
+    static STORE: OnceLock<Mutex<BookmarkStore>> = OnceLock::new();
+
+    STORE.get_or_init(|| {
+        let store = bookmarks_path()
+            .ok()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str::<BookmarkStore>(&contents).ok())
+            .unwrap_or_default();
+
+        Mutex::new(store)
+    })
+}
+
+/// Percent-encodes `path` as a `file://` URI.
+pub(crate) fn path_to_file_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+
+    for &byte in path.as_os_str().as_encoded_bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                uri.push(byte as char)
+            }
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    uri
+}
+
+/// Decodes a local `file://` URI; remote URIs yield `None`.
+pub(crate) fn file_uri_to_path(uri: &str) -> Option<PathBuf> {
+    let encoded = uri
+        .strip_prefix("file://")
+        .map(|rest| rest.strip_prefix("localhost").unwrap_or(rest))?;
+
+    if !encoded.starts_with('/') {
+        return None;
+    }
+
+    let bytes = encoded.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| bytes.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|digits| std::str::from_utf8(digits).ok())
+            .and_then(|digits| u8::from_str_radix(digits, 16).ok());
+
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStringExt;
+        Some(PathBuf::from(std::ffi::OsString::from_vec(decoded)))
+    }
+
+    #[cfg(not(unix))]
+    {
+        Some(PathBuf::from(String::from_utf8_lossy(&decoded).to_string()))
+    }
+}
+
+async fn read_gtk_bookmarks() -> Result<Vec<GtkLine>, String> {
+    let contents = match fs::read_to_string(gtk_bookmarks_path()?).await {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read GTK bookmarks: {}", e)),
+    };
+
+    Ok(contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let (uri, name) = match line.split_once(' ') {
+                Some((uri, name)) => (uri, Some(name.to_string())),
+                None => (line, None),
+            };
+
+            match file_uri_to_path(uri) {
+                Some(path) => GtkLine::File(StoredBookmark {
+                    path: path.to_string_lossy().to_string(),
+                    name,
+                }),
+                None => GtkLine::Other(line.to_string()),
+            }
+        })
+        .collect())
+}
+
+/// Rewrites the GTK bookmarks file with `bookmarks` in the slots previously
+/// held by local entries, leaving remote entries where they were.
+async fn write_gtk_bookmarks(bookmarks: &[StoredBookmark]) -> Result<(), String> {
+    let existing = read_gtk_bookmarks().await?;
+    let mut pending = bookmarks.iter();
+    let mut lines = Vec::new();
+
+    let format_line = |bookmark: &StoredBookmark| {
+        let uri = path_to_file_uri(Path::new(&bookmark.path));
+        match &bookmark.name {
+            Some(name) => format!("{} {}", uri, name),
+            None => uri,
+        }
+    };
+
+    for line in existing {
+        match line {
+            GtkLine::Other(line) => lines.push(line),
+            GtkLine::File(_) => lines.extend(pending.next().map(format_line)),
+        }
+    }
+    lines.extend(pending.map(format_line));
+
+    let path = gtk_bookmarks_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create GTK configuration directory: {}", e))?;
+    }
+
+    // The file is often a symlink into a dotfiles repository; the link is
+    // kept and the file it points at is replaced.
+    let path = fs::canonicalize(&path).await.unwrap_or(path);
+
+    let mut contents = lines.join("\n");
+    contents.push('\n');
+
+    write_replacing(&path, contents)
+        .await
+        .map_err(|e| format!("Failed to write GTK bookmarks: {}", e))
+}
+
+/// Writes beside `path` and renames over it, so a crash cannot leave it
+/// truncated.
+async fn write_replacing(path: &Path, contents: String) -> std::io::Result<()> {
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+
+    fs::write(&temporary, contents).await?;
+    fs::rename(&temporary, path).await
+}
+
+async fn current_bookmarks(store: &BookmarkStore) -> Result<Vec<StoredBookmark>, String> {
+    if !store.gtk_sync {
+        return Ok(store.bookmarks.clone());
+    }
+
+    Ok(read_gtk_bookmarks()
+        .await?
+        .into_iter()
+        .filter_map(|line| match line {
+            GtkLine::File(bookmark) => Some(bookmark),
+            GtkLine::Other(_) => None,
+        })
+        .collect())
+}
+
+async fn save_bookmarks(
+    store: &mut BookmarkStore,
+    bookmarks: Vec<StoredBookmark>,
+) -> Result<Vec<Bookmark>, String> {
+    if store.gtk_sync {
+        write_gtk_bookmarks(&bookmarks).await?;
+    }
+
+    store.bookmarks = bookmarks;
+
+    let path = bookmarks_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create configuration directory: {}", e))?;
+    }
+
+    let contents = serde_json::to_string_pretty(store)
+        .map_err(|e| format!("Failed to serialize bookmarks: {}", e))?;
+
+    write_replacing(&path, contents)
+        .await
+        .map_err(|e| format!("Failed to write bookmarks: {}", e))?;
+
+    Ok(to_bookmarks(&store.bookmarks))
+}
+
+fn to_bookmarks(bookmarks: &[StoredBookmark]) -> Vec<Bookmark> {
+    bookmarks
+        .iter()
+        .map(|bookmark| {
+            let path = Path::new(&bookmark.path);
+
+            Bookmark {
+                name: bookmark.name.clone().unwrap_or_else(|| {
+                    path.file_name()
+                        .map(|name| name.to_string_lossy().to_string())
+                        .unwrap_or_else(|| bookmark.path.clone())
+                }),
+                path: bookmark.path.clone(),
+                exists: path.is_dir(),
+            }
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub async fn list_bookmarks() -> Result<Vec<Bookmark>, String> {
+    let store = get_store().lock().await;
+
+    Ok(to_bookmarks(&current_bookmarks(&store).await?))
+}
+
+#[tauri::command]
+pub async fn add_bookmark(path: String, name: Option<String>) -> Result<Vec<Bookmark>, String> {
+    if !Path::new(&path).is_dir() {
+        return Err(format!("Directory does not exist: {}", path));
+    }
+
+    let mut store = get_store().lock().await;
+    let mut bookmarks = current_bookmarks(&store).await?;
+
+    if bookmarks.iter().any(|bookmark| bookmark.path == path) {
+        return Err(format!("Bookmark already exists: {}", path));
+    }
+
+    bookmarks.push(StoredBookmark {
+        path,
+        name: name
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty()),
+    });
+
+    save_bookmarks(&mut store, bookmarks).await
+}
+
+#[tauri::command]
+pub async fn remove_bookmark(path: String) -> Result<Vec<Bookmark>, String> {
+    let mut store = get_store().lock().await;
+    let mut bookmarks = current_bookmarks(&store).await?;
+
+    let count = bookmarks.len();
+    bookmarks.retain(|bookmark| bookmark.path != path);
+
+    if bookmarks.len() == count {
+        return Err(format!("Bookmark does not exist: {}", path));
+    }
+
+    save_bookmarks(&mut store, bookmarks).await
+}
+
+/// Reorders bookmarks to match `paths`, which must list every bookmark
+/// exactly once.
+#[tauri::command]
+pub async fn reorder_bookmarks(paths: Vec<String>) -> Result<Vec<Bookmark>, String> {
+    let mut store = get_store().lock().await;
+    let mut bookmarks = current_bookmarks(&store).await?;
+
+    if paths.len() != bookmarks.len() {
+        return Err("Bookmark order does not match existing bookmarks".to_string());
+    }
+
+    let mut reordered = Vec::with_capacity(paths.len());
+    for path in &paths {
+        let index = bookmarks
+            .iter()
+            .position(|bookmark| &bookmark.path == path)
+            .ok_or_else(|| format!("Bookmark does not exist: {}", path))?;
+        reordered.push(bookmarks.remove(index));
+    }
+
+    save_bookmarks(&mut store, reordered).await
+}
+
+/// Turns GTK bookmarks sharing on or off. Enabling it merges the app's
+/// bookmarks into the GTK file, after the ones already there.
+#[tauri::command]
+pub async fn set_gtk_bookmarks_sync(enabled: bool) -> Result<Vec<Bookmark>, String> {
+    let mut store = get_store().lock().await;
+
+    if store.gtk_sync == enabled {
+        return Ok(to_bookmarks(&current_bookmarks(&store).await?));
+    }
+
+    // Changes are made on a copy so a failed save leaves the setting as it
+    // was.
+    let mut updated = BookmarkStore {
+        gtk_sync: enabled,
+        ..store.clone()
+    };
+
+    let bookmarks = if enabled {
+        let mut merged = current_bookmarks(&updated).await?;
+        let mut bookmarks = store.bookmarks.clone();
+        bookmarks.retain(|bookmark| !merged.iter().any(|existing| existing.path == bookmark.path));
+        merged.extend(bookmarks);
+        merged
+    } else {
+        current_bookmarks(&store).await?
+    };
+
+    let bookmarks = save_bookmarks(&mut updated, bookmarks).await?;
+    *store = updated;
+
+    Ok(bookmarks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_uris_are_percent_encoded() {
+        assert_eq!(
+            path_to_file_uri(Path::new("/home/me/My Files/100%")),
+            "file:///home/me/My%20Files/100%25"
+        );
+        assert_eq!(
+            path_to_file_uri(Path::new("/tmp/été#1?")),
+            "file:///tmp/%C3%A9t%C3%A9%231%3F"
+        );
+    }
+
+    #[test]
+    fn file_uris_round_trip() {
+        for path in [
+            "/",
+            "/home/me/My Files",
+            "/srv/100%/%41",
+            "/tmp/été",
+            "/tmp/a+b=c&d",
+            "/tmp/line\nbreak",
+        ] {
+            let uri = path_to_file_uri(Path::new(path));
+            assert_eq!(file_uri_to_path(&uri), Some(PathBuf::from(path)), "{}", uri);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn file_uris_round_trip_names_that_are_not_utf8() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let path = Path::new(std::ffi::OsStr::from_bytes(b"/tmp/caf\xe9 \xff"));
+        let uri = path_to_file_uri(path);
+
+        assert_eq!(uri, "file:///tmp/caf%E9%20%FF");
+        assert_eq!(file_uri_to_path(&uri).as_deref(), Some(path));
+    }
+
+    #[test]
+    fn only_local_file_uris_are_decoded() {
+        assert_eq!(
+            file_uri_to_path("file://localhost/srv/data"),
+            Some(PathBuf::from("/srv/data"))
+        );
+        assert_eq!(
+            file_uri_to_path("file:///odd%zz%4"),
+            Some(PathBuf::from("/odd%zz%4"))
+        );
+        assert_eq!(file_uri_to_path("file://server/share"), None);
+        assert_eq!(file_uri_to_path("sftp://host/home"), None);
+        assert_eq!(file_uri_to_path("/plain/path"), None);
+    }
+}
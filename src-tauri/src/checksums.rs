@@ -0,0 +1,561 @@
+use md5::Md5;
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::io::Read;
+use std::path::{Component, Path};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio::task;
+
+const READ_BUFFER_SIZE: usize = 1024 * 1024;
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChecksumAlgorithm {
+    Md5,
+    Sha1,
+    Sha256,
+    Blake3,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Checksums {
+    path: String,
+    size: u64,
+    md5: Option<String>,
+    sha1: Option<String>,
+    sha256: Option<String>,
+    blake3: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChecksumProgress {
+    path: String,
+    processed_bytes: u64,
+    total_bytes: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationStatus {
+    Ok,
+    Mismatch,
+    Missing,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationResult {
+    file_name: String,
+    expected: String,
+    algorithm: Option<ChecksumAlgorithm>,
+    actual: Option<String>,
+    status: VerificationStatus,
+    error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChecksumVerification {
+    path: String,
+    algorithm: ChecksumAlgorithm,
+    results: Vec<VerificationResult>,
+    passed: usize,
+    failed: usize,
+    missing: usize,
+}
+
+enum Hasher {
+    Md5(Md5),
+    Sha1(Sha1),
+    Sha256(Sha256),
+    Blake3(Box<blake3::Hasher>),
+}
+
+impl Hasher {
+    fn new(algorithm: ChecksumAlgorithm) -> Self {
+        match algorithm {
+            ChecksumAlgorithm::Md5 => Hasher::Md5(Md5::new()),
+            ChecksumAlgorithm::Sha1 => Hasher::Sha1(Sha1::new()),
+            ChecksumAlgorithm::Sha256 => Hasher::Sha256(Sha256::new()),
+            ChecksumAlgorithm::Blake3 => Hasher::Blake3(Box::new(blake3::Hasher::new())),
+        }
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        match self {
+            Hasher::Md5(hasher) => hasher.update(bytes),
+            Hasher::Sha1(hasher) => hasher.update(bytes),
+            Hasher::Sha256(hasher) => hasher.update(bytes),
+            Hasher::Blake3(hasher) => {
+                hasher.update(bytes);
+            }
+        }
+    }
+
+    fn finalize(self) -> String {
+        match self {
+            Hasher::Md5(hasher) => format!("{:x}", hasher.finalize()),
+            Hasher::Sha1(hasher) => format!("{:x}", hasher.finalize()),
+            Hasher::Sha256(hasher) => format!("{:x}", hasher.finalize()),
+            Hasher::Blake3(hasher) => hasher.finalize().to_hex().to_string(),
+        }
+    }
+}
+
+fn hash_file(
+    app: &AppHandle,
+    path: &Path,
+    algorithms: &[ChecksumAlgorithm],
+) -> Result<(u64, Vec<(ChecksumAlgorithm, String)>), String> {
+    let mut file = std::fs::File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
+
+    let total_bytes = file
+        .metadata()
+        .map_err(|e| format!("Failed to read metadata: {}", e))?
+        .len();
+
+    let mut hashers = algorithms
+        .iter()
+        .map(|&algorithm| (algorithm, Hasher::new(algorithm)))
+        .collect::<Vec<_>>();
+
+    let mut buffer = vec![0u8; READ_BUFFER_SIZE];
+    let mut processed_bytes = 0u64;
+    let mut last_emit = Instant::now();
+
+    let emit_progress = |processed_bytes: u64| {
+        let _ = app.emit(
+            "checksum-progress",
+            ChecksumProgress {
+                path: path.to_string_lossy().to_string(),
+                processed_bytes,
+                total_bytes,
+            },
+        );
+    };
+
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .map_err(|e| format!("Failed to read file: {}", e))?;
+
+        if read == 0 {
+            break;
+        }
+
+        for (_, hasher) in &mut hashers {
+            hasher.update(&buffer[..read]);
+        }
+
+        processed_bytes += read as u64;
+
+        if last_emit.elapsed() >= PROGRESS_INTERVAL {
+            last_emit = Instant::now();
+            emit_progress(processed_bytes);
+        }
+    }
+
+    emit_progress(processed_bytes);
+
+    let digests = hashers
+        .into_iter()
+        .map(|(algorithm, hasher)| (algorithm, hasher.finalize()))
+        .collect();
+
+    Ok((processed_bytes, digests))
+}
+
+#[tauri::command]
+pub async fn compute_checksums(
+    app: AppHandle,
+    path: String,
+    algorithms: Vec<ChecksumAlgorithm>,
+) -> Result<Checksums, String> {
+    if algorithms.is_empty() {
+        return Err("No checksum algorithms specified".to_string());
+    }
+
+    let file_path = Path::new(&path).to_path_buf();
+
+    if !file_path.is_file() {
+        return Err(format!("File does not exist: {}", path));
+    }
+
+    let (size, digests) = task::spawn_blocking(move || hash_file(&app, &file_path, &algorithms))
+        .await
+        .map_err(|e| format!("Failed to compute checksums: {}", e))??;
+
+    let mut checksums = Checksums {
+        path,
+        size,
+        ..Default::default()
+    };
+
+    for (algorithm, digest) in digests {
+        let field = match algorithm {
+            ChecksumAlgorithm::Md5 => &mut checksums.md5,
+            ChecksumAlgorithm::Sha1 => &mut checksums.sha1,
+            ChecksumAlgorithm::Sha256 => &mut checksums.sha256,
+            ChecksumAlgorithm::Blake3 => &mut checksums.blake3,
+        };
+        *field = Some(digest);
+    }
+
+    Ok(checksums)
+}
+
+/// Guesses the algorithm from a checksum file's name, such as `SHA256SUMS`,
+/// `image.iso.sha256` or `B3SUMS`, optionally followed by `.txt`.
+fn algorithm_from_name(name: &str) -> Option<ChecksumAlgorithm> {
+    let name = name.to_ascii_lowercase();
+    let name = name.strip_suffix(".txt").unwrap_or(&name);
+    let suffix = name.rsplit(['.', '-', '_']).next()?;
+    let stem = suffix
+        .strip_suffix("sums")
+        .or_else(|| suffix.strip_suffix("sum"))
+        .unwrap_or(suffix);
+
+    match stem {
+        "md5" => Some(ChecksumAlgorithm::Md5),
+        "sha1" => Some(ChecksumAlgorithm::Sha1),
+        "sha256" => Some(ChecksumAlgorithm::Sha256),
+        "b3" | "blake3" => Some(ChecksumAlgorithm::Blake3),
+        _ => None,
+    }
+}
+
+/// Maps the tag of a BSD-style line, as written by `--tag`.
+fn algorithm_from_tag(tag: &str) -> Option<ChecksumAlgorithm> {
+    match tag.to_ascii_uppercase().as_str() {
+        "MD5" => Some(ChecksumAlgorithm::Md5),
+        "SHA1" => Some(ChecksumAlgorithm::Sha1),
+        "SHA256" => Some(ChecksumAlgorithm::Sha256),
+        "BLAKE3" => Some(ChecksumAlgorithm::Blake3),
+        _ => None,
+    }
+}
+
+fn algorithm_from_length(length: usize) -> Option<ChecksumAlgorithm> {
+    match length {
+        32 => Some(ChecksumAlgorithm::Md5),
+        40 => Some(ChecksumAlgorithm::Sha1),
+        64 => Some(ChecksumAlgorithm::Sha256),
+        _ => None,
+    }
+}
+
+/// Hex digits in a digest of `algorithm`.
+fn digest_length(algorithm: ChecksumAlgorithm) -> usize {
+    match algorithm {
+        ChecksumAlgorithm::Md5 => 32,
+        ChecksumAlgorithm::Sha1 => 40,
+        ChecksumAlgorithm::Sha256 | ChecksumAlgorithm::Blake3 => 64,
+    }
+}
+
+/// Whether `name` stays below the checksum file's directory. `./name`, as
+/// written by `sha256sum ./*`, is allowed.
+fn is_safe_file_name(name: &str) -> bool {
+    let path = Path::new(name);
+
+    path.components()
+        .any(|component| matches!(component, Component::Normal(_)))
+        && path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
+fn unescape_file_name(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    let mut chars = name.chars();
+
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n')) => {
+                chars.next();
+                result.push('\n');
+            }
+            ('\\', Some('\\')) => {
+                chars.next();
+                result.push('\\');
+            }
+            _ => result.push(c),
+        }
+    }
+
+    result
+}
+
+/// Parses a single line in either the GNU coreutils (`<digest>  <name>`) or
+/// BSD tagged (`SHA256 (<name>) = <digest>`) format.
+fn parse_checksum_line(line: &str) -> Option<(Option<ChecksumAlgorithm>, String, String)> {
+    if let Some((tag, rest)) = line.split_once(" (") {
+        if let Some((name, digest)) = rest.rsplit_once(") = ") {
+            return Some((
+                algorithm_from_tag(tag),
+                name.to_string(),
+                digest.trim().to_ascii_lowercase(),
+            ));
+        }
+    }
+
+    let (escaped, line) = match line.strip_prefix('\\') {
+        Some(line) => (true, line),
+        None => (false, line),
+    };
+
+    let (digest, name) = line.split_once(' ')?;
+    let name = name
+        .strip_prefix('*')
+        .or_else(|| name.strip_prefix(' '))
+        .unwrap_or(name);
+
+    if digest.is_empty() || name.is_empty() || !digest.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let name = if escaped {
+        unescape_file_name(name)
+    } else {
+        name.to_string()
+    };
+
+    Some((None, name, digest.to_ascii_lowercase()))
+}
+
+#[tauri::command]
+pub async fn verify_checksum_file(
+    app: AppHandle,
+    path: String,
+) -> Result<ChecksumVerification, String> {
+    let checksum_path = Path::new(&path);
+
+    let content = tokio::fs::read_to_string(checksum_path)
+        .await
+        .map_err(|e| format!("Failed to read checksum file: {}", e))?;
+
+    let base_dir = checksum_path
+        .parent()
+        .map(Path::to_path_buf)
+        .ok_or_else(|| "Cannot determine parent directory".to_string())?;
+
+    let entries = content
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(parse_checksum_line)
+        .collect::<Vec<_>>();
+
+    if entries.is_empty() {
+        return Err("No checksums found in file".to_string());
+    }
+
+    let file_name = checksum_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    // Tagged lines name their own algorithm; the rest share one guessed from
+    // the file name or the first untagged digest.
+    let fallback = algorithm_from_name(&file_name).or_else(|| {
+        entries
+            .iter()
+            .find(|(algorithm, _, _)| algorithm.is_none())
+            .and_then(|(_, _, digest)| algorithm_from_length(digest.len()))
+    });
+
+    let algorithm = entries
+        .iter()
+        .find_map(|(algorithm, _, _)| algorithm.or(fallback))
+        .ok_or_else(|| "Unable to determine checksum algorithm".to_string())?;
+
+    let results = task::spawn_blocking(move || {
+        entries
+            .into_iter()
+            .map(|(tagged, file_name, expected)| {
+                let Some(algorithm) = tagged.or(fallback) else {
+                    return VerificationResult {
+                        file_name,
+                        expected,
+                        algorithm: None,
+                        actual: None,
+                        status: VerificationStatus::Error,
+                        error: Some("Unable to determine checksum algorithm".to_string()),
+                    };
+                };
+
+                let invalid = if !is_safe_file_name(&file_name) {
+                    Some("File name points outside the checksum file's directory".to_string())
+                } else if expected.len() != digest_length(algorithm) {
+                    Some(format!(
+                        "Checksum has {} digits instead of {}",
+                        expected.len(),
+                        digest_length(algorithm)
+                    ))
+                } else {
+                    None
+                };
+
+                if let Some(error) = invalid {
+                    return VerificationResult {
+                        file_name,
+                        expected,
+                        algorithm: Some(algorithm),
+                        actual: None,
+                        status: VerificationStatus::Error,
+                        error: Some(error),
+                    };
+                }
+
+                let target = base_dir.join(&file_name);
+
+                if !target.is_file() {
+                    return VerificationResult {
+                        file_name,
+                        expected,
+                        algorithm: Some(algorithm),
+                        actual: None,
+                        status: VerificationStatus::Missing,
+                        error: None,
+                    };
+                }
+
+                match hash_file(&app, &target, &[algorithm]) {
+                    Ok((_, mut digests)) => {
+                        let actual = digests.pop().map(|(_, digest)| digest);
+                        let status = if actual.as_deref() == Some(expected.as_str()) {
+                            VerificationStatus::Ok
+                        } else {
+                            VerificationStatus::Mismatch
+                        };
+
+                        VerificationResult {
+                            file_name,
+                            expected,
+                            algorithm: Some(algorithm),
+                            actual,
+                            status,
+                            error: None,
+                        }
+                    }
+                    Err(e) => VerificationResult {
+                        file_name,
+                        expected,
+                        algorithm: Some(algorithm),
+                        actual: None,
+                        status: VerificationStatus::Error,
+                        error: Some(e),
+                    },
+                }
+            })
+            .collect::<Vec<_>>()
+    })
+    .await
+    .map_err(|e| format!("Failed to verify checksums: {}", e))?;
+
+    let count = |status: VerificationStatus| {
+        results
+            .iter()
+            .filter(|result| result.status == status)
+            .count()
+    };
+
+    Ok(ChecksumVerification {
+        passed: count(VerificationStatus::Ok),
+        failed: count(VerificationStatus::Mismatch) + count(VerificationStatus::Error),
+        missing: count(VerificationStatus::Missing),
+        path,
+        algorithm,
+        results,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_algorithm_is_guessed_from_the_file_suffix() {
+        let cases = [
+            ("SHA256SUMS", Some(ChecksumAlgorithm::Sha256)),
+            ("sha1sums.txt", Some(ChecksumAlgorithm::Sha1)),
+            ("image.iso.md5", Some(ChecksumAlgorithm::Md5)),
+            ("release-MD5SUM", Some(ChecksumAlgorithm::Md5)),
+            ("B3SUMS", Some(ChecksumAlgorithm::Blake3)),
+            ("archive.tar.b3", Some(ChecksumAlgorithm::Blake3)),
+            ("archive.blake3", Some(ChecksumAlgorithm::Blake3)),
+            ("hb3-notes.txt", None),
+            ("b3-roadmap.odt", None),
+            ("sha256-overview.pdf", None),
+            ("checksums", None),
+        ];
+
+        for (name, expected) in cases {
+            assert_eq!(algorithm_from_name(name), expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn gnu_lines_are_parsed() {
+        let digest = "d41d8cd98f00b204e9800998ecf8427e";
+
+        assert_eq!(
+            parse_checksum_line(&format!("{}  file name.txt", digest)),
+            Some((None, "file name.txt".to_string(), digest.to_string()))
+        );
+        assert_eq!(
+            parse_checksum_line(&format!("{} *binary.iso", digest.to_uppercase())),
+            Some((None, "binary.iso".to_string(), digest.to_string()))
+        );
+        assert_eq!(
+            parse_checksum_line(&format!("\\{}  new\\nline\\\\name", digest)),
+            Some((None, "new\nline\\name".to_string(), digest.to_string()))
+        );
+        assert_eq!(parse_checksum_line("not-a-digest  file"), None);
+        assert_eq!(parse_checksum_line(digest), None);
+    }
+
+    #[test]
+    fn tagged_lines_carry_their_own_algorithm() {
+        let digest = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+        assert_eq!(
+            parse_checksum_line(&format!("SHA256 (a (1).txt) = {}", digest)),
+            Some((
+                Some(ChecksumAlgorithm::Sha256),
+                "a (1).txt".to_string(),
+                digest.to_string()
+            ))
+        );
+        assert_eq!(
+            parse_checksum_line(&format!("BLAKE3 (b) = {}", digest)).map(|entry| entry.0),
+            Some(Some(ChecksumAlgorithm::Blake3))
+        );
+        assert_eq!(
+            parse_checksum_line(&format!("CRC32 (c) = {}", digest)).map(|entry| entry.0),
+            Some(None)
+        );
+    }
+
+    #[test]
+    fn escapes_are_undone_in_file_names() {
+        assert_eq!(unescape_file_name("plain"), "plain");
+        assert_eq!(unescape_file_name("a\\nb"), "a\nb");
+        assert_eq!(unescape_file_name("a\\\\nb"), "a\\nb");
+        assert_eq!(unescape_file_name("trailing\\"), "trailing\\");
+        assert_eq!(unescape_file_name("other\\t"), "other\\t");
+    }
+
+    #[test]
+    fn file_names_must_stay_below_the_checksum_file() {
+        for name in ["file", "./file", "dir/file", "dir/./file"] {
+            assert!(is_safe_file_name(name), "{}", name);
+        }
+
+        for name in ["", ".", "..", "../file", "dir/../../file", "/etc/passwd"] {
+            assert!(!is_safe_file_name(name), "{}", name);
+        }
+    }
+}
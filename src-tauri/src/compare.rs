@@ -0,0 +1,652 @@
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::BTreeSet;
+use std::ffi::{OsStr, OsString};
+use std::fs::{File, Metadata};
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+use tokio::task;
+
+use crate::archives;
+use crate::jobs::{self, JobRequest};
+
+/// FAT and some network filesystems only keep two-second timestamps.
+const MTIME_TOLERANCE_SECS: i64 = 2;
+const READ_BUFFER_SIZE: usize = 256 * 1024;
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompareMode {
+    /// Name, size and modification time.
+    Quick,
+    /// Like `Quick`, but same-sized files are compared byte for byte.
+    Content,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompareStatus {
+    OnlyLeft,
+    OnlyRight,
+    Identical,
+    NewerLeft,
+    NewerRight,
+    /// Both sides differ but neither is newer, or one is a file and the
+    /// other a directory.
+    Different,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompareEntry {
+    relative_path: String,
+    status: CompareStatus,
+    is_directory: bool,
+    left_size: Option<u64>,
+    right_size: Option<u64>,
+    left_modified: Option<i64>,
+    right_modified: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectoryComparison {
+    left: String,
+    right: String,
+    mode: CompareMode,
+    entries: Vec<CompareEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompareProgress {
+    left: String,
+    right: String,
+    compared_entries: u64,
+    current_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncDirection {
+    /// Make the right side match the left.
+    LeftToRight,
+    /// Make the left side match the right.
+    RightToLeft,
+    /// Copy missing items both ways and let the newer file win.
+    Bidirectional,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncPlan {
+    left: String,
+    right: String,
+    direction: SyncDirection,
+    entries: Vec<CompareEntry>,
+    delete_extraneous: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncOperation {
+    CopyToRight,
+    CopyToLeft,
+    DeleteLeft,
+    DeleteRight,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncAction {
+    pub(crate) relative_path: String,
+    pub(crate) operation: SyncOperation,
+}
+
+#[cfg(unix)]
+fn name_bytes(name: &OsStr) -> Cow<'_, [u8]> {
+    use std::os::unix::ffi::OsStrExt;
+
+    Cow::Borrowed(name.as_bytes())
+}
+
+#[cfg(not(unix))]
+fn name_bytes(name: &OsStr) -> Cow<'_, [u8]> {
+    match name.to_string_lossy() {
+        Cow::Borrowed(name) => Cow::Borrowed(name.as_bytes()),
+        Cow::Owned(name) => Cow::Owned(name.into_bytes()),
+    }
+}
+
+#[cfg(unix)]
+fn name_from_bytes(bytes: Vec<u8>) -> Option<OsString> {
+    use std::os::unix::ffi::OsStringExt;
+
+    Some(OsString::from_vec(bytes))
+}
+
+#[cfg(not(unix))]
+fn name_from_bytes(bytes: Vec<u8>) -> Option<OsString> {
+    String::from_utf8(bytes).ok().map(OsString::from)
+}
+
+/// Joins the components of `relative` with `/`, escaped the same way as
+/// archive member keys so names that are not UTF-8 survive the trip to the
+/// frontend and back.
+fn encode_relative(relative: &Path) -> String {
+    relative
+        .components()
+        .map(|component| archives::key_component(&name_bytes(component.as_os_str())).into_owned())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn unescape_component(component: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(component.len());
+    let mut rest = component.as_bytes();
+
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte != b'%' {
+            bytes.push(byte);
+            rest = tail;
+            continue;
+        }
+
+        let digits = tail
+            .get(..2)
+            .filter(|digits| digits.iter().all(u8::is_ascii_hexdigit))?;
+        bytes.push(u8::from_str_radix(std::str::from_utf8(digits).ok()?, 16).ok()?);
+        rest = &tail[2..];
+    }
+
+    Some(bytes)
+}
+
+/// Turns a relative path from a comparison back into a path, rejecting any
+/// that would escape the compared directories.
+pub(crate) fn decode_relative(relative: &str) -> Option<PathBuf> {
+    let mut path = PathBuf::new();
+
+    for component in relative.split('/') {
+        let name = name_from_bytes(unescape_component(component)?)?;
+        let mut components = Path::new(&name).components();
+
+        match (components.next(), components.next()) {
+            (Some(Component::Normal(normal)), None) if normal == name => path.push(normal),
+            _ => return None,
+        }
+    }
+
+    Some(path)
+}
+
+fn modified_secs(metadata: &Metadata) -> Option<i64> {
+    metadata
+        .modified()
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|duration| duration.as_secs() as i64)
+}
+
+fn same_content(left: &Path, right: &Path, metadata: &Metadata) -> std::io::Result<bool> {
+    if metadata.is_symlink() {
+        return Ok(std::fs::read_link(left)? == std::fs::read_link(right)?);
+    }
+
+    let (mut left, mut right) = (File::open(left)?, File::open(right)?);
+    let mut left_buffer = vec![0u8; READ_BUFFER_SIZE];
+    let mut right_buffer = vec![0u8; READ_BUFFER_SIZE];
+
+    loop {
+        let read = left.read(&mut left_buffer)?;
+        if read == 0 {
+            return Ok(right.read(&mut right_buffer[..1])? == 0);
+        }
+
+        right.read_exact(&mut right_buffer[..read])?;
+        if left_buffer[..read] != right_buffer[..read] {
+            return Ok(false);
+        }
+    }
+}
+
+fn classify(
+    left: &Path,
+    right: &Path,
+    left_metadata: &Metadata,
+    right_metadata: &Metadata,
+    mode: CompareMode,
+) -> CompareStatus {
+    if left_metadata.is_dir() != right_metadata.is_dir()
+        || left_metadata.is_symlink() != right_metadata.is_symlink()
+    {
+        return CompareStatus::Different;
+    }
+
+    let left_modified = modified_secs(left_metadata).unwrap_or(0);
+    let right_modified = modified_secs(right_metadata).unwrap_or(0);
+    let same_time = (left_modified - right_modified).abs() <= MTIME_TOLERANCE_SECS;
+
+    if left_metadata.len() == right_metadata.len() {
+        let identical = match mode {
+            CompareMode::Quick => same_time,
+            CompareMode::Content => same_content(left, right, left_metadata).unwrap_or(false),
+        };
+
+        if identical {
+            return CompareStatus::Identical;
+        }
+    }
+
+    if same_time {
+        CompareStatus::Different
+    } else if left_modified > right_modified {
+        CompareStatus::NewerLeft
+    } else {
+        CompareStatus::NewerRight
+    }
+}
+
+fn child_names(path: &Path) -> BTreeSet<std::ffi::OsString> {
+    std::fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|entry| entry.file_name()).collect())
+        .unwrap_or_default()
+}
+
+struct Comparer<'a> {
+    /// Where progress is reported; `None` when comparing without a window,
+    /// as in tests.
+    app: Option<&'a AppHandle>,
+    left: &'a Path,
+    right: &'a Path,
+    mode: CompareMode,
+    compared_entries: u64,
+    last_emit: Instant,
+    entries: Vec<CompareEntry>,
+}
+
+impl Comparer<'_> {
+    fn emit_progress(&mut self, current_path: Option<&Path>) {
+        self.last_emit = Instant::now();
+
+        let Some(app) = self.app else {
+            return;
+        };
+
+        let _ = app.emit(
+            "compare-progress",
+            CompareProgress {
+                left: self.left.to_string_lossy().to_string(),
+                right: self.right.to_string_lossy().to_string(),
+                compared_entries: self.compared_entries,
+                current_path: current_path.map(|path| path.to_string_lossy().to_string()),
+            },
+        );
+    }
+
+    fn compare(&mut self, relative: &Path) {
+        let (left_dir, right_dir) = (self.left.join(relative), self.right.join(relative));
+
+        let mut names = child_names(&left_dir);
+        names.extend(child_names(&right_dir));
+
+        for name in names {
+            let relative = relative.join(&name);
+            let (left, right) = (left_dir.join(&name), right_dir.join(&name));
+            let left_metadata = std::fs::symlink_metadata(&left).ok();
+            let right_metadata = std::fs::symlink_metadata(&right).ok();
+
+            self.compared_entries += 1;
+            if self.last_emit.elapsed() >= PROGRESS_INTERVAL {
+                self.emit_progress(Some(&relative));
+            }
+
+            let status = match (&left_metadata, &right_metadata) {
+                (Some(left_metadata), Some(right_metadata))
+                    if left_metadata.is_dir() && right_metadata.is_dir() =>
+                {
+                    self.compare(&relative);
+                    continue;
+                }
+                (Some(left_metadata), Some(right_metadata)) => {
+                    classify(&left, &right, left_metadata, right_metadata, self.mode)
+                }
+                (Some(_), None) => CompareStatus::OnlyLeft,
+                (None, Some(_)) => CompareStatus::OnlyRight,
+                (None, None) => continue,
+            };
+
+            let is_directory = left_metadata
+                .as_ref()
+                .or(right_metadata.as_ref())
+                .is_some_and(Metadata::is_dir);
+
+            self.entries.push(CompareEntry {
+                relative_path: encode_relative(&relative),
+                status,
+                is_directory,
+                left_size: left_metadata.as_ref().map(Metadata::len),
+                right_size: right_metadata.as_ref().map(Metadata::len),
+                left_modified: left_metadata.as_ref().and_then(modified_secs),
+                right_modified: right_metadata.as_ref().and_then(modified_secs),
+            });
+        }
+    }
+}
+
+pub(crate) fn compare_trees(
+    app: Option<&AppHandle>,
+    left: &Path,
+    right: &Path,
+    mode: CompareMode,
+) -> Vec<CompareEntry> {
+    let mut comparer = Comparer {
+        app,
+        left,
+        right,
+        mode,
+        compared_entries: 0,
+        last_emit: Instant::now(),
+        entries: Vec::new(),
+    };
+
+    comparer.compare(Path::new(""));
+    comparer.emit_progress(None);
+    comparer.entries
+}
+
+/// Recursively compares two directory trees. Directories present on both
+/// sides are descended into rather than reported; a directory present on
+/// only one side is reported once as a whole.
+#[tauri::command]
+pub async fn compare_directories(
+    app: AppHandle,
+    left: String,
+    right: String,
+    mode: CompareMode,
+) -> Result<DirectoryComparison, String> {
+    for path in [&left, &right] {
+        if !Path::new(path).is_dir() {
+            return Err(format!("Path is not a directory: {}", path));
+        }
+    }
+
+    let (left_path, right_path) = (PathBuf::from(&left), PathBuf::from(&right));
+
+    let entries =
+        task::spawn_blocking(move || compare_trees(Some(&app), &left_path, &right_path, mode))
+            .await
+            .map_err(|e| format!("Failed to compare directories: {}", e))?;
+
+    Ok(DirectoryComparison {
+        left,
+        right,
+        mode,
+        entries,
+    })
+}
+
+fn plan_operation(
+    status: CompareStatus,
+    direction: SyncDirection,
+    delete_extraneous: bool,
+) -> Option<SyncOperation> {
+    use CompareStatus::*;
+    use SyncDirection::*;
+
+    match (direction, status) {
+        (_, Identical) => None,
+        (LeftToRight, OnlyRight) | (RightToLeft, OnlyLeft) if !delete_extraneous => None,
+        (LeftToRight, OnlyRight) => Some(SyncOperation::DeleteRight),
+        (RightToLeft, OnlyLeft) => Some(SyncOperation::DeleteLeft),
+        (LeftToRight, _) => Some(SyncOperation::CopyToRight),
+        (RightToLeft, _) => Some(SyncOperation::CopyToLeft),
+        (Bidirectional, OnlyLeft | NewerLeft) => Some(SyncOperation::CopyToRight),
+        (Bidirectional, OnlyRight | NewerRight) => Some(SyncOperation::CopyToLeft),
+        (Bidirectional, Different) => None,
+    }
+}
+
+pub(crate) fn plan_actions(
+    entries: &[CompareEntry],
+    direction: SyncDirection,
+    delete_extraneous: bool,
+) -> Vec<SyncAction> {
+    entries
+        .iter()
+        .filter_map(|entry| {
+            plan_operation(entry.status, direction, delete_extraneous).map(|operation| SyncAction {
+                relative_path: entry.relative_path.clone(),
+                operation,
+            })
+        })
+        .collect()
+}
+
+/// Turns the chosen comparison entries into copy and delete actions and
+/// runs them as a background job, returning the job id.
+#[tauri::command]
+pub async fn sync_directories(app: AppHandle, plan: SyncPlan) -> Result<String, String> {
+    let actions = plan_actions(&plan.entries, plan.direction, plan.delete_extraneous);
+
+    if actions.is_empty() {
+        return Err("Nothing to synchronize".to_string());
+    }
+
+    jobs::submit_job(
+        app,
+        JobRequest::Sync {
+            left: plan.left,
+            right: plan.right,
+            actions,
+        },
+    )
+    .await
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    const OLDER: u64 = 1_600_000_000;
+    const NEWER: u64 = OLDER + 3600;
+
+    fn write(path: &Path, contents: &str, modified: u64) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+        File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(UNIX_EPOCH + Duration::from_secs(modified))
+            .unwrap();
+    }
+
+    /// Two trees with one of each kind of difference:
+    /// - `same.txt` is identical on both sides.
+    /// - `changed.txt` has the same size on both sides but is newer on the left.
+    /// - `left.txt` exists only on the left.
+    /// - `right.txt` and `extra/` exist only on the right.
+    /// - `nested/inner.txt` is newer on the right.
+    pub(crate) fn sample_trees() -> (tempfile::TempDir, tempfile::TempDir) {
+        let (left, right) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let (l, r) = (left.path(), right.path());
+
+        write(&l.join("same.txt"), "same", OLDER);
+        write(&r.join("same.txt"), "same", OLDER);
+        write(&l.join("changed.txt"), "left", NEWER);
+        write(&r.join("changed.txt"), "rght", OLDER);
+        write(&l.join("left.txt"), "left only", OLDER);
+        write(&r.join("right.txt"), "right only", OLDER);
+        write(&r.join("extra/file.txt"), "extra", OLDER);
+        write(&l.join("nested/inner.txt"), "old", OLDER);
+        write(&r.join("nested/inner.txt"), "new!", NEWER);
+
+        (left, right)
+    }
+
+    fn statuses(left: &Path, right: &Path, mode: CompareMode) -> BTreeMap<String, CompareStatus> {
+        compare_trees(None, left, right, mode)
+            .into_iter()
+            .map(|entry| (entry.relative_path, entry.status))
+            .collect()
+    }
+
+    fn planned(
+        direction: SyncDirection,
+        delete_extraneous: bool,
+    ) -> BTreeMap<String, SyncOperation> {
+        let (left, right) = sample_trees();
+        let entries = compare_trees(None, left.path(), right.path(), CompareMode::Quick);
+
+        plan_actions(&entries, direction, delete_extraneous)
+            .into_iter()
+            .map(|action| (action.relative_path, action.operation))
+            .collect()
+    }
+
+    fn expected(operations: &[(&str, SyncOperation)]) -> BTreeMap<String, SyncOperation> {
+        operations
+            .iter()
+            .map(|&(path, operation)| (path.to_string(), operation))
+            .collect()
+    }
+
+    #[test]
+    fn trees_are_compared_by_size_and_modification_time() {
+        let (left, right) = sample_trees();
+
+        assert_eq!(
+            statuses(left.path(), right.path(), CompareMode::Quick),
+            BTreeMap::from([
+                ("changed.txt".to_string(), CompareStatus::NewerLeft),
+                ("extra".to_string(), CompareStatus::OnlyRight),
+                ("left.txt".to_string(), CompareStatus::OnlyLeft),
+                ("nested/inner.txt".to_string(), CompareStatus::NewerRight),
+                ("right.txt".to_string(), CompareStatus::OnlyRight),
+                ("same.txt".to_string(), CompareStatus::Identical),
+            ])
+        );
+    }
+
+    #[test]
+    fn content_mode_reads_files_that_look_the_same() {
+        let (left, right) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        write(&left.path().join("a.txt"), "abcd", OLDER);
+        write(&right.path().join("a.txt"), "abce", OLDER);
+        write(&left.path().join("b.txt"), "same", OLDER);
+        write(&right.path().join("b.txt"), "same", NEWER);
+
+        let quick = statuses(left.path(), right.path(), CompareMode::Quick);
+        assert_eq!(quick["a.txt"], CompareStatus::Identical);
+        assert_eq!(quick["b.txt"], CompareStatus::NewerRight);
+
+        let content = statuses(left.path(), right.path(), CompareMode::Content);
+        assert_eq!(content["a.txt"], CompareStatus::Different);
+        assert_eq!(content["b.txt"], CompareStatus::Identical);
+    }
+
+    #[test]
+    fn left_to_right_copies_everything_that_differs() {
+        use SyncOperation::*;
+
+        assert_eq!(
+            planned(SyncDirection::LeftToRight, false),
+            expected(&[
+                ("changed.txt", CopyToRight),
+                ("left.txt", CopyToRight),
+                ("nested/inner.txt", CopyToRight),
+            ])
+        );
+        assert_eq!(
+            planned(SyncDirection::LeftToRight, true),
+            expected(&[
+                ("changed.txt", CopyToRight),
+                ("extra", DeleteRight),
+                ("left.txt", CopyToRight),
+                ("nested/inner.txt", CopyToRight),
+                ("right.txt", DeleteRight),
+            ])
+        );
+    }
+
+    #[test]
+    fn right_to_left_copies_everything_that_differs() {
+        use SyncOperation::*;
+
+        assert_eq!(
+            planned(SyncDirection::RightToLeft, false),
+            expected(&[
+                ("changed.txt", CopyToLeft),
+                ("extra", CopyToLeft),
+                ("nested/inner.txt", CopyToLeft),
+                ("right.txt", CopyToLeft),
+            ])
+        );
+        assert_eq!(
+            planned(SyncDirection::RightToLeft, true),
+            expected(&[
+                ("changed.txt", CopyToLeft),
+                ("extra", CopyToLeft),
+                ("left.txt", DeleteLeft),
+                ("nested/inner.txt", CopyToLeft),
+                ("right.txt", CopyToLeft),
+            ])
+        );
+    }
+
+    #[test]
+    fn bidirectional_syncs_let_the_newer_side_win_and_never_delete() {
+        use SyncOperation::*;
+
+        let operations = expected(&[
+            ("changed.txt", CopyToRight),
+            ("extra", CopyToLeft),
+            ("left.txt", CopyToRight),
+            ("nested/inner.txt", CopyToLeft),
+            ("right.txt", CopyToLeft),
+        ]);
+
+        assert_eq!(planned(SyncDirection::Bidirectional, false), operations);
+        assert_eq!(planned(SyncDirection::Bidirectional, true), operations);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn relative_paths_round_trip_names_that_are_not_utf8() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let names: [&[u8]; 6] = [
+            b"plain.txt",
+            b"100%",
+            b"%41",
+            b"with space",
+            b"caf\xe9",
+            b"\xff%\xfe",
+        ];
+
+        for name in names {
+            let relative = Path::new("dir").join(OsStr::from_bytes(name));
+            let encoded = encode_relative(&relative);
+
+            assert_eq!(decode_relative(&encoded), Some(relative), "{}", encoded);
+        }
+
+        assert_eq!(encode_relative(Path::new("dir/100%")), "dir/100%25");
+        assert_eq!(encode_relative(Path::new("caf\u{e9}")), "caf\u{e9}");
+    }
+
+    #[test]
+    fn relative_paths_cannot_escape() {
+        for relative in [
+            "", ".", "..", "a/../b", "/etc", "a//b", "a/", "%2F", "%2E%2E", "%zz", "%4",
+        ] {
+            assert_eq!(decode_relative(relative), None, "{}", relative);
+        }
+
+        assert_eq!(
+            decode_relative("a/b%20c"),
+            Some(PathBuf::from("a").join("b c"))
+        );
+    }
+}
@@ -0,0 +1,543 @@
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex as StdMutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio::fs;
+use tokio::sync::Mutex;
+use tokio::task;
+
+use crate::app_config_dir;
+use crate::applications::mime_type_of;
+
+/// Output kept from each stream. A command that writes more is stopped so
+/// it cannot flood the UI.
+const MAX_OUTPUT_BYTES: usize = 1024 * 1024;
+const ACTION_TIMEOUT: Duration = Duration::from_secs(300);
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// How long output is still collected after the command exits, for pipes
+/// held open by something it left running in the background.
+const OUTPUT_GRACE: Duration = Duration::from_millis(500);
+
+/// A user-defined shell command offered in the context menu. `command` is
+/// run with `sh -c` after expanding the placeholders `%f` (first selected
+/// path), `%F` (all selected paths), `%d`/`%D` (their directories),
+/// `%n`/`%N` (their file names) and `%%`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomAction {
+    #[serde(default)]
+    id: String,
+    name: String,
+    command: String,
+    #[serde(default)]
+    icon: Option<String>,
+    /// MIME types such as `image/png`, `image/*` or `inode/directory` the
+    /// action applies to. Empty means every item.
+    #[serde(default)]
+    mime_types: Vec<String>,
+    /// Whether the action is offered when several items are selected.
+    #[serde(default)]
+    multiple: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionOutput {
+    exit_code: Option<i32>,
+    success: bool,
+    stdout: String,
+    stderr: String,
+}
+
+fn actions_path() -> Result<PathBuf, String> {
+    Ok(app_config_dir()?.join("custom_actions.json"))
+}
+
+fn get_actions() -> &'static Mutex<Vec<CustomAction>> {
+    static ACTIONS: OnceLock<Mutex<Vec<CustomAction>>> = OnceLock::new();
+
+    ACTIONS.get_or_init(|| {
+        let actions = actions_path()
+            .ok()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str::<Vec<CustomAction>>(&contents).ok())
+            .unwrap_or_default();
+
+        Mutex::new(actions)
+    })
+}
+
+async fn save_actions(actions: &[CustomAction]) -> Result<(), String> {
+    let path = actions_path()?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create configuration directory: {}", e))?;
+    }
+
+    let contents = serde_json::to_string_pretty(actions)
+        .map_err(|e| format!("Failed to serialize custom actions: {}", e))?;
+
+    // Written beside the real file and renamed over it, so a crash cannot
+    // leave it truncated.
+    let temporary = path.with_extension("json.tmp");
+
+    fs::write(&temporary, contents)
+        .await
+        .map_err(|e| format!("Failed to write custom actions: {}", e))?;
+
+    fs::rename(&temporary, &path)
+        .await
+        .map_err(|e| format!("Failed to write custom actions: {}", e))
+}
+
+#[tauri::command]
+pub async fn list_custom_actions() -> Result<Vec<CustomAction>, String> {
+    Ok(get_actions().lock().await.clone())
+}
+
+/// Adds `action`, or replaces the existing action with the same ID. New
+/// actions are given an ID when they have none.
+#[tauri::command]
+pub async fn save_custom_action(mut action: CustomAction) -> Result<Vec<CustomAction>, String> {
+    action.name = action.name.trim().to_string();
+    action.command = action.command.trim().to_string();
+
+    if action.name.is_empty() {
+        return Err("Action name cannot be empty".to_string());
+    }
+
+    if action.command.is_empty() {
+        return Err("Action command cannot be empty".to_string());
+    }
+
+    let mut actions = get_actions().lock().await;
+
+    if action.id.is_empty() {
+        action.id = uuid::Uuid::new_v4().to_string();
+        actions.push(action);
+    } else {
+        let existing = actions
+            .iter_mut()
+            .find(|existing| existing.id == action.id)
+            .ok_or_else(|| format!("Custom action does not exist: {}", action.id))?;
+        *existing = action;
+    }
+
+    save_actions(&actions).await?;
+    Ok(actions.clone())
+}
+
+#[tauri::command]
+pub async fn delete_custom_action(id: String) -> Result<Vec<CustomAction>, String> {
+    let mut actions = get_actions().lock().await;
+
+    let count = actions.len();
+    actions.retain(|action| action.id != id);
+
+    if actions.len() == count {
+        return Err(format!("Custom action does not exist: {}", id));
+    }
+
+    save_actions(&actions).await?;
+    Ok(actions.clone())
+}
+
+fn matches_mime_type(patterns: &[String], mime_type: &str) -> bool {
+    patterns.is_empty()
+        || patterns.iter().any(|pattern| {
+            pattern == "*"
+                || pattern == mime_type
+                || pattern
+                    .strip_suffix("/*")
+                    .is_some_and(|media| mime_type.split('/').next() == Some(media))
+        })
+}
+
+/// Lists the actions applicable to the selected `paths`, for building the
+/// context menu.
+#[tauri::command]
+pub async fn actions_for_paths(paths: Vec<String>) -> Result<Vec<CustomAction>, String> {
+    if paths.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut mime_types = Vec::with_capacity(paths.len());
+    for path in &paths {
+        mime_types.push(mime_type_of(Path::new(path)).await);
+    }
+
+    let actions = get_actions().lock().await;
+
+    Ok(actions
+        .iter()
+        .filter(|action| action.multiple || paths.len() == 1)
+        .filter(|action| {
+            mime_types
+                .iter()
+                .all(|mime_type| matches_mime_type(&action.mime_types, mime_type))
+        })
+        .cloned()
+        .collect())
+}
+
+/// Single-quotes `value` for `sh`, closing the quote around embedded ones.
+fn shell_quote(value: &[u8], script: &mut Vec<u8>) {
+    script.push(b'\'');
+    for &byte in value {
+        match byte {
+            b'\'' => script.extend_from_slice(b"'\\''"),
+            _ => script.push(byte),
+        }
+    }
+    script.push(b'\'');
+}
+
+fn quote_all<'a>(values: impl Iterator<Item = &'a Path>, script: &mut Vec<u8>) {
+    for (index, value) in values.enumerate() {
+        if index > 0 {
+            script.push(b' ');
+        }
+        shell_quote(value.as_os_str().as_encoded_bytes(), script);
+    }
+}
+
+fn parent_of(path: &Path) -> &Path {
+    path.parent().unwrap_or(Path::new("/"))
+}
+
+fn name_of(path: &Path) -> &Path {
+    path.file_name().map(Path::new).unwrap_or(path)
+}
+
+/// Substitutes the placeholders in `template` with shell-quoted values.
+/// Unknown placeholders are left as they are.
+fn expand_command(template: &str, paths: &[PathBuf]) -> OsString {
+    let mut script = Vec::with_capacity(template.len());
+    let mut chars = template.chars();
+    let first = &paths[..1];
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            let mut buffer = [0; 4];
+            script.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+            continue;
+        }
+
+        match chars.next() {
+            Some('f') => quote_all(first.iter().map(PathBuf::as_path), &mut script),
+            Some('F') => quote_all(paths.iter().map(PathBuf::as_path), &mut script),
+            Some('d') => quote_all(first.iter().map(|path| parent_of(path)), &mut script),
+            Some('D') => quote_all(paths.iter().map(|path| parent_of(path)), &mut script),
+            Some('n') => quote_all(first.iter().map(|path| name_of(path)), &mut script),
+            Some('N') => quote_all(paths.iter().map(|path| name_of(path)), &mut script),
+            Some('%') => script.push(b'%'),
+            Some(other) => {
+                script.push(b'%');
+                let mut buffer = [0; 4];
+                script.extend_from_slice(other.encode_utf8(&mut buffer).as_bytes());
+            }
+            None => script.push(b'%'),
+        }
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStringExt;
+        OsString::from_vec(script)
+    }
+
+    #[cfg(not(unix))]
+    {
+        OsString::from(String::from_utf8_lossy(&script).to_string())
+    }
+}
+
+/// Output read from one of a command's pipes on a separate thread.
+struct Capture {
+    output: Arc<StdMutex<Vec<u8>>>,
+    done: mpsc::Receiver<()>,
+}
+
+impl Capture {
+    /// Reads `pipe` until it closes or `MAX_OUTPUT_BYTES` were read, raising
+    /// `exceeded` in the latter case.
+    fn start(mut pipe: impl Read + Send + 'static, exceeded: Arc<AtomicBool>) -> Self {
+        let output = Arc::new(StdMutex::new(Vec::new()));
+        let (sender, done) = mpsc::channel();
+        let buffer = output.clone();
+
+        thread::spawn(move || {
+            let mut chunk = [0; 8192];
+
+            while let Ok(read) = pipe.read(&mut chunk) {
+                if read == 0 {
+                    break;
+                }
+
+                let mut buffer = buffer.lock().unwrap();
+                let room = MAX_OUTPUT_BYTES - buffer.len();
+                buffer.extend_from_slice(&chunk[..read.min(room)]);
+
+                if read > room {
+                    exceeded.store(true, Ordering::Relaxed);
+                    break;
+                }
+            }
+
+            let _ = sender.send(());
+        });
+
+        Self { output, done }
+    }
+
+    fn finish(self, deadline: Instant) -> String {
+        let _ = self
+            .done
+            .recv_timeout(deadline.saturating_duration_since(Instant::now()));
+        let output = std::mem::take(&mut *self.output.lock().unwrap());
+        String::from_utf8_lossy(&output).to_string()
+    }
+}
+
+/// Kills a runaway command together with anything it started.
+fn terminate(child: &mut Child) {
+    #[cfg(unix)]
+    unsafe {
+        libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+    }
+
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+fn run_command(script: OsString, directory: &Path) -> Result<ActionOutput, String> {
+    let mut command = Command::new("sh");
+    command
+        .arg("-c")
+        .arg(script)
+        .current_dir(directory)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    // Its own process group, so a runaway command can be stopped along
+    // with its children.
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("Failed to run custom action: {}", e))?;
+
+    let exceeded = Arc::new(AtomicBool::new(false));
+    let (Some(stdout), Some(stderr)) = (child.stdout.take(), child.stderr.take()) else {
+        terminate(&mut child);
+        return Err("Failed to capture custom action output".to_string());
+    };
+    let stdout = Capture::start(stdout, exceeded.clone());
+    let stderr = Capture::start(stderr, exceeded.clone());
+
+    let started = Instant::now();
+
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) => {}
+            Err(e) => {
+                terminate(&mut child);
+                return Err(format!("Failed to run custom action: {}", e));
+            }
+        }
+
+        if exceeded.load(Ordering::Relaxed) {
+            terminate(&mut child);
+            break None;
+        }
+
+        if started.elapsed() >= ACTION_TIMEOUT {
+            terminate(&mut child);
+            return Err(format!(
+                "Custom action stopped after running for {} seconds",
+                ACTION_TIMEOUT.as_secs()
+            ));
+        }
+
+        thread::sleep(POLL_INTERVAL);
+    };
+
+    // A command can also end because output stopped being read.
+    let status = match status {
+        Some(status) if !exceeded.load(Ordering::Relaxed) => status,
+        _ => {
+            return Err(format!(
+                "Custom action stopped after writing more than {} bytes of output",
+                MAX_OUTPUT_BYTES
+            ))
+        }
+    };
+
+    let deadline = Instant::now() + OUTPUT_GRACE;
+
+    Ok(ActionOutput {
+        exit_code: status.code(),
+        success: status.success(),
+        stdout: stdout.finish(deadline),
+        stderr: stderr.finish(deadline),
+    })
+}
+
+/// Runs the custom action `id` on `paths` from the first item's directory
+/// and waits for it, returning its output and exit status. Actions that run
+/// too long or write too much output are stopped.
+#[tauri::command]
+pub async fn run_custom_action(id: String, paths: Vec<String>) -> Result<ActionOutput, String> {
+    let action = get_actions()
+        .lock()
+        .await
+        .iter()
+        .find(|action| action.id == id)
+        .cloned()
+        .ok_or_else(|| format!("Custom action does not exist: {}", id))?;
+
+    if paths.is_empty() {
+        return Err("No items selected".to_string());
+    }
+
+    if paths.len() > 1 && !action.multiple {
+        return Err(format!(
+            "Custom action only accepts a single item: {}",
+            action.name
+        ));
+    }
+
+    let paths = paths.into_iter().map(PathBuf::from).collect::<Vec<_>>();
+    for path in &paths {
+        if fs::symlink_metadata(path).await.is_err() {
+            return Err(format!("Item does not exist: {}", path.display()));
+        }
+
+        if !matches_mime_type(&action.mime_types, &mime_type_of(path).await) {
+            return Err(format!(
+                "Custom action {} does not apply to: {}",
+                action.name,
+                path.display()
+            ));
+        }
+    }
+
+    task::spawn_blocking(move || {
+        run_command(
+            expand_command(&action.command, &paths),
+            parent_of(&paths[0]),
+        )
+    })
+    .await
+    .map_err(|e| format!("Failed to run custom action: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(names: &[&str]) -> Vec<PathBuf> {
+        names.iter().map(PathBuf::from).collect()
+    }
+
+    /// Runs the expanded `template` and returns the words the shell passed
+    /// to `printf`.
+    #[cfg(unix)]
+    fn shell_words(template: &str, paths: &[PathBuf], directory: &Path) -> Vec<Vec<u8>> {
+        let output = Command::new("sh")
+            .arg("-c")
+            .arg(expand_command(template, paths))
+            .current_dir(directory)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+
+        output
+            .stdout
+            .split(|&byte| byte == 0)
+            .filter(|word| !word.is_empty())
+            .map(<[u8]>::to_vec)
+            .collect()
+    }
+
+    #[test]
+    fn placeholders_are_expanded_and_quoted() {
+        let selected = paths(&["/srv/it's here/a.txt", "/tmp/b"]);
+
+        assert_eq!(
+            expand_command("open %f", &selected),
+            OsString::from(r"open '/srv/it'\''s here/a.txt'")
+        );
+        assert_eq!(
+            expand_command("ls %D; echo %N", &selected),
+            OsString::from(r"ls '/srv/it'\''s here' '/tmp'; echo 'a.txt' 'b'")
+        );
+        assert_eq!(
+            expand_command("%d %n 100%% %x %", &selected),
+            OsString::from(r"'/srv/it'\''s here' 'a.txt' 100% %x %")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hostile_names_reach_the_command_unchanged() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let names: [&[u8]; 7] = [
+            b"it's",
+            b"$(touch pwned)",
+            b"`touch pwned`",
+            b"line\nbreak; touch pwned",
+            b"'; touch pwned; '",
+            b"%f %F",
+            b"caf\xe9",
+        ];
+        let selected = names
+            .iter()
+            .map(|name| dir.path().join(std::ffi::OsStr::from_bytes(name)))
+            .collect::<Vec<_>>();
+
+        let words = shell_words(r"printf '%s\0' %F", &selected, dir.path());
+
+        assert_eq!(
+            words,
+            selected
+                .iter()
+                .map(|path| path.as_os_str().as_bytes().to_vec())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            shell_words(r"printf '%s\0' %N", &selected, dir.path()),
+            names.map(<[u8]>::to_vec)
+        );
+        assert!(!dir.path().join("pwned").exists());
+    }
+
+    #[test]
+    fn actions_apply_to_matching_mime_types() {
+        let patterns =
+            |patterns: &[&str]| patterns.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+
+        assert!(matches_mime_type(&[], "text/plain"));
+        assert!(matches_mime_type(&patterns(&["*"]), "inode/directory"));
+        assert!(matches_mime_type(&patterns(&["image/*"]), "image/png"));
+        assert!(matches_mime_type(
+            &patterns(&["text/plain", "image/png"]),
+            "image/png"
+        ));
+        assert!(!matches_mime_type(&patterns(&["image/*"]), "imagex/png"));
+        assert!(!matches_mime_type(&patterns(&["image/png"]), "image/jpeg"));
+    }
+}
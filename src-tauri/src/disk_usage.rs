@@ -0,0 +1,323 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio::task;
+
+const CACHE_TTL: Duration = Duration::from_secs(300);
+const MAX_CACHED_TREES: usize = 8;
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+const DEFAULT_DEPTH: usize = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskUsageNode {
+    name: String,
+    path: String,
+    is_directory: bool,
+    apparent_size: u64,
+    disk_usage: u64,
+    file_count: u64,
+    directory_count: u64,
+    errors: u64,
+    children: Vec<DiskUsageNode>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskUsageProgress {
+    path: String,
+    current_path: Option<String>,
+    apparent_size: u64,
+    disk_usage: u64,
+    file_count: u64,
+    directory_count: u64,
+    /// A top-level child whose totals just became final.
+    completed: Option<DiskUsageNode>,
+}
+
+#[derive(Debug, Default)]
+struct UsageNode {
+    name: OsString,
+    is_directory: bool,
+    apparent_size: u64,
+    disk_usage: u64,
+    file_count: u64,
+    directory_count: u64,
+    errors: u64,
+    children: Vec<UsageNode>,
+}
+
+impl UsageNode {
+    fn add(&mut self, child: UsageNode) {
+        self.apparent_size += child.apparent_size;
+        self.disk_usage += child.disk_usage;
+        self.file_count += child.file_count;
+        self.directory_count += child.directory_count;
+        self.errors += child.errors;
+        self.children.push(child);
+    }
+
+    fn find(&self, relative: &Path) -> Option<&UsageNode> {
+        relative.iter().try_fold(self, |node, name| {
+            node.children.iter().find(|child| child.name == name)
+        })
+    }
+
+    /// Copies the node for the frontend, keeping `depth` levels of children.
+    fn to_node(&self, path: &Path, depth: usize) -> DiskUsageNode {
+        DiskUsageNode {
+            name: path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.to_string_lossy().to_string()),
+            path: path.to_string_lossy().to_string(),
+            is_directory: self.is_directory,
+            apparent_size: self.apparent_size,
+            disk_usage: self.disk_usage,
+            file_count: self.file_count,
+            directory_count: self.directory_count,
+            errors: self.errors,
+            children: if depth == 0 {
+                Vec::new()
+            } else {
+                self.children
+                    .iter()
+                    .map(|child| child.to_node(&path.join(&child.name), depth - 1))
+                    .collect()
+            },
+        }
+    }
+}
+
+struct CachedTree {
+    root: PathBuf,
+    cross_filesystems: bool,
+    timestamp: Instant,
+    tree: Arc<UsageNode>,
+}
+
+fn get_usage_cache() -> &'static Mutex<Vec<CachedTree>> {
+    static CACHE: OnceLock<Mutex<Vec<CachedTree>>> = OnceLock::new();
+
+    CACHE.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+/// Finds a fresh cached scan covering `path`, so drilling into a
+/// subdirectory of an analyzed tree doesn't walk the disk again.
+fn cached_tree(path: &Path, cross_filesystems: bool) -> Option<(Arc<UsageNode>, PathBuf)> {
+    let mut cache = get_usage_cache().lock().unwrap();
+    cache.retain(|cached| cached.timestamp.elapsed() < CACHE_TTL);
+
+    cache
+        .iter()
+        .filter(|cached| cached.cross_filesystems == cross_filesystems)
+        .find_map(|cached| {
+            let relative = path.strip_prefix(&cached.root).ok()?;
+            cached.tree.find(relative)?;
+            Some((cached.tree.clone(), relative.to_path_buf()))
+        })
+}
+
+fn store_tree(root: &Path, cross_filesystems: bool, tree: Arc<UsageNode>) {
+    let mut cache = get_usage_cache().lock().unwrap();
+
+    cache.retain(|cached| {
+        !(cached.cross_filesystems == cross_filesystems
+            && (cached.root.starts_with(root) || root.starts_with(&cached.root)))
+    });
+
+    if cache.len() >= MAX_CACHED_TREES {
+        cache.remove(0);
+    }
+
+    cache.push(CachedTree {
+        root: root.to_path_buf(),
+        cross_filesystems,
+        timestamp: Instant::now(),
+        tree,
+    });
+}
+
+#[cfg(unix)]
+fn usage_of(metadata: &std::fs::Metadata) -> (u64, u64, Option<(u64, u64)>) {
+    use std::os::unix::fs::MetadataExt;
+
+    let inode =
+        (metadata.nlink() > 1 && !metadata.is_dir()).then(|| (metadata.dev(), metadata.ino()));
+    (metadata.dev(), metadata.blocks() * 512, inode)
+}
+
+#[cfg(not(unix))]
+fn usage_of(metadata: &std::fs::Metadata) -> (u64, u64, Option<(u64, u64)>) {
+    (0, metadata.len(), None)
+}
+
+struct Scanner<'a> {
+    app: &'a AppHandle,
+    root: &'a Path,
+    device: u64,
+    cross_filesystems: bool,
+    seen_inodes: HashSet<(u64, u64)>,
+    totals: UsageNode,
+    last_emit: Instant,
+}
+
+impl Scanner<'_> {
+    fn emit_progress(&mut self, current_path: Option<&Path>, completed: Option<DiskUsageNode>) {
+        self.last_emit = Instant::now();
+
+        let _ = self.app.emit(
+            "disk-usage-progress",
+            DiskUsageProgress {
+                path: self.root.to_string_lossy().to_string(),
+                current_path: current_path.map(|path| path.to_string_lossy().to_string()),
+                apparent_size: self.totals.apparent_size,
+                disk_usage: self.totals.disk_usage,
+                file_count: self.totals.file_count,
+                directory_count: self.totals.directory_count,
+                completed,
+            },
+        );
+    }
+
+    fn scan(&mut self, path: &Path, depth: usize) -> UsageNode {
+        let mut node = UsageNode {
+            name: path.file_name().map(OsString::from).unwrap_or_default(),
+            ..Default::default()
+        };
+
+        let metadata = match std::fs::symlink_metadata(path) {
+            Ok(metadata) => metadata,
+            Err(_) => {
+                node.errors = 1;
+                return node;
+            }
+        };
+
+        let (device, disk_usage, inode) = usage_of(&metadata);
+
+        if inode.is_some_and(|inode| !self.seen_inodes.insert(inode)) {
+            node.file_count = 1;
+            self.totals.file_count += 1;
+            return node;
+        }
+
+        node.is_directory = metadata.is_dir();
+        node.apparent_size = metadata.len();
+        node.disk_usage = disk_usage;
+
+        self.totals.apparent_size += node.apparent_size;
+        self.totals.disk_usage += node.disk_usage;
+
+        if !node.is_directory {
+            node.file_count = 1;
+            self.totals.file_count += 1;
+            return node;
+        }
+
+        node.directory_count = 1;
+        self.totals.directory_count += 1;
+
+        if depth > 0 && device != self.device && !self.cross_filesystems {
+            return node;
+        }
+
+        if self.last_emit.elapsed() >= PROGRESS_INTERVAL {
+            self.emit_progress(Some(path), None);
+        }
+
+        let entries = match std::fs::read_dir(path) {
+            Ok(entries) => entries,
+            Err(_) => {
+                node.errors += 1;
+                return node;
+            }
+        };
+
+        for entry in entries {
+            let Ok(entry) = entry else {
+                node.errors += 1;
+                continue;
+            };
+
+            let child = self.scan(&entry.path(), depth + 1);
+
+            if depth == 0 {
+                let completed = child.to_node(&entry.path(), 0);
+                self.emit_progress(Some(path), Some(completed));
+            }
+
+            node.add(child);
+        }
+
+        node.children
+            .sort_by(|a, b| b.disk_usage.cmp(&a.disk_usage).then(a.name.cmp(&b.name)));
+
+        node
+    }
+}
+
+fn analyze_blocking(
+    app: &AppHandle,
+    root: &Path,
+    cross_filesystems: bool,
+) -> Result<UsageNode, String> {
+    let metadata =
+        std::fs::metadata(root).map_err(|e| format!("Failed to read metadata: {}", e))?;
+
+    if !metadata.is_dir() {
+        return Err("Path is not a directory".to_string());
+    }
+
+    let mut scanner = Scanner {
+        app,
+        root,
+        device: usage_of(&metadata).0,
+        cross_filesystems,
+        seen_inodes: HashSet::new(),
+        totals: UsageNode::default(),
+        last_emit: Instant::now(),
+    };
+
+    let tree = scanner.scan(root, 0);
+    scanner.emit_progress(None, None);
+
+    Ok(tree)
+}
+
+/// Summarizes disk usage below `path`. Results are cached per scanned root,
+/// so asking for a subdirectory of a recent scan is answered from memory
+/// unless `refresh` is set.
+#[tauri::command]
+pub async fn analyze_disk_usage(
+    app: AppHandle,
+    path: String,
+    depth: Option<usize>,
+    cross_filesystems: Option<bool>,
+    refresh: Option<bool>,
+) -> Result<DiskUsageNode, String> {
+    let root = PathBuf::from(&path);
+    let depth = depth.unwrap_or(DEFAULT_DEPTH);
+    let cross_filesystems = cross_filesystems.unwrap_or(false);
+
+    if !refresh.unwrap_or(false) {
+        if let Some((tree, relative)) = cached_tree(&root, cross_filesystems) {
+            if let Some(node) = tree.find(&relative) {
+                return Ok(node.to_node(&root, depth));
+            }
+        }
+    }
+
+    let scan_root = root.clone();
+    let tree = task::spawn_blocking(move || analyze_blocking(&app, &scan_root, cross_filesystems))
+        .await
+        .map_err(|e| format!("Failed to analyze disk usage: {}", e))??;
+
+    let tree = Arc::new(tree);
+    let node = tree.to_node(&root, depth);
+    store_tree(&root, cross_filesystems, tree);
+
+    Ok(node)
+}
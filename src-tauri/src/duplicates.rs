@@ -0,0 +1,290 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio::task;
+
+const PARTIAL_BLOCK_SIZE: u64 = 4096;
+const READ_BUFFER_SIZE: usize = 1024 * 1024;
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicatePhase {
+    Scanning,
+    PartialHash,
+    FullHash,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateProgress {
+    phase: DuplicatePhase,
+    processed_files: u64,
+    total_files: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateGroup {
+    size: u64,
+    hash: String,
+    wasted_bytes: u64,
+    paths: Vec<String>,
+}
+
+struct Candidate {
+    path: PathBuf,
+    inode: Option<(u64, u64)>,
+}
+
+#[cfg(unix)]
+fn inode_of(metadata: &std::fs::Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn inode_of(_metadata: &std::fs::Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+struct Finder<'a> {
+    app: &'a AppHandle,
+    phase: DuplicatePhase,
+    processed_files: u64,
+    total_files: u64,
+    last_emit: Instant,
+}
+
+impl Finder<'_> {
+    fn start_phase(&mut self, phase: DuplicatePhase, total_files: u64) {
+        self.phase = phase;
+        self.processed_files = 0;
+        self.total_files = total_files;
+        self.emit_progress(true);
+    }
+
+    fn advance(&mut self) {
+        self.processed_files += 1;
+        self.emit_progress(false);
+    }
+
+    fn emit_progress(&mut self, force: bool) {
+        if !force && self.last_emit.elapsed() < PROGRESS_INTERVAL {
+            return;
+        }
+        self.last_emit = Instant::now();
+
+        let _ = self.app.emit(
+            "duplicate-progress",
+            DuplicateProgress {
+                phase: self.phase,
+                processed_files: self.processed_files,
+                total_files: self.total_files,
+            },
+        );
+    }
+
+    fn collect(
+        &mut self,
+        path: &Path,
+        min_size: u64,
+        visited: &mut HashSet<PathBuf>,
+        by_size: &mut BTreeMap<u64, Vec<Candidate>>,
+    ) {
+        let Ok(metadata) = std::fs::symlink_metadata(path) else {
+            return;
+        };
+
+        if metadata.is_dir() {
+            let Ok(entries) = std::fs::read_dir(path) else {
+                return;
+            };
+
+            for entry in entries.flatten() {
+                self.collect(&entry.path(), min_size, visited, by_size);
+            }
+        } else if metadata.is_file() && metadata.len() >= min_size && visited.insert(path.into()) {
+            self.total_files += 1;
+            self.emit_progress(false);
+
+            by_size.entry(metadata.len()).or_default().push(Candidate {
+                path: path.to_path_buf(),
+                inode: inode_of(&metadata),
+            });
+        }
+    }
+}
+
+/// Hashes the first and last blocks, which is enough to split most
+/// same-sized files apart without reading them whole.
+fn partial_hash(path: &Path, size: u64) -> std::io::Result<blake3::Hash> {
+    let mut file = File::open(path)?;
+    let mut block = Vec::with_capacity(PARTIAL_BLOCK_SIZE as usize);
+
+    if size > PARTIAL_BLOCK_SIZE * 2 {
+        (&mut file)
+            .take(PARTIAL_BLOCK_SIZE)
+            .read_to_end(&mut block)?;
+        file.seek(SeekFrom::End(-(PARTIAL_BLOCK_SIZE as i64)))?;
+    }
+    file.read_to_end(&mut block)?;
+
+    Ok(blake3::hash(&block))
+}
+
+fn full_hash(path: &Path) -> std::io::Result<blake3::Hash> {
+    let mut file = File::open(path)?;
+    let mut hasher = blake3::Hasher::new();
+    let mut buffer = vec![0u8; READ_BUFFER_SIZE];
+
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(hasher.finalize())
+}
+
+fn group_by<K: std::hash::Hash + Eq>(
+    finder: &mut Finder,
+    candidates: Vec<Candidate>,
+    key: impl Fn(&Path) -> std::io::Result<K>,
+) -> Vec<(K, Vec<Candidate>)> {
+    let mut groups: HashMap<K, Vec<Candidate>> = HashMap::new();
+
+    for candidate in candidates {
+        if let Ok(key) = key(&candidate.path) {
+            groups.entry(key).or_default().push(candidate);
+        }
+        finder.advance();
+    }
+
+    groups
+        .into_iter()
+        .filter(|(_, group)| group.len() > 1)
+        .collect()
+}
+
+fn find_blocking(
+    app: &AppHandle,
+    roots: &[String],
+    min_size: u64,
+    ignore_hard_links: bool,
+) -> Vec<DuplicateGroup> {
+    let mut finder = Finder {
+        app,
+        phase: DuplicatePhase::Scanning,
+        processed_files: 0,
+        total_files: 0,
+        last_emit: Instant::now(),
+    };
+
+    let mut visited = HashSet::new();
+    let mut by_size = BTreeMap::new();
+
+    for root in roots {
+        finder.collect(Path::new(root), min_size, &mut visited, &mut by_size);
+    }
+
+    let buckets = by_size
+        .into_iter()
+        .rev()
+        .map(|(size, mut candidates)| {
+            if ignore_hard_links {
+                let mut inodes = HashSet::new();
+                candidates.retain(|candidate| match candidate.inode {
+                    Some(inode) => inodes.insert(inode),
+                    None => true,
+                });
+            }
+            (size, candidates)
+        })
+        .filter(|(_, candidates)| candidates.len() > 1)
+        .collect::<Vec<_>>();
+
+    let partial_total = buckets.iter().map(|(_, group)| group.len() as u64).sum();
+    finder.start_phase(DuplicatePhase::PartialHash, partial_total);
+
+    let mut partial_groups = Vec::new();
+    for (size, candidates) in buckets {
+        for (hash, group) in group_by(&mut finder, candidates, |path| partial_hash(path, size)) {
+            partial_groups.push((size, hash, group));
+        }
+    }
+
+    let full_total = partial_groups
+        .iter()
+        .filter(|(size, _, _)| *size > PARTIAL_BLOCK_SIZE * 2)
+        .map(|(_, _, group)| group.len() as u64)
+        .sum();
+    finder.start_phase(DuplicatePhase::FullHash, full_total);
+
+    let mut duplicates = Vec::new();
+
+    for (size, partial, candidates) in partial_groups {
+        // Small files were read whole by the partial hash already.
+        let confirmed = if size > PARTIAL_BLOCK_SIZE * 2 {
+            group_by(&mut finder, candidates, full_hash)
+        } else {
+            vec![(partial, candidates)]
+        };
+
+        for (hash, group) in confirmed {
+            let mut paths = group
+                .into_iter()
+                .map(|candidate| candidate.path.to_string_lossy().to_string())
+                .collect::<Vec<_>>();
+            paths.sort();
+
+            let duplicate = DuplicateGroup {
+                size,
+                hash: hash.to_hex().to_string(),
+                wasted_bytes: size * (paths.len() as u64 - 1),
+                paths,
+            };
+
+            let _ = app.emit("duplicate-group", &duplicate);
+            duplicates.push(duplicate);
+        }
+    }
+
+    finder.emit_progress(true);
+
+    duplicates.sort_by_key(|duplicate| std::cmp::Reverse(duplicate.wasted_bytes));
+    duplicates
+}
+
+/// Finds groups of identical files below `roots`. Each group is emitted as a
+/// `duplicate-group` event as soon as it is confirmed; the full list, largest
+/// savings first, is returned at the end.
+#[tauri::command]
+pub async fn find_duplicates(
+    app: AppHandle,
+    roots: Vec<String>,
+    min_size: Option<u64>,
+    ignore_hard_links: Option<bool>,
+) -> Result<Vec<DuplicateGroup>, String> {
+    if roots.is_empty() {
+        return Err("No folders to search".to_string());
+    }
+
+    for root in &roots {
+        if !Path::new(root).exists() {
+            return Err(format!("Item does not exist: {}", root));
+        }
+    }
+
+    let min_size = min_size.unwrap_or(1).max(1);
+    let ignore_hard_links = ignore_hard_links.unwrap_or(true);
+
+    task::spawn_blocking(move || find_blocking(&app, &roots, min_size, ignore_hard_links))
+        .await
+        .map_err(|e| format!("Failed to find duplicates: {}", e))
+}
@@ -0,0 +1,472 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tokio::fs;
+use tokio::io::AsyncReadExt;
+
+const SNIFF_BYTES: u64 = 512;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileCategory {
+    Image,
+    Video,
+    Audio,
+    Document,
+    Archive,
+    Code,
+    Executable,
+    Other,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileTypeInfo {
+    path: String,
+    mime_type: Option<String>,
+    detected_mime_type: Option<String>,
+    category: FileCategory,
+    mismatch: bool,
+}
+
+const ARCHIVE_TYPES: &[&str] = &[
+    "application/zip",
+    "application/gzip",
+    "application/x-gzip",
+    "application/x-tar",
+    "application/x-xz",
+    "application/zstd",
+    "application/x-bzip2",
+    "application/x-7z-compressed",
+    "application/vnd.rar",
+    "application/x-rar-compressed",
+    "application/java-archive",
+    "application/vnd.debian.binary-package",
+    "application/x-rpm",
+];
+
+const DOCUMENT_TYPES: &[&str] = &[
+    "application/pdf",
+    "application/rtf",
+    "application/msword",
+    "application/vnd.ms-excel",
+    "application/vnd.ms-powerpoint",
+    "application/epub+zip",
+    "application/postscript",
+    "text/plain",
+    "text/markdown",
+    "text/csv",
+];
+
+const ZIP_CONTAINER_TYPES: &[&str] = &[
+    "application/epub+zip",
+    "application/java-archive",
+    "application/vnd.android.package-archive",
+];
+
+const GZIP_CONTAINER_TYPES: &[&str] = &[
+    "application/x-gzip",
+    "application/x-compressed",
+    "application/x-compressed-tar",
+];
+
+const CODE_EXTENSIONS: &[&str] = &[
+    "rs", "py", "js", "jsx", "ts", "tsx", "c", "h", "cc", "cpp", "hpp", "cs", "go", "java", "kt",
+    "rb", "php", "swift", "sh", "bash", "zsh", "fish", "lua", "pl", "html", "css", "scss", "json",
+    "toml", "yaml", "yml", "xml", "sql", "vue", "svelte",
+];
+
+pub(crate) fn mime_from_extension(path: &Path) -> Option<String> {
+    mime_guess::from_path(path).first_raw().map(str::to_string)
+}
+
+pub(crate) fn category_for(
+    mime_type: Option<&str>,
+    path: &Path,
+    mode: Option<u32>,
+) -> FileCategory {
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    if CODE_EXTENSIONS.contains(&extension.as_str()) {
+        return FileCategory::Code;
+    }
+
+    let Some(mime_type) = mime_type else {
+        return match mode {
+            Some(mode) if mode & 0o111 != 0 => FileCategory::Executable,
+            _ => FileCategory::Other,
+        };
+    };
+
+    if ARCHIVE_TYPES.contains(&mime_type) {
+        return FileCategory::Archive;
+    }
+
+    if DOCUMENT_TYPES.contains(&mime_type)
+        || mime_type.starts_with("application/vnd.openxmlformats-officedocument")
+        || mime_type.starts_with("application/vnd.oasis.opendocument")
+    {
+        return FileCategory::Document;
+    }
+
+    match mime_type.split('/').next() {
+        Some("image") => FileCategory::Image,
+        Some("video") => FileCategory::Video,
+        Some("audio") => FileCategory::Audio,
+        Some("text") => FileCategory::Code,
+        _ if mime_type.contains("executable")
+            || mime_type.contains("sharedlib")
+            || mime_type == "application/x-msdownload"
+            || mime_type == "application/x-mach-binary" =>
+        {
+            FileCategory::Executable
+        }
+        _ if mime_type.contains("javascript") || mime_type.contains("json") => FileCategory::Code,
+        _ => FileCategory::Other,
+    }
+}
+
+fn sniff_iso_media(brand: &[u8]) -> &'static str {
+    match brand {
+        b"qt  " => "video/quicktime",
+        b"M4A " | b"M4B " => "audio/mp4",
+        b"heic" | b"heix" | b"mif1" | b"msf1" => "image/heic",
+        b"avif" | b"avis" => "image/avif",
+        b"3gp4" | b"3gp5" | b"3g2a" => "video/3gpp",
+        _ => "video/mp4",
+    }
+}
+
+fn sniff(bytes: &[u8]) -> Option<&'static str> {
+    let starts = |magic: &[u8]| bytes.starts_with(magic);
+    let at = |offset: usize, magic: &[u8]| {
+        bytes.len() >= offset + magic.len() && &bytes[offset..offset + magic.len()] == magic
+    };
+
+    let mime_type = if starts(b"\x89PNG\r\n\x1a\n") {
+        "image/png"
+    } else if starts(&[0xFF, 0xD8, 0xFF]) {
+        "image/jpeg"
+    } else if starts(b"GIF87a") || starts(b"GIF89a") {
+        "image/gif"
+    } else if starts(b"RIFF") && at(8, b"WEBP") {
+        "image/webp"
+    } else if starts(b"RIFF") && at(8, b"WAVE") {
+        "audio/wav"
+    } else if starts(b"RIFF") && at(8, b"AVI ") {
+        "video/x-msvideo"
+    } else if starts(b"BM") && bytes.len() > 14 && at(6, &[0, 0, 0, 0]) {
+        "image/bmp"
+    } else if starts(b"II*\0") || starts(b"MM\0*") {
+        "image/tiff"
+    } else if starts(&[0, 0, 1, 0]) {
+        "image/x-icon"
+    } else if at(4, b"ftyp") {
+        sniff_iso_media(bytes.get(8..12).unwrap_or_default())
+    } else if starts(&[0x1A, 0x45, 0xDF, 0xA3]) {
+        "video/x-matroska"
+    } else if starts(b"fLaC") {
+        "audio/flac"
+    } else if starts(b"OggS") {
+        "audio/ogg"
+    } else if starts(b"ID3") || starts(&[0xFF, 0xFB]) || starts(&[0xFF, 0xF3]) {
+        "audio/mpeg"
+    } else if starts(b"%PDF-") {
+        "application/pdf"
+    } else if starts(b"%!PS") {
+        "application/postscript"
+    } else if starts(b"{\\rtf") {
+        "application/rtf"
+    } else if starts(b"PK\x03\x04") || starts(b"PK\x05\x06") {
+        "application/zip"
+    } else if starts(&[0x1F, 0x8B]) {
+        "application/gzip"
+    } else if starts(&[0xFD, b'7', b'z', b'X', b'Z', 0]) {
+        "application/x-xz"
+    } else if starts(&[0x28, 0xB5, 0x2F, 0xFD]) {
+        "application/zstd"
+    } else if starts(b"BZh") {
+        "application/x-bzip2"
+    } else if starts(&[b'7', b'z', 0xBC, 0xAF, 0x27, 0x1C]) {
+        "application/x-7z-compressed"
+    } else if starts(b"Rar!\x1a\x07") {
+        "application/vnd.rar"
+    } else if at(257, b"ustar") {
+        "application/x-tar"
+    } else if starts(b"\x7fELF") {
+        "application/x-executable"
+    } else if starts(&[0xFE, 0xED, 0xFA, 0xCE])
+        || starts(&[0xFE, 0xED, 0xFA, 0xCF])
+        || starts(&[0xCE, 0xFA, 0xED, 0xFE])
+        || starts(&[0xCF, 0xFA, 0xED, 0xFE])
+    {
+        "application/x-mach-binary"
+    } else if starts(b"MZ") {
+        "application/x-msdownload"
+    } else if starts(b"\0asm") {
+        "application/wasm"
+    } else if starts(b"SQLite format 3\0") {
+        "application/vnd.sqlite3"
+    } else {
+        return None;
+    };
+
+    Some(mime_type)
+}
+
+/// What extensions such as `.bin` and `.dat` map to. It says nothing about
+/// the contents, so any detected type agrees with it and wins over it.
+const GENERIC_MIME_TYPE: &str = "application/octet-stream";
+
+fn is_compatible(extension_type: &str, detected_type: &str) -> bool {
+    if extension_type == detected_type || extension_type == GENERIC_MIME_TYPE {
+        return true;
+    }
+
+    // Macro-enabled Office files (`.docm`, `.xlsm`, ...) are OOXML too.
+    let is_zip_container = ZIP_CONTAINER_TYPES.contains(&extension_type)
+        || extension_type.starts_with("application/vnd.openxmlformats-officedocument")
+        || extension_type.starts_with("application/vnd.oasis.opendocument")
+        || (extension_type.starts_with("application/vnd.ms-")
+            && extension_type.to_lowercase().contains("macroenabled"));
+
+    // Compressed tarballs, and XML formats stored gzipped such as `.svgz`.
+    let is_gzip_container =
+        GZIP_CONTAINER_TYPES.contains(&extension_type) || extension_type.ends_with("+xml");
+
+    match detected_type {
+        "application/zip" => is_zip_container,
+        "application/gzip" => is_gzip_container,
+        "application/x-executable" => {
+            extension_type.contains("sharedlib") || extension_type.contains("executable")
+        }
+        "video/x-matroska" => {
+            extension_type.ends_with("/webm") || extension_type.ends_with("matroska")
+        }
+        "video/mp4" | "audio/mp4" | "video/quicktime" | "video/3gpp" => {
+            extension_type.starts_with("video/") || extension_type.starts_with("audio/")
+        }
+        "audio/ogg" => extension_type.ends_with("/ogg") || extension_type == "audio/opus",
+        "audio/wav" => extension_type.contains("wav"),
+        "image/x-icon" => extension_type == "image/vnd.microsoft.icon",
+        _ => false,
+    }
+}
+
+async fn detect_file_type(path: String) -> FileTypeInfo {
+    let file_path = Path::new(&path);
+    let mime_type = mime_from_extension(file_path);
+
+    let metadata = fs::metadata(file_path).await.ok();
+    let mode = metadata
+        .as_ref()
+        .and_then(|metadata| crate::permissions::ownership(metadata).mode);
+
+    let mut bytes = Vec::new();
+    if metadata.as_ref().is_some_and(|metadata| metadata.is_file()) {
+        if let Ok(file) = fs::File::open(file_path).await {
+            let _ = file.take(SNIFF_BYTES).read_to_end(&mut bytes).await;
+        }
+    }
+
+    let detected_mime_type = sniff(&bytes).map(str::to_string);
+
+    let mismatch = match (&mime_type, &detected_mime_type) {
+        (Some(extension_type), Some(detected_type)) => {
+            !is_compatible(extension_type, detected_type)
+        }
+        _ => false,
+    };
+
+    let effective = effective_type(
+        mime_type.as_deref(),
+        detected_mime_type.as_deref(),
+        mismatch,
+    );
+
+    // Once the contents overrule the extension, the extension should not
+    // sway the category either.
+    let category = if mime_type.is_some() && effective != mime_type.as_deref() {
+        category_for(effective, Path::new(""), mode)
+    } else {
+        category_for(effective, file_path, mode)
+    };
+
+    FileTypeInfo {
+        path,
+        mime_type,
+        detected_mime_type,
+        category,
+        mismatch,
+    }
+}
+
+/// Prefers the sniffed type when it contradicts the extension or the
+/// extension only maps to the generic binary type.
+fn effective_type<'a>(
+    mime_type: Option<&'a str>,
+    detected_mime_type: Option<&'a str>,
+    mismatch: bool,
+) -> Option<&'a str> {
+    if mismatch || mime_type == Some(GENERIC_MIME_TYPE) {
+        detected_mime_type.or(mime_type)
+    } else {
+        mime_type.or(detected_mime_type)
+    }
+}
+
+/// The MIME type `path` most likely has, preferring the sniffed type when
+/// it contradicts the extension.
+pub(crate) async fn effective_mime_type(path: &Path) -> Option<String> {
+    let info = detect_file_type(path.to_string_lossy().to_string()).await;
+
+    effective_type(
+        info.mime_type.as_deref(),
+        info.detected_mime_type.as_deref(),
+        info.mismatch,
+    )
+    .map(str::to_string)
+}
+
+#[tauri::command]
+pub async fn detect_file_types(paths: Vec<String>) -> Result<Vec<FileTypeInfo>, String> {
+    Ok(futures::future::join_all(paths.into_iter().map(detect_file_type)).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn padded(prefix: &[u8], offset: usize, magic: &[u8]) -> Vec<u8> {
+        let mut bytes = prefix.to_vec();
+        bytes.resize(offset, 0);
+        bytes.extend_from_slice(magic);
+        bytes
+    }
+
+    #[test]
+    fn magic_numbers_are_recognized() {
+        let cases: &[(&[u8], Option<&str>)] = &[
+            (b"\x89PNG\r\n\x1a\n....", Some("image/png")),
+            (&[0xFF, 0xD8, 0xFF, 0xE0], Some("image/jpeg")),
+            (b"GIF89a", Some("image/gif")),
+            (b"RIFF\0\0\0\0WEBPVP8 ", Some("image/webp")),
+            (b"RIFF\0\0\0\0WAVEfmt ", Some("audio/wav")),
+            (b"\0\0\0\x20ftypisom", Some("video/mp4")),
+            (b"\0\0\0\x20ftypM4A ", Some("audio/mp4")),
+            (b"\0\0\0\x20ftypheic", Some("image/heic")),
+            (&[0x1A, 0x45, 0xDF, 0xA3], Some("video/x-matroska")),
+            (b"ID3\x04", Some("audio/mpeg")),
+            (b"%PDF-1.7", Some("application/pdf")),
+            (b"PK\x03\x04", Some("application/zip")),
+            (&[0x1F, 0x8B, 0x08], Some("application/gzip")),
+            (&[0x28, 0xB5, 0x2F, 0xFD], Some("application/zstd")),
+            (b"\x7fELF\x02", Some("application/x-executable")),
+            (b"MZ\x90\0", Some("application/x-msdownload")),
+            (b"SQLite format 3\0", Some("application/vnd.sqlite3")),
+            (b"plain text", None),
+            (b"", None),
+            (b"RIFF", None),
+        ];
+
+        for (bytes, expected) in cases {
+            assert_eq!(sniff(bytes), *expected, "{:?}", bytes);
+        }
+
+        assert_eq!(
+            sniff(&padded(b"file.txt", 257, b"ustar")),
+            Some("application/x-tar")
+        );
+    }
+
+    #[test]
+    fn containers_agree_with_their_extensions() {
+        let compatible = [
+            ("image/png", "image/png"),
+            ("application/octet-stream", "image/png"),
+            ("application/octet-stream", "application/zip"),
+            ("application/epub+zip", "application/zip"),
+            ("application/vnd.android.package-archive", "application/zip"),
+            (
+                "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+                "application/zip",
+            ),
+            (
+                "application/vnd.ms-word.document.macroEnabled.12",
+                "application/zip",
+            ),
+            (
+                "application/vnd.ms-excel.sheet.macroEnabled.12",
+                "application/zip",
+            ),
+            ("application/vnd.oasis.opendocument.text", "application/zip"),
+            ("image/svg+xml", "application/gzip"),
+            ("application/x-compressed-tar", "application/gzip"),
+            ("application/x-compressed", "application/gzip"),
+            ("application/x-sharedlib", "application/x-executable"),
+            ("video/webm", "video/x-matroska"),
+            ("audio/mp4", "video/mp4"),
+            ("audio/opus", "audio/ogg"),
+            ("image/vnd.microsoft.icon", "image/x-icon"),
+        ];
+
+        for (extension_type, detected_type) in compatible {
+            assert!(
+                is_compatible(extension_type, detected_type),
+                "{} / {}",
+                extension_type,
+                detected_type
+            );
+        }
+    }
+
+    #[test]
+    fn contradicting_contents_are_mismatches() {
+        let mismatched = [
+            ("image/png", "image/jpeg"),
+            ("text/plain", "application/x-executable"),
+            ("application/pdf", "application/zip"),
+            ("application/vnd.ms-excel", "application/zip"),
+            ("image/jpeg", "application/gzip"),
+            ("audio/mpeg", "video/x-matroska"),
+        ];
+
+        for (extension_type, detected_type) in mismatched {
+            assert!(
+                !is_compatible(extension_type, detected_type),
+                "{} / {}",
+                extension_type,
+                detected_type
+            );
+        }
+    }
+
+    #[test]
+    fn detected_types_win_over_generic_or_contradicted_extensions() {
+        assert_eq!(
+            effective_type(Some("image/png"), Some("image/png"), false),
+            Some("image/png")
+        );
+        assert_eq!(
+            effective_type(Some("image/svg+xml"), Some("application/gzip"), false),
+            Some("image/svg+xml")
+        );
+        assert_eq!(
+            effective_type(Some(GENERIC_MIME_TYPE), Some("image/png"), false),
+            Some("image/png")
+        );
+        assert_eq!(
+            effective_type(Some(GENERIC_MIME_TYPE), None, false),
+            Some(GENERIC_MIME_TYPE)
+        );
+        assert_eq!(
+            effective_type(Some("image/png"), Some("image/jpeg"), true),
+            Some("image/jpeg")
+        );
+        assert_eq!(
+            effective_type(None, Some("image/png"), false),
+            Some("image/png")
+        );
+    }
+}
@@ -0,0 +1,1273 @@
+use chrono::{DateTime, Utc};
+use futures::future::{select, Either};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio::fs;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::{watch, Semaphore};
+use tokio::task;
+
+use crate::archives::{self, ArchiveFormat, ConflictPolicy};
+use crate::compare::{self, SyncAction, SyncOperation};
+use crate::journal::{self, DeletionBatch, OperationKind};
+
+const COPY_BUFFER_SIZE: usize = 1024 * 1024;
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+const PAUSE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JobRequest {
+    Copy {
+        sources: Vec<String>,
+        destination: String,
+    },
+    Move {
+        sources: Vec<String>,
+        destination: String,
+    },
+    Delete {
+        paths: Vec<String>,
+    },
+    CreateArchive {
+        paths: Vec<String>,
+        destination: String,
+        format: ArchiveFormat,
+        level: Option<u32>,
+    },
+    ExtractArchive {
+        archive: String,
+        destination: String,
+        on_conflict: ConflictPolicy,
+    },
+    Sync {
+        left: String,
+        right: String,
+        actions: Vec<SyncAction>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    Queued,
+    Running,
+    Paused,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobInfo {
+    id: String,
+    request: JobRequest,
+    state: JobState,
+    total_bytes: u64,
+    processed_bytes: u64,
+    total_items: u64,
+    processed_items: u64,
+    current_path: Option<String>,
+    error: Option<String>,
+    created: DateTime<Utc>,
+    started: Option<DateTime<Utc>>,
+    finished: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Control {
+    Run,
+    Pause,
+    Cancel,
+}
+
+enum JobError {
+    Cancelled,
+    Failed(String),
+}
+
+impl From<std::io::Error> for JobError {
+    fn from(e: std::io::Error) -> Self {
+        JobError::Failed(e.to_string())
+    }
+}
+
+impl From<String> for JobError {
+    fn from(e: String) -> Self {
+        JobError::Failed(e)
+    }
+}
+
+struct Job {
+    info: Mutex<JobInfo>,
+    control: watch::Sender<Control>,
+    last_emit: Mutex<Instant>,
+    /// Where progress is reported; `None` for jobs run without a window,
+    /// as in tests.
+    app: Option<AppHandle>,
+}
+
+impl Job {
+    fn new(request: JobRequest, app: Option<AppHandle>) -> Self {
+        let (control, _) = watch::channel(Control::Run);
+
+        Job {
+            info: Mutex::new(JobInfo {
+                id: uuid::Uuid::new_v4().to_string(),
+                request,
+                state: JobState::Queued,
+                total_bytes: 0,
+                processed_bytes: 0,
+                total_items: 0,
+                processed_items: 0,
+                current_path: None,
+                error: None,
+                created: Utc::now(),
+                started: None,
+                finished: None,
+            }),
+            control,
+            last_emit: Mutex::new(Instant::now()),
+            app,
+        }
+    }
+
+    fn emit(&self, event: &str) {
+        if let Some(app) = &self.app {
+            let _ = app.emit(event, self.snapshot());
+        }
+    }
+
+    fn snapshot(&self) -> JobInfo {
+        self.info.lock().unwrap().clone()
+    }
+
+    fn update<T>(&self, f: impl FnOnce(&mut JobInfo) -> T) -> T {
+        f(&mut self.info.lock().unwrap())
+    }
+
+    fn emit_progress(&self, force: bool) {
+        {
+            let mut last_emit = self.last_emit.lock().unwrap();
+            if !force && last_emit.elapsed() < PROGRESS_INTERVAL {
+                return;
+            }
+            *last_emit = Instant::now();
+        }
+
+        self.emit("job-progress");
+    }
+
+    fn advance(&self, bytes: u64, items: u64) {
+        self.update(|info| {
+            info.processed_bytes += bytes;
+            info.processed_items += items;
+        });
+        self.emit_progress(false);
+    }
+
+    fn set_current(&self, path: &Path) {
+        self.update(|info| info.current_path = Some(path.to_string_lossy().to_string()));
+    }
+
+    async fn checkpoint(&self) -> Result<(), JobError> {
+        let mut receiver = self.control.subscribe();
+        let control = receiver
+            .wait_for(|control| *control != Control::Pause)
+            .await
+            .map(|control| *control)
+            .unwrap_or(Control::Cancel);
+
+        match control {
+            Control::Cancel => Err(JobError::Cancelled),
+            _ => Ok(()),
+        }
+    }
+
+    /// Blocking counterpart of `checkpoint` for work running on a blocking
+    /// thread.
+    fn checkpoint_blocking(&self) -> Result<(), JobError> {
+        loop {
+            match *self.control.borrow() {
+                Control::Run => return Ok(()),
+                Control::Cancel => return Err(JobError::Cancelled),
+                Control::Pause => {}
+            }
+
+            std::thread::sleep(PAUSE_POLL_INTERVAL);
+        }
+    }
+
+    async fn cancelled(&self) {
+        let mut receiver = self.control.subscribe();
+        let _ = receiver
+            .wait_for(|control| *control == Control::Cancel)
+            .await;
+    }
+}
+
+type JobRegistry = Mutex<HashMap<String, Arc<Job>>>;
+
+fn get_jobs() -> &'static JobRegistry {
+    static JOBS: OnceLock<JobRegistry> = OnceLock::new();
+
+    JOBS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn get_job(id: &str) -> Result<Arc<Job>, String> {
+    get_jobs()
+        .lock()
+        .unwrap()
+        .get(id)
+        .cloned()
+        .ok_or_else(|| format!("Job not found: {}", id))
+}
+
+fn device_queue(device: u64) -> Arc<Semaphore> {
+    static QUEUES: OnceLock<Mutex<HashMap<u64, Arc<Semaphore>>>> = OnceLock::new();
+
+    QUEUES
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap()
+        .entry(device)
+        .or_insert_with(|| Arc::new(Semaphore::new(1)))
+        .clone()
+}
+
+#[cfg(unix)]
+fn device_id(path: &Path) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+
+    path.ancestors()
+        .find_map(|ancestor| std::fs::metadata(ancestor).ok())
+        .map(|metadata| metadata.dev())
+        .unwrap_or(0)
+}
+
+#[cfg(not(unix))]
+fn device_id(path: &Path) -> u64 {
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    path.components().next().hash(&mut hasher);
+    hasher.finish()
+}
+
+fn validate_request(request: &JobRequest) -> Result<PathBuf, String> {
+    match request {
+        JobRequest::Copy {
+            sources,
+            destination,
+        }
+        | JobRequest::Move {
+            sources,
+            destination,
+        } => {
+            let destination_path = Path::new(destination);
+
+            if !destination_path.is_dir() {
+                return Err("Destination is not a directory".to_string());
+            }
+
+            for source in sources {
+                let source_path = Path::new(source);
+
+                match archives::split_archive_path(source) {
+                    Some(_) if matches!(request, JobRequest::Move { .. }) => {
+                        return Err("Items inside an archive can only be copied".to_string());
+                    }
+                    Some((_, inner)) if inner.is_empty() => {
+                        return Err(format!("Invalid source path: {}", source));
+                    }
+                    Some(_) => {}
+                    None if source_path.symlink_metadata().is_err() => {
+                        return Err(format!("Item does not exist: {}", source));
+                    }
+                    None => {}
+                }
+
+                let file_name = source_path
+                    .file_name()
+                    .ok_or_else(|| format!("Invalid source path: {}", source))?;
+
+                if destination_path.join(file_name).symlink_metadata().is_ok() {
+                    return Err(format!(
+                        "Item with name '{}' already exists in destination",
+                        file_name.to_string_lossy()
+                    ));
+                }
+
+                if destination_path.starts_with(source_path) {
+                    return Err(format!("Cannot copy or move '{}' into itself", source));
+                }
+            }
+
+            Ok(destination_path.to_path_buf())
+        }
+        JobRequest::Delete { paths } => {
+            for path in paths {
+                if Path::new(path).symlink_metadata().is_err() {
+                    return Err(format!("Item does not exist: {}", path));
+                }
+            }
+
+            paths
+                .first()
+                .map(PathBuf::from)
+                .ok_or_else(|| "No items to delete".to_string())
+        }
+        JobRequest::CreateArchive {
+            paths, destination, ..
+        } => {
+            if paths.is_empty() {
+                return Err("No items to archive".to_string());
+            }
+
+            for path in paths {
+                if Path::new(path).symlink_metadata().is_err() {
+                    return Err(format!("Item does not exist: {}", path));
+                }
+            }
+
+            let destination_path = Path::new(destination);
+
+            if destination_path.symlink_metadata().is_ok() {
+                return Err(format!("Item already exists: {}", destination));
+            }
+
+            match destination_path.parent() {
+                Some(parent) if parent.is_dir() => Ok(parent.to_path_buf()),
+                _ => Err("Destination is not a directory".to_string()),
+            }
+        }
+        JobRequest::ExtractArchive {
+            archive,
+            destination,
+            ..
+        } => {
+            let archive_path = Path::new(archive);
+
+            if !archive_path.is_file() {
+                return Err(format!("File does not exist: {}", archive));
+            }
+
+            if ArchiveFormat::from_path(archive_path).is_none() {
+                return Err(format!("Unsupported archive format: {}", archive));
+            }
+
+            let destination_path = Path::new(destination);
+
+            if destination_path.exists() && !destination_path.is_dir() {
+                return Err("Destination is not a directory".to_string());
+            }
+
+            Ok(destination_path.to_path_buf())
+        }
+        JobRequest::Sync {
+            left,
+            right,
+            actions,
+        } => {
+            for path in [left, right] {
+                if !Path::new(path).is_dir() {
+                    return Err(format!("Path is not a directory: {}", path));
+                }
+            }
+
+            for action in actions {
+                if compare::decode_relative(&action.relative_path).is_none() {
+                    return Err(format!("Invalid path: {}", action.relative_path));
+                }
+            }
+
+            // Queued behind the side being written to; a sync writing both
+            // sides goes with the right one.
+            let writes_left_only = actions.iter().all(|action| {
+                matches!(
+                    action.operation,
+                    SyncOperation::CopyToLeft | SyncOperation::DeleteLeft
+                )
+            });
+
+            Ok(PathBuf::from(if writes_left_only { left } else { right }))
+        }
+    }
+}
+
+async fn scan_tree(job: &Job, path: &Path) -> Result<(u64, u64), JobError> {
+    job.checkpoint().await?;
+
+    let metadata = fs::symlink_metadata(path).await?;
+
+    if !metadata.is_dir() {
+        return Ok((metadata.len(), 1));
+    }
+
+    let (mut bytes, mut items) = (0, 1);
+    let mut entries = fs::read_dir(path).await?;
+
+    while let Some(entry) = entries.next_entry().await? {
+        let (entry_bytes, entry_items) = Box::pin(scan_tree(job, &entry.path())).await?;
+        bytes += entry_bytes;
+        items += entry_items;
+    }
+
+    Ok((bytes, items))
+}
+
+async fn scan_sources(job: &Job, paths: &[String]) -> Result<(), JobError> {
+    for path in paths {
+        let (bytes, items) = match archives::split_archive_path(path) {
+            Some((archive, inner)) => {
+                task::spawn_blocking(move || archives::member_totals(&archive, &inner))
+                    .await
+                    .map_err(|e| JobError::Failed(e.to_string()))??
+            }
+            None => scan_tree(job, Path::new(path)).await?,
+        };
+        job.update(|info| {
+            info.total_bytes += bytes;
+            info.total_items += items;
+        });
+    }
+
+    job.emit_progress(true);
+
+    Ok(())
+}
+
+async fn copy_file(job: &Job, from: &Path, to: &Path) -> Result<(), JobError> {
+    let mut reader = fs::File::open(from).await?;
+    let mut writer = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(to)
+        .await?;
+    let mut buffer = vec![0u8; COPY_BUFFER_SIZE];
+
+    loop {
+        job.checkpoint().await?;
+
+        let read = reader.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+
+        writer.write_all(&buffer[..read]).await?;
+        job.advance(read as u64, 0);
+    }
+
+    writer.flush().await?;
+
+    let metadata = fs::metadata(from).await?;
+    fs::set_permissions(to, metadata.permissions()).await?;
+
+    if let Ok(modified) = metadata.modified() {
+        let _ = writer.into_std().await.set_modified(modified);
+    }
+
+    Ok(())
+}
+
+async fn copy_tree(job: &Job, from: &Path, to: &Path) -> Result<(), JobError> {
+    job.checkpoint().await?;
+    job.set_current(from);
+
+    let metadata = fs::symlink_metadata(from).await?;
+
+    #[cfg(unix)]
+    if metadata.is_symlink() {
+        let target = fs::read_link(from).await?;
+        fs::symlink(target, to).await?;
+        job.advance(0, 1);
+        return Ok(());
+    }
+
+    if metadata.is_dir() {
+        fs::create_dir(to).await?;
+
+        let mut entries = fs::read_dir(from).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            Box::pin(copy_tree(job, &entry.path(), &to.join(entry.file_name()))).await?;
+        }
+
+        fs::set_permissions(to, metadata.permissions()).await?;
+    } else {
+        copy_file(job, from, to).await?;
+    }
+
+    job.advance(0, 1);
+
+    Ok(())
+}
+
+/// Removes the source of a cross-device move once it has been copied.
+async fn remove_tree(job: &Job, path: &Path) -> Result<(), JobError> {
+    job.checkpoint().await?;
+    job.set_current(path);
+
+    let metadata = fs::symlink_metadata(path).await?;
+
+    if metadata.is_dir() {
+        let mut entries = fs::read_dir(path).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            Box::pin(remove_tree(job, &entry.path())).await?;
+        }
+
+        fs::remove_dir(path).await?;
+    } else {
+        fs::remove_file(path).await?;
+    }
+
+    job.advance(0, 1);
+
+    Ok(())
+}
+
+async fn discard_partial(path: &Path) {
+    if fs::symlink_metadata(path).await.is_ok() {
+        let _ = journal::remove_all(path).await;
+    }
+}
+
+/// A hidden name next to `target` that a copy is built under, so nothing is
+/// ever written over what is already at `target`.
+fn partial_path(target: &Path) -> PathBuf {
+    target.with_file_name(format!(".wiregui-partial-{}", uuid::Uuid::new_v4()))
+}
+
+/// Renames `from` to `to`, failing instead of replacing anything at `to`.
+#[cfg(target_os = "linux")]
+fn rename_noreplace(from: &Path, to: &Path) -> std::io::Result<()> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let from_c = CString::new(from.as_os_str().as_bytes())?;
+    let to_c = CString::new(to.as_os_str().as_bytes())?;
+
+    let result = unsafe {
+        libc::renameat2(
+            libc::AT_FDCWD,
+            from_c.as_ptr(),
+            libc::AT_FDCWD,
+            to_c.as_ptr(),
+            libc::RENAME_NOREPLACE,
+        )
+    };
+
+    if result == 0 {
+        return Ok(());
+    }
+
+    let error = std::io::Error::last_os_error();
+    match error.raw_os_error() {
+        // Filesystems without RENAME_NOREPLACE support.
+        Some(libc::EINVAL) | Some(libc::ENOSYS) => rename_if_vacant(from, to),
+        _ => Err(error),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn rename_noreplace(from: &Path, to: &Path) -> std::io::Result<()> {
+    rename_if_vacant(from, to)
+}
+
+fn rename_if_vacant(from: &Path, to: &Path) -> std::io::Result<()> {
+    if to.symlink_metadata().is_ok() {
+        return Err(std::io::ErrorKind::AlreadyExists.into());
+    }
+
+    std::fs::rename(from, to)
+}
+
+async fn rename_exclusive(from: &Path, to: &Path) -> std::io::Result<()> {
+    let (from, to) = (from.to_path_buf(), to.to_path_buf());
+
+    task::spawn_blocking(move || rename_noreplace(&from, &to))
+        .await
+        .map_err(std::io::Error::other)?
+}
+
+fn placement_error(error: std::io::Error, target: &Path) -> JobError {
+    if error.kind() != std::io::ErrorKind::AlreadyExists {
+        return error.into();
+    }
+
+    JobError::Failed(format!(
+        "Item with name '{}' already exists in destination",
+        target.file_name().unwrap_or_default().to_string_lossy()
+    ))
+}
+
+/// Moves a finished copy from `partial` to `target`, failing if an item
+/// appeared at `target` since the job was submitted.
+async fn place(partial: &Path, target: &Path) -> Result<(), JobError> {
+    rename_exclusive(partial, target)
+        .await
+        .map_err(|e| placement_error(e, target))
+}
+
+/// Copies `source` to `target` by way of a partial copy, which is removed
+/// again if anything fails.
+async fn copy_into_place(job: &Arc<Job>, source: &str, target: &Path) -> Result<(), JobError> {
+    let partial = partial_path(target);
+
+    let copied = match archives::split_archive_path(source) {
+        Some((archive, inner)) => copy_from_archive(job, archive, inner, partial.clone()).await,
+        None => copy_tree(job, Path::new(source), &partial).await,
+    };
+
+    let result = match copied {
+        Ok(()) => place(&partial, target).await,
+        Err(e) => Err(e),
+    };
+
+    if result.is_err() {
+        discard_partial(&partial).await;
+    }
+
+    result
+}
+
+async fn copy_from_archive(
+    job: &Arc<Job>,
+    archive: PathBuf,
+    inner: String,
+    target: PathBuf,
+) -> Result<(), JobError> {
+    let job = job.clone();
+
+    task::spawn_blocking(move || {
+        archives::extract_members(
+            &archive,
+            &inner,
+            &target,
+            ConflictPolicy::Fail,
+            |path, bytes, items| {
+                job.checkpoint_blocking()?;
+                job.set_current(path);
+                job.advance(bytes, items);
+                Ok(())
+            },
+        )
+    })
+    .await
+    .map_err(|e| JobError::Failed(e.to_string()))?
+}
+
+async fn run_copy(job: &Arc<Job>, sources: &[String], destination: &Path) -> Result<(), JobError> {
+    scan_sources(job, sources).await?;
+
+    for source in sources {
+        let source_path = Path::new(source);
+        let target = destination.join(source_path.file_name().unwrap_or_default());
+
+        copy_into_place(job, source, &target).await?;
+
+        let _ = journal::record_operation(OperationKind::Copy {
+            from: source.clone(),
+            to: target.to_string_lossy().to_string(),
+        })
+        .await;
+    }
+
+    Ok(())
+}
+
+async fn run_move(job: &Arc<Job>, sources: &[String], destination: &Path) -> Result<(), JobError> {
+    job.update(|info| info.total_items = sources.len() as u64);
+
+    for source in sources {
+        job.checkpoint().await?;
+
+        let source_path = Path::new(source);
+        let target = destination.join(source_path.file_name().unwrap_or_default());
+        job.set_current(source_path);
+
+        match rename_exclusive(source_path, &target).await {
+            Ok(()) => job.advance(0, 1),
+            Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => {
+                let (bytes, items) = scan_tree(job, source_path).await?;
+                job.update(|info| {
+                    info.total_bytes += bytes;
+                    info.total_items += items * 2 - 1;
+                });
+
+                copy_into_place(job, source, &target).await?;
+                remove_tree(job, source_path).await?;
+            }
+            Err(e) => return Err(placement_error(e, &target)),
+        }
+
+        let _ = journal::record_operation(OperationKind::Rename {
+            from: source.clone(),
+            to: target.to_string_lossy().to_string(),
+        })
+        .await;
+    }
+
+    Ok(())
+}
+
+/// Moves `paths` to the holding area so the deletion can be undone. What
+/// was staged is recorded even when the job fails or is cancelled part way.
+async fn run_delete(job: &Job, paths: &[String]) -> Result<(), JobError> {
+    let mut batch = DeletionBatch::new();
+    let result = stage_deletions(job, paths, &mut batch).await;
+
+    batch.commit().await?;
+    result
+}
+
+async fn stage_deletions(
+    job: &Job,
+    paths: &[String],
+    batch: &mut DeletionBatch,
+) -> Result<(), JobError> {
+    let mut sizes = Vec::with_capacity(paths.len());
+    for path in paths {
+        sizes.push(scan_tree(job, Path::new(path)).await?);
+    }
+
+    job.update(|info| {
+        info.total_bytes = sizes.iter().map(|(bytes, _)| bytes).sum();
+        info.total_items = sizes.iter().map(|(_, items)| items).sum();
+    });
+    job.emit_progress(true);
+
+    for (path, (bytes, items)) in paths.iter().zip(sizes) {
+        job.checkpoint().await?;
+        job.set_current(Path::new(path));
+
+        batch.stage(Path::new(path)).await?;
+        job.advance(bytes, items);
+    }
+
+    Ok(())
+}
+
+async fn run_create_archive(
+    job: &Arc<Job>,
+    paths: &[String],
+    destination: &Path,
+    format: ArchiveFormat,
+    level: Option<u32>,
+) -> Result<(), JobError> {
+    scan_sources(job, paths).await?;
+
+    let worker = job.clone();
+    let sources = paths.to_vec();
+    let target = destination.to_path_buf();
+
+    task::spawn_blocking(move || {
+        archives::create_members::<JobError>(
+            &sources,
+            &target,
+            format,
+            level,
+            |path, bytes, items| {
+                worker.checkpoint_blocking()?;
+                worker.set_current(path);
+                worker.advance(bytes, items);
+                Ok(())
+            },
+        )
+    })
+    .await
+    .map_err(|e| JobError::Failed(e.to_string()))??;
+
+    let _ = journal::record_operation(OperationKind::CreateFile {
+        path: destination.to_string_lossy().to_string(),
+    })
+    .await;
+
+    Ok(())
+}
+
+async fn run_extract_archive(
+    job: &Arc<Job>,
+    archive: &Path,
+    destination: &Path,
+    on_conflict: ConflictPolicy,
+) -> Result<(), JobError> {
+    let worker = job.clone();
+    let archive = archive.to_path_buf();
+    let target = destination.to_path_buf();
+
+    task::spawn_blocking(move || {
+        let (bytes, items) = archives::member_totals(&archive, "")?;
+        worker.update(|info| {
+            info.total_bytes = bytes;
+            info.total_items = items;
+        });
+        worker.emit_progress(true);
+
+        archives::extract_members(&archive, "", &target, on_conflict, |path, bytes, items| {
+            worker.checkpoint_blocking()?;
+            worker.set_current(path);
+            worker.advance(bytes, items);
+            Ok(())
+        })
+    })
+    .await
+    .map_err(|e| JobError::Failed(e.to_string()))?
+}
+
+/// Returns the `(source, target)` of `action`.
+fn sync_paths(
+    left: &Path,
+    right: &Path,
+    action: &SyncAction,
+) -> Result<(PathBuf, PathBuf), JobError> {
+    let relative = compare::decode_relative(&action.relative_path)
+        .ok_or_else(|| format!("Invalid path: {}", action.relative_path))?;
+    let (left, right) = (left.join(&relative), right.join(&relative));
+
+    Ok(match action.operation {
+        SyncOperation::CopyToRight | SyncOperation::DeleteLeft => (left, right),
+        SyncOperation::CopyToLeft | SyncOperation::DeleteRight => (right, left),
+    })
+}
+
+/// Puts the finished copy at `partial` in place of `target`. Whatever was
+/// at `target` is staged in the job's deletion batch first, so replacing it
+/// can be undone like any other deletion.
+async fn replace_with(
+    batch: &mut DeletionBatch,
+    partial: &Path,
+    target: &Path,
+) -> Result<(), JobError> {
+    if fs::symlink_metadata(target).await.is_ok() {
+        batch.stage(target).await?;
+    }
+
+    place(partial, target).await
+}
+
+async fn run_sync(
+    job: &Job,
+    left: &Path,
+    right: &Path,
+    actions: &[SyncAction],
+) -> Result<(), JobError> {
+    let mut batch = DeletionBatch::new();
+    let result = apply_sync_actions(job, left, right, actions, &mut batch).await;
+
+    batch.commit().await?;
+    result
+}
+
+async fn apply_sync_actions(
+    job: &Job,
+    left: &Path,
+    right: &Path,
+    actions: &[SyncAction],
+    batch: &mut DeletionBatch,
+) -> Result<(), JobError> {
+    let mut sizes = Vec::with_capacity(actions.len());
+    for action in actions {
+        let (source, _) = sync_paths(left, right, action)?;
+        let (bytes, items) = match fs::symlink_metadata(&source).await {
+            Ok(_) => scan_tree(job, &source).await?,
+            Err(_) => (0, 1),
+        };
+        job.update(|info| {
+            info.total_bytes += bytes;
+            info.total_items += items;
+        });
+        sizes.push((bytes, items));
+    }
+
+    job.emit_progress(true);
+
+    for (action, (bytes, items)) in actions.iter().zip(sizes) {
+        job.checkpoint().await?;
+
+        let (source, target) = sync_paths(left, right, action)?;
+
+        match action.operation {
+            SyncOperation::DeleteLeft | SyncOperation::DeleteRight => {
+                job.set_current(&source);
+
+                if fs::symlink_metadata(&source).await.is_ok() {
+                    batch.stage(&source).await?;
+                }
+                job.advance(bytes, items);
+            }
+            SyncOperation::CopyToLeft | SyncOperation::CopyToRight => {
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent).await?;
+                }
+
+                let partial = partial_path(&target);
+
+                let result = match copy_tree(job, &source, &partial).await {
+                    Ok(()) => replace_with(batch, &partial, &target).await,
+                    Err(e) => Err(e),
+                };
+
+                if let Err(e) = result {
+                    discard_partial(&partial).await;
+                    return Err(e);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_job(job: Arc<Job>, device: u64) {
+    let queue = device_queue(device);
+
+    let permit = match select(Box::pin(queue.acquire_owned()), Box::pin(job.cancelled())).await {
+        Either::Left((Ok(permit), _)) => Some(permit),
+        _ => None,
+    };
+
+    let result = match permit {
+        Some(_) => {
+            job.update(|info| {
+                if info.state == JobState::Queued {
+                    info.state = JobState::Running;
+                }
+                info.started = Some(Utc::now());
+            });
+            job.emit_progress(true);
+
+            let request = job.snapshot().request;
+            match &request {
+                JobRequest::Copy {
+                    sources,
+                    destination,
+                } => run_copy(&job, sources, Path::new(destination)).await,
+                JobRequest::Move {
+                    sources,
+                    destination,
+                } => run_move(&job, sources, Path::new(destination)).await,
+                JobRequest::Delete { paths } => run_delete(&job, paths).await,
+                JobRequest::CreateArchive {
+                    paths,
+                    destination,
+                    format,
+                    level,
+                } => run_create_archive(&job, paths, Path::new(destination), *format, *level).await,
+                JobRequest::ExtractArchive {
+                    archive,
+                    destination,
+                    on_conflict,
+                } => {
+                    run_extract_archive(
+                        &job,
+                        Path::new(archive),
+                        Path::new(destination),
+                        *on_conflict,
+                    )
+                    .await
+                }
+                JobRequest::Sync {
+                    left,
+                    right,
+                    actions,
+                } => run_sync(&job, Path::new(left), Path::new(right), actions).await,
+            }
+        }
+        None => Err(JobError::Cancelled),
+    };
+
+    job.update(|info| {
+        info.current_path = None;
+        info.finished = Some(Utc::now());
+        match result {
+            Ok(()) => info.state = JobState::Completed,
+            Err(JobError::Cancelled) => info.state = JobState::Cancelled,
+            Err(JobError::Failed(e)) => {
+                info.state = JobState::Failed;
+                info.error = Some(e);
+            }
+        }
+    });
+
+    job.emit("job-finished");
+}
+
+#[tauri::command]
+pub async fn submit_job(app: AppHandle, request: JobRequest) -> Result<String, String> {
+    let device_path = validate_request(&request)?;
+    let device = device_id(&device_path);
+
+    let job = Arc::new(Job::new(request, Some(app)));
+    let id = job.snapshot().id;
+
+    get_jobs().lock().unwrap().insert(id.clone(), job.clone());
+
+    tauri::async_runtime::spawn(run_job(job, device));
+
+    Ok(id)
+}
+
+#[tauri::command]
+pub async fn list_jobs() -> Result<Vec<JobInfo>, String> {
+    let mut jobs = get_jobs()
+        .lock()
+        .unwrap()
+        .values()
+        .map(|job| job.snapshot())
+        .collect::<Vec<_>>();
+
+    jobs.sort_by_key(|job| job.created);
+
+    Ok(jobs)
+}
+
+#[tauri::command]
+pub async fn pause_job(id: String) -> Result<(), String> {
+    let job = get_job(&id)?;
+
+    let paused = job.update(|info| {
+        if matches!(info.state, JobState::Queued | JobState::Running) {
+            info.state = JobState::Paused;
+            true
+        } else {
+            false
+        }
+    });
+
+    if !paused {
+        return Err("Job is not running".to_string());
+    }
+
+    job.control.send_replace(Control::Pause);
+    job.emit_progress(true);
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn resume_job(id: String) -> Result<(), String> {
+    let job = get_job(&id)?;
+
+    let resumed = job.update(|info| {
+        if info.state != JobState::Paused {
+            return false;
+        }
+
+        info.state = if info.started.is_some() {
+            JobState::Running
+        } else {
+            JobState::Queued
+        };
+        true
+    });
+
+    if !resumed {
+        return Err("Job is not paused".to_string());
+    }
+
+    job.control.send_replace(Control::Run);
+    job.emit_progress(true);
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn cancel_job(id: String) -> Result<(), String> {
+    let job = get_job(&id)?;
+
+    let state = job.snapshot().state;
+    if !matches!(
+        state,
+        JobState::Queued | JobState::Running | JobState::Paused
+    ) {
+        return Err("Job has already finished".to_string());
+    }
+
+    job.control.send_replace(Control::Cancel);
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn clear_finished_jobs() -> Result<(), String> {
+    get_jobs().lock().unwrap().retain(|_, job| {
+        matches!(
+            job.snapshot().state,
+            JobState::Queued | JobState::Running | JobState::Paused
+        )
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sync_request(left: &Path, right: &Path, operations: &[SyncOperation]) -> JobRequest {
+        JobRequest::Sync {
+            left: left.to_string_lossy().to_string(),
+            right: right.to_string_lossy().to_string(),
+            actions: operations
+                .iter()
+                .map(|&operation| SyncAction {
+                    relative_path: "a.txt".to_string(),
+                    operation,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn renames_never_replace_an_existing_item() {
+        let dir = tempfile::tempdir().unwrap();
+        let (from, to) = (dir.path().join("from.txt"), dir.path().join("to.txt"));
+        std::fs::write(&from, "new").unwrap();
+        std::fs::write(&to, "old").unwrap();
+
+        let error = rename_noreplace(&from, &to).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::AlreadyExists);
+        assert_eq!(std::fs::read_to_string(&from).unwrap(), "new");
+        assert_eq!(std::fs::read_to_string(&to).unwrap(), "old");
+
+        std::fs::remove_file(&to).unwrap();
+        rename_noreplace(&from, &to).unwrap();
+        assert!(!from.exists());
+        assert_eq!(std::fs::read_to_string(&to).unwrap(), "new");
+    }
+
+    #[test]
+    fn renames_treat_a_dangling_link_as_taken() {
+        let dir = tempfile::tempdir().unwrap();
+        let (from, to) = (dir.path().join("from.txt"), dir.path().join("link"));
+        std::fs::write(&from, "new").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(dir.path().join("missing"), &to).unwrap();
+        #[cfg(not(unix))]
+        std::fs::write(&to, "old").unwrap();
+
+        assert!(rename_noreplace(&from, &to).is_err());
+        assert!(rename_if_vacant(&from, &to).is_err());
+        assert!(from.exists());
+    }
+
+    #[test]
+    fn syncs_queue_behind_the_side_they_write_to() {
+        let (left, right) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let (left, right) = (left.path(), right.path());
+
+        let to_left = sync_request(
+            left,
+            right,
+            &[SyncOperation::CopyToLeft, SyncOperation::DeleteLeft],
+        );
+        assert_eq!(validate_request(&to_left).unwrap(), left);
+
+        let to_right = sync_request(
+            left,
+            right,
+            &[SyncOperation::CopyToRight, SyncOperation::DeleteRight],
+        );
+        assert_eq!(validate_request(&to_right).unwrap(), right);
+
+        let both = sync_request(
+            left,
+            right,
+            &[SyncOperation::CopyToLeft, SyncOperation::CopyToRight],
+        );
+        assert_eq!(validate_request(&both).unwrap(), right);
+    }
+
+    #[tokio::test]
+    async fn syncing_replaces_items_through_the_undo_journal() {
+        let (_turn, _) = crate::journal::tests::fresh_journal().await;
+        let (left, right) = compare::tests::sample_trees();
+        let (left, right) = (left.path(), right.path());
+
+        let entries = compare::compare_trees(None, left, right, compare::CompareMode::Quick);
+        let actions = compare::plan_actions(&entries, compare::SyncDirection::LeftToRight, true);
+        let job = Job::new(
+            JobRequest::Sync {
+                left: left.to_string_lossy().to_string(),
+                right: right.to_string_lossy().to_string(),
+                actions: actions.clone(),
+            },
+            None,
+        );
+
+        assert!(run_sync(&job, left, right, &actions).await.is_ok());
+        let after = compare::compare_trees(None, left, right, compare::CompareMode::Content);
+        assert!(
+            compare::plan_actions(&after, compare::SyncDirection::LeftToRight, true).is_empty()
+        );
+
+        // Undo puts back what the sync replaced or deleted, once the copies
+        // are out of the way.
+        for name in ["changed.txt", "nested/inner.txt"] {
+            std::fs::remove_file(right.join(name)).unwrap();
+        }
+        crate::journal::undo_last_operation().await.unwrap();
+
+        let read = |name: &str| std::fs::read_to_string(right.join(name)).unwrap();
+        assert_eq!(read("changed.txt"), "rght");
+        assert_eq!(read("nested/inner.txt"), "new!");
+        assert_eq!(read("right.txt"), "right only");
+        assert_eq!(read("extra/file.txt"), "extra");
+    }
+
+    /// A FIFO in the source holds the copy at a known point, so the job can
+    /// be cancelled once a partial copy exists.
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn cancelling_a_copy_removes_the_partial_copy() {
+        use std::ffi::CString;
+        use std::os::unix::ffi::OsStrExt;
+
+        let (source, destination) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let tree = source.path().join("tree");
+        std::fs::create_dir(&tree).unwrap();
+        std::fs::write(tree.join("a.txt"), "a").unwrap();
+
+        let fifo = tree.join("pipe");
+        let fifo_c = CString::new(fifo.as_os_str().as_bytes()).unwrap();
+        assert_eq!(unsafe { libc::mkfifo(fifo_c.as_ptr(), 0o600) }, 0);
+
+        let job = Arc::new(Job::new(
+            JobRequest::Copy {
+                sources: vec![tree.to_string_lossy().to_string()],
+                destination: destination.path().to_string_lossy().to_string(),
+            },
+            None,
+        ));
+        let target = destination.path().join("tree");
+
+        let copy = tokio::spawn({
+            let (job, tree, target) = (job.clone(), tree.clone(), target.clone());
+            async move { copy_into_place(&job, &tree.to_string_lossy(), &target).await }
+        });
+
+        // Past this point the copy opens the FIFO without another
+        // checkpoint, so it is blocked on it until the write below.
+        let waiting = (job.clone(), fifo.to_string_lossy().to_string());
+        task::spawn_blocking(move || {
+            let (job, fifo) = waiting;
+            while job.snapshot().current_path.as_ref() != Some(&fifo) {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+        })
+        .await
+        .unwrap();
+
+        job.control.send_replace(Control::Cancel);
+        // The copy may close the FIFO before anything is written to it.
+        let _ = task::spawn_blocking(move || std::fs::write(fifo, "x")).await;
+
+        assert!(matches!(copy.await.unwrap(), Err(JobError::Cancelled)));
+        assert_eq!(std::fs::read_dir(destination.path()).unwrap().count(), 0);
+        assert!(tree.join("a.txt").exists());
+    }
+}
@@ -0,0 +1,869 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tokio::fs;
+use tokio::sync::Mutex;
+use tokio::task;
+
+use crate::app_data_dir;
+use crate::batch_rename::{self, RenamePair};
+
+const MAX_JOURNAL_ENTRIES: usize = 200;
+/// Data kept for undo across all records. The oldest records are dropped,
+/// along with what they parked, once it is exceeded.
+const MAX_HOLDING_BYTES: u64 = 10 * 1024 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OperationKind {
+    CreateFile {
+        path: String,
+    },
+    CreateDirectory {
+        path: String,
+    },
+    CreateLink {
+        path: String,
+        target: String,
+    },
+    Rename {
+        from: String,
+        to: String,
+    },
+    Copy {
+        from: String,
+        to: String,
+    },
+    BatchRename {
+        renames: Vec<RenamePair>,
+    },
+    Delete {
+        path: String,
+    },
+    /// Deletions made by one job, undone and redone together.
+    DeleteMany {
+        paths: Vec<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperationRecord {
+    id: String,
+    timestamp: DateTime<Utc>,
+    operation: OperationKind,
+    /// Size of what the record currently has parked in a holding directory.
+    #[serde(default)]
+    staged_bytes: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OperationHistory {
+    operations: Vec<OperationRecord>,
+    cursor: usize,
+}
+
+fn journal_path() -> Result<PathBuf, String> {
+    Ok(app_data_dir()?.join("journal.json"))
+}
+
+fn home_holding_dir() -> Result<PathBuf, String> {
+    Ok(app_data_dir()?.join("holding"))
+}
+
+/// A holding directory on the same filesystem as `path`, so parking it is a
+/// rename rather than a copy: the app's own when it shares the filesystem,
+/// otherwise `.wiregui-holding-$uid` at the top of the mount, in the manner
+/// of the trash specification's `.Trash-$uid`.
+#[cfg(unix)]
+fn volume_holding_dir(path: &Path) -> Option<PathBuf> {
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+
+    let parent = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())?;
+    let device = std::fs::metadata(parent).ok()?.dev();
+
+    if let Ok(home) = home_holding_dir() {
+        if std::fs::create_dir_all(&home).is_ok()
+            && std::fs::metadata(&home).is_ok_and(|metadata| metadata.dev() == device)
+        {
+            return Some(home);
+        }
+    }
+
+    let top = parent
+        .ancestors()
+        .take_while(|ancestor| {
+            std::fs::metadata(ancestor).is_ok_and(|metadata| metadata.dev() == device)
+        })
+        .last()?;
+
+    let uid = unsafe { libc::getuid() };
+    let dir = top.join(format!(".wiregui-holding-{}", uid));
+
+    match std::fs::DirBuilder::new().mode(0o700).create(&dir) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {}
+        Err(_) => return None,
+    }
+
+    // Other users can write to the top of shared volumes, so only a real
+    // directory owned by us is trusted.
+    let metadata = std::fs::symlink_metadata(&dir).ok()?;
+    (metadata.is_dir() && metadata.uid() == uid && metadata.dev() == device).then_some(dir)
+}
+
+#[cfg(not(unix))]
+fn volume_holding_dir(_path: &Path) -> Option<PathBuf> {
+    None
+}
+
+/// Where the item parked for `path` under `key` lives, if anywhere.
+fn find_staged(path: &Path, key: &str) -> Option<PathBuf> {
+    volume_holding_dir(path)
+        .into_iter()
+        .chain(home_holding_dir().ok())
+        .map(|dir| dir.join(key))
+        .find(|candidate| candidate.symlink_metadata().is_ok())
+}
+
+/// The original location and holding key of everything `record` may have
+/// parked.
+fn staged_items(record: &OperationRecord) -> Vec<(PathBuf, String)> {
+    match &record.operation {
+        OperationKind::CreateFile { path }
+        | OperationKind::CreateDirectory { path }
+        | OperationKind::CreateLink { path, .. }
+        | OperationKind::Copy { to: path, .. }
+        | OperationKind::Delete { path } => vec![(PathBuf::from(path), record.id.clone())],
+        OperationKind::DeleteMany { paths } => paths
+            .iter()
+            .enumerate()
+            .map(|(index, path)| (PathBuf::from(path), format!("{}-{}", record.id, index)))
+            .collect(),
+        OperationKind::Rename { .. } | OperationKind::BatchRename { .. } => Vec::new(),
+    }
+}
+
+fn tree_size(path: &Path) -> u64 {
+    let Ok(metadata) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+
+    std::fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| tree_size(&entry.path()))
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+/// Parks `path` under `key` and returns its size. Items are only copied to
+/// the app's own holding directory when their filesystem has no usable one,
+/// and then only if they fit within `MAX_HOLDING_BYTES`.
+async fn stage(path: &Path, key: &str) -> Result<u64, String> {
+    let source = path.to_path_buf();
+    let (holding, bytes) =
+        task::spawn_blocking(move || (volume_holding_dir(&source), tree_size(&source)))
+            .await
+            .map_err(|e| e.to_string())?;
+
+    let holding = match holding {
+        Some(dir) => dir,
+        None if bytes <= MAX_HOLDING_BYTES => home_holding_dir()?,
+        None => {
+            return Err(format!(
+                "Item is too large to keep for undo: {}",
+                path.display()
+            ))
+        }
+    };
+
+    move_path(path, &holding.join(key))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(bytes)
+}
+
+/// Moves the item parked under `key` back to `path`.
+async fn unstage(path: &Path, key: &str) -> Result<(), String> {
+    let staged = find_staged(path, key)
+        .ok_or_else(|| format!("Item is no longer kept for undo: {}", path.display()))?;
+
+    ensure_vacant(path).await?;
+    move_path(&staged, path).await.map_err(|e| e.to_string())
+}
+
+fn get_journal() -> &'static Mutex<OperationHistory> {
+    static JOURNAL: OnceLock<Mutex<OperationHistory>> = OnceLock::new();
+
+    JOURNAL.get_or_init(|| {
+        let history = journal_path()
+            .ok()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str::<OperationHistory>(&contents).ok())
+            .map(|mut history| {
+                history.cursor = history.cursor.min(history.operations.len());
+                history
+            })
+            .unwrap_or_default();
+
+        Mutex::new(history)
+    })
+}
+
+async fn save_journal(history: &OperationHistory) -> Result<(), String> {
+    let path = journal_path()?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create journal directory: {}", e))?;
+    }
+
+    let contents = serde_json::to_string_pretty(history)
+        .map_err(|e| format!("Failed to serialize journal: {}", e))?;
+
+    // Written beside the real file and renamed over it, so a crash cannot
+    // leave it truncated and the items it tracks unrecoverable.
+    let temporary = path.with_extension("json.tmp");
+
+    fs::write(&temporary, contents)
+        .await
+        .map_err(|e| format!("Failed to write journal: {}", e))?;
+
+    fs::rename(&temporary, &path)
+        .await
+        .map_err(|e| format!("Failed to write journal: {}", e))
+}
+
+async fn push_record(
+    history: &mut OperationHistory,
+    record: OperationRecord,
+) -> Result<(), String> {
+    let cursor = history.cursor;
+    let discarded = history.operations.split_off(cursor);
+    for record in &discarded {
+        purge_staged(record).await;
+    }
+
+    history.operations.push(record);
+
+    if history.operations.len() > MAX_JOURNAL_ENTRIES {
+        let excess = history.operations.len() - MAX_JOURNAL_ENTRIES;
+        let expired = history.operations.drain(..excess).collect::<Vec<_>>();
+        for record in &expired {
+            purge_staged(record).await;
+        }
+    }
+
+    history.cursor = history.operations.len();
+    enforce_holding_limit(history).await;
+
+    save_journal(history).await
+}
+
+/// Drops the oldest records until what the journal keeps parked fits in
+/// `MAX_HOLDING_BYTES`. The newest record is always kept.
+async fn enforce_holding_limit(history: &mut OperationHistory) {
+    let mut total = history
+        .operations
+        .iter()
+        .map(|record| record.staged_bytes)
+        .sum::<u64>();
+
+    let mut excess = 0;
+    while total > MAX_HOLDING_BYTES && excess + 1 < history.operations.len() {
+        total -= history.operations[excess].staged_bytes;
+        excess += 1;
+    }
+
+    let expired = history.operations.drain(..excess).collect::<Vec<_>>();
+    for record in &expired {
+        purge_staged(record).await;
+    }
+
+    history.cursor = history.cursor.saturating_sub(excess);
+}
+
+pub(crate) async fn record_operation(operation: OperationKind) -> Result<(), String> {
+    let mut history = get_journal().lock().await;
+
+    let record = OperationRecord {
+        id: uuid::Uuid::new_v4().to_string(),
+        timestamp: Utc::now(),
+        operation,
+        staged_bytes: 0,
+    };
+
+    push_record(&mut history, record).await
+}
+
+/// Parks `path` for undo. Staging can mean copying a large tree to another
+/// filesystem, so the journal is only locked to record the result.
+pub(crate) async fn stage_deletion(path: &Path) -> Result<(), String> {
+    let id = uuid::Uuid::new_v4().to_string();
+    let staged_bytes = stage(path, &id)
+        .await
+        .map_err(|e| format!("Failed to delete item: {}", e))?;
+
+    let record = OperationRecord {
+        id,
+        timestamp: Utc::now(),
+        operation: OperationKind::Delete {
+            path: path.to_string_lossy().to_string(),
+        },
+        staged_bytes,
+    };
+
+    push_record(&mut *get_journal().lock().await, record).await
+}
+
+/// Deletions parked one at a time and recorded as a single undoable step,
+/// for jobs that remove many items.
+pub(crate) struct DeletionBatch {
+    id: String,
+    paths: Vec<String>,
+    staged_bytes: u64,
+}
+
+impl DeletionBatch {
+    pub(crate) fn new() -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            paths: Vec::new(),
+            staged_bytes: 0,
+        }
+    }
+
+    /// Parks `path` for undo and returns its size.
+    pub(crate) async fn stage(&mut self, path: &Path) -> Result<u64, String> {
+        let key = format!("{}-{}", self.id, self.paths.len());
+        let bytes = stage(path, &key)
+            .await
+            .map_err(|e| format!("Failed to delete item: {}", e))?;
+
+        self.paths.push(path.to_string_lossy().to_string());
+        self.staged_bytes += bytes;
+
+        Ok(bytes)
+    }
+
+    /// Records what was staged, which is also needed when the job stopped
+    /// part way so the items parked so far can be restored.
+    pub(crate) async fn commit(self) -> Result<(), String> {
+        if self.paths.is_empty() {
+            return Ok(());
+        }
+
+        let mut history = get_journal().lock().await;
+
+        let record = OperationRecord {
+            id: self.id,
+            timestamp: Utc::now(),
+            operation: OperationKind::DeleteMany { paths: self.paths },
+            staged_bytes: self.staged_bytes,
+        };
+
+        push_record(&mut history, record).await
+    }
+}
+
+async fn purge_staged(record: &OperationRecord) {
+    for (path, key) in staged_items(record) {
+        if let Some(staged) = find_staged(&path, &key) {
+            let _ = remove_all(&staged).await;
+        }
+    }
+}
+
+/// Removes `path` and everything below it, first giving the owner full
+/// access to directories so read-only trees can be emptied.
+pub(crate) async fn remove_all(path: &Path) -> std::io::Result<()> {
+    let path = path.to_path_buf();
+
+    task::spawn_blocking(move || remove_all_blocking(&path))
+        .await
+        .map_err(std::io::Error::other)?
+}
+
+fn remove_all_blocking(path: &Path) -> std::io::Result<()> {
+    let metadata = std::fs::symlink_metadata(path)?;
+
+    if !metadata.is_dir() {
+        return std::fs::remove_file(path);
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = metadata.permissions().mode();
+        if mode & 0o700 != 0o700 {
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode | 0o700))?;
+        }
+    }
+
+    for entry in std::fs::read_dir(path)? {
+        remove_all_blocking(&entry?.path())?;
+    }
+
+    std::fs::remove_dir(path)
+}
+
+/// `rename` that also moves directories without write permission, which
+/// Linux refuses to give a new parent because their `..` entry changes.
+async fn rename_path(from: &Path, to: &Path) -> std::io::Result<()> {
+    let error = match fs::rename(from, to).await {
+        Ok(()) => return Ok(()),
+        Err(e) => e,
+    };
+
+    #[cfg(unix)]
+    if error.kind() == std::io::ErrorKind::PermissionDenied {
+        use std::fs::Permissions;
+        use std::os::unix::fs::PermissionsExt;
+
+        let metadata = fs::symlink_metadata(from).await?;
+        let mode = metadata.permissions().mode();
+
+        if metadata.is_dir() && mode & 0o200 == 0 {
+            fs::set_permissions(from, Permissions::from_mode(mode | 0o200)).await?;
+
+            return match fs::rename(from, to).await {
+                Ok(()) => {
+                    let _ = fs::set_permissions(to, Permissions::from_mode(mode)).await;
+                    Ok(())
+                }
+                Err(e) => {
+                    let _ = fs::set_permissions(from, Permissions::from_mode(mode)).await;
+                    Err(e)
+                }
+            };
+        }
+    }
+
+    Err(error)
+}
+
+async fn move_path(from: &Path, to: &Path) -> std::io::Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+
+    match rename_path(from, to).await {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => {
+            if let Err(e) = copy_recursive(from.to_path_buf(), to.to_path_buf()).await {
+                let _ = remove_all(to).await;
+                return Err(e);
+            }
+
+            // The copy is the only complete one if this fails part way, so
+            // it is kept.
+            remove_all(from).await
+        }
+        Err(e) => Err(e),
+    }
+}
+
+async fn copy_recursive(from: PathBuf, to: PathBuf) -> std::io::Result<()> {
+    let metadata = fs::symlink_metadata(&from).await?;
+
+    #[cfg(unix)]
+    if metadata.is_symlink() {
+        let target = fs::read_link(&from).await?;
+        return fs::symlink(target, &to).await;
+    }
+
+    if metadata.is_dir() {
+        fs::create_dir_all(&to).await?;
+
+        let mut entries = fs::read_dir(&from).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            Box::pin(copy_recursive(entry.path(), to.join(entry.file_name()))).await?;
+        }
+
+        // Applied last so read-only directories can still be filled.
+        fs::set_permissions(&to, metadata.permissions()).await
+    } else {
+        fs::copy(&from, &to).await.map(|_| ())
+    }
+}
+
+async fn ensure_vacant(path: &Path) -> Result<(), String> {
+    if fs::symlink_metadata(path).await.is_ok() {
+        return Err(format!("An item already exists at: {}", path.display()));
+    }
+
+    Ok(())
+}
+
+async fn ensure_present(path: &Path) -> Result<(), String> {
+    if fs::symlink_metadata(path).await.is_err() {
+        return Err(format!("Item no longer exists: {}", path.display()));
+    }
+
+    Ok(())
+}
+
+/// Undoes `record` and returns the size of what it leaves parked.
+async fn revert(record: &OperationRecord) -> Result<u64, String> {
+    match &record.operation {
+        OperationKind::CreateFile { path }
+        | OperationKind::CreateDirectory { path }
+        | OperationKind::CreateLink { path, .. } => {
+            let path = Path::new(path);
+            ensure_present(path).await?;
+            stage(path, &record.id)
+                .await
+                .map_err(|e| format!("Failed to undo creation: {}", e))
+        }
+        OperationKind::Rename { from, to } => {
+            let (from, to) = (Path::new(from), Path::new(to));
+            ensure_present(to).await?;
+            ensure_vacant(from).await?;
+            move_path(to, from)
+                .await
+                .map(|_| 0)
+                .map_err(|e| format!("Failed to undo rename: {}", e))
+        }
+        OperationKind::Copy { to, .. } => {
+            let to = Path::new(to);
+            ensure_present(to).await?;
+            stage(to, &record.id)
+                .await
+                .map_err(|e| format!("Failed to undo copy: {}", e))
+        }
+        OperationKind::BatchRename { renames } => {
+            let reversed = renames
+                .iter()
+                .map(|pair| RenamePair {
+                    from: pair.to.clone(),
+                    to: pair.from.clone(),
+                })
+                .collect::<Vec<_>>();
+            batch_rename::apply_renames(&reversed).await.map(|_| 0)
+        }
+        OperationKind::Delete { path } => unstage(Path::new(path), &record.id)
+            .await
+            .map(|_| 0)
+            .map_err(|e| format!("Failed to restore deleted item: {}", e)),
+        OperationKind::DeleteMany { .. } => {
+            let items = staged_items(record);
+
+            for (path, key) in &items {
+                if find_staged(path, key).is_none() {
+                    return Err(format!(
+                        "Item is no longer kept for undo: {}",
+                        path.display()
+                    ));
+                }
+                ensure_vacant(path).await?;
+            }
+
+            for (path, key) in &items {
+                unstage(path, key)
+                    .await
+                    .map_err(|e| format!("Failed to restore deleted item: {}", e))?;
+            }
+
+            Ok(0)
+        }
+    }
+}
+
+/// Redoes `record` and returns the size of what it leaves parked.
+async fn reapply(record: &OperationRecord) -> Result<u64, String> {
+    match &record.operation {
+        OperationKind::CreateFile { path }
+        | OperationKind::CreateDirectory { path }
+        | OperationKind::CreateLink { path, .. } => unstage(Path::new(path), &record.id)
+            .await
+            .map(|_| 0)
+            .map_err(|e| format!("Failed to redo creation: {}", e)),
+        OperationKind::Rename { from, to } => {
+            let (from, to) = (Path::new(from), Path::new(to));
+            ensure_present(from).await?;
+            ensure_vacant(to).await?;
+            move_path(from, to)
+                .await
+                .map(|_| 0)
+                .map_err(|e| format!("Failed to redo rename: {}", e))
+        }
+        OperationKind::Copy { to, .. } => unstage(Path::new(to), &record.id)
+            .await
+            .map(|_| 0)
+            .map_err(|e| format!("Failed to redo copy: {}", e)),
+        OperationKind::BatchRename { renames } => {
+            batch_rename::apply_renames(renames).await.map(|_| 0)
+        }
+        OperationKind::Delete { path } => {
+            let path = Path::new(path);
+            ensure_present(path).await?;
+            stage(path, &record.id)
+                .await
+                .map_err(|e| format!("Failed to redo deletion: {}", e))
+        }
+        OperationKind::DeleteMany { paths } => {
+            for path in paths {
+                ensure_present(Path::new(path)).await?;
+            }
+
+            let mut staged_bytes = 0;
+            for (path, key) in staged_items(record) {
+                staged_bytes += stage(&path, &key)
+                    .await
+                    .map_err(|e| format!("Failed to redo deletion: {}", e))?;
+            }
+
+            Ok(staged_bytes)
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn undo_last_operation() -> Result<OperationRecord, String> {
+    let mut history = get_journal().lock().await;
+
+    if history.cursor == 0 {
+        return Err("Nothing to undo".to_string());
+    }
+
+    let index = history.cursor - 1;
+    let staged_bytes = revert(&history.operations[index]).await?;
+    history.operations[index].staged_bytes = staged_bytes;
+    let record = history.operations[index].clone();
+
+    history.cursor -= 1;
+    enforce_holding_limit(&mut history).await;
+    save_journal(&history).await?;
+
+    Ok(record)
+}
+
+#[tauri::command]
+pub async fn redo_operation() -> Result<OperationRecord, String> {
+    let mut history = get_journal().lock().await;
+
+    if history.cursor >= history.operations.len() {
+        return Err("Nothing to redo".to_string());
+    }
+
+    let index = history.cursor;
+    let staged_bytes = reapply(&history.operations[index]).await?;
+    history.operations[index].staged_bytes = staged_bytes;
+    let record = history.operations[index].clone();
+
+    history.cursor += 1;
+    enforce_holding_limit(&mut history).await;
+    save_journal(&history).await?;
+
+    Ok(record)
+}
+
+#[tauri::command]
+pub async fn list_operations() -> Result<OperationHistory, String> {
+    Ok(get_journal().lock().await.clone())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// The journal is global, so tests take turns, each starting from an
+    /// empty history kept in a scratch data directory.
+    pub(crate) async fn fresh_journal() -> (tokio::sync::MutexGuard<'static, ()>, tempfile::TempDir)
+    {
+        static DATA: OnceLock<tempfile::TempDir> = OnceLock::new();
+        static TURN: OnceLock<Mutex<()>> = OnceLock::new();
+
+        DATA.get_or_init(|| {
+            let dir = tempfile::tempdir().unwrap();
+            std::env::set_var("XDG_DATA_HOME", dir.path());
+            dir
+        });
+
+        let turn = TURN.get_or_init(|| Mutex::new(())).lock().await;
+        *get_journal().lock().await = OperationHistory::default();
+
+        (turn, tempfile::tempdir().unwrap())
+    }
+
+    fn text(path: &Path) -> String {
+        path.to_string_lossy().to_string()
+    }
+
+    async fn rename_record(dir: &Path, index: usize) {
+        record_operation(OperationKind::Rename {
+            from: text(&dir.join(format!("from-{}", index))),
+            to: text(&dir.join(format!("to-{}", index))),
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn undo_and_redo_move_a_rename_back_and_forth() {
+        let (_turn, dir) = fresh_journal().await;
+        let (from, to) = (dir.path().join("a.txt"), dir.path().join("b.txt"));
+        std::fs::write(&to, "a").unwrap();
+
+        record_operation(OperationKind::Rename {
+            from: text(&from),
+            to: text(&to),
+        })
+        .await
+        .unwrap();
+
+        undo_last_operation().await.unwrap();
+        assert!(from.exists() && !to.exists());
+        assert!(undo_last_operation().await.is_err());
+
+        redo_operation().await.unwrap();
+        assert!(!from.exists() && to.exists());
+        assert!(redo_operation().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn undo_refuses_to_replace_an_item_that_took_the_old_place() {
+        let (_turn, dir) = fresh_journal().await;
+        let (from, to) = (dir.path().join("a.txt"), dir.path().join("b.txt"));
+        std::fs::write(&from, "new").unwrap();
+        std::fs::write(&to, "old").unwrap();
+
+        record_operation(OperationKind::Rename {
+            from: text(&from),
+            to: text(&to),
+        })
+        .await
+        .unwrap();
+
+        assert!(undo_last_operation().await.is_err());
+        assert_eq!(std::fs::read_to_string(&from).unwrap(), "new");
+        assert_eq!(list_operations().await.unwrap().cursor, 1);
+    }
+
+    #[tokio::test]
+    async fn deleted_items_are_restored_by_undo_and_parked_again_by_redo() {
+        let (_turn, dir) = fresh_journal().await;
+        let path = dir.path().join("notes");
+        std::fs::create_dir(&path).unwrap();
+        std::fs::write(path.join("todo.txt"), "hello").unwrap();
+
+        stage_deletion(&path).await.unwrap();
+        assert!(!path.exists());
+        assert_eq!(
+            list_operations().await.unwrap().operations[0].staged_bytes,
+            5
+        );
+
+        undo_last_operation().await.unwrap();
+        assert_eq!(
+            std::fs::read_to_string(path.join("todo.txt")).unwrap(),
+            "hello"
+        );
+        assert_eq!(
+            list_operations().await.unwrap().operations[0].staged_bytes,
+            0
+        );
+
+        redo_operation().await.unwrap();
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn batched_deletions_are_undone_together() {
+        let (_turn, dir) = fresh_journal().await;
+        let paths = ["a.txt", "b.txt"].map(|name| dir.path().join(name));
+        for path in &paths {
+            std::fs::write(path, "x").unwrap();
+        }
+
+        let mut batch = DeletionBatch::new();
+        for path in &paths {
+            batch.stage(path).await.unwrap();
+        }
+        batch.commit().await.unwrap();
+
+        let history = list_operations().await.unwrap();
+        assert_eq!(history.operations.len(), 1);
+        assert_eq!(history.operations[0].staged_bytes, 2);
+        assert!(paths.iter().all(|path| !path.exists()));
+
+        undo_last_operation().await.unwrap();
+        assert!(paths.iter().all(|path| path.exists()));
+    }
+
+    #[tokio::test]
+    async fn recording_after_an_undo_drops_the_undone_records() {
+        let (_turn, dir) = fresh_journal().await;
+        let created = dir.path().join("new.txt");
+        std::fs::write(&created, "x").unwrap();
+
+        record_operation(OperationKind::CreateFile {
+            path: text(&created),
+        })
+        .await
+        .unwrap();
+        undo_last_operation().await.unwrap();
+
+        let id = list_operations().await.unwrap().operations[0].id.clone();
+        let parked = find_staged(&created, &id).unwrap();
+        assert!(!created.exists());
+
+        rename_record(dir.path(), 0).await;
+
+        let history = list_operations().await.unwrap();
+        assert_eq!(history.operations.len(), 1);
+        assert_eq!(history.cursor, 1);
+        assert!(matches!(
+            history.operations[0].operation,
+            OperationKind::Rename { .. }
+        ));
+        assert!(!parked.exists());
+    }
+
+    #[tokio::test]
+    async fn the_oldest_records_expire_with_what_they_parked() {
+        let (_turn, dir) = fresh_journal().await;
+        let path = dir.path().join("old.txt");
+        std::fs::write(&path, "x").unwrap();
+
+        stage_deletion(&path).await.unwrap();
+        let id = list_operations().await.unwrap().operations[0].id.clone();
+        let parked = find_staged(&path, &id).unwrap();
+
+        for index in 0..MAX_JOURNAL_ENTRIES {
+            rename_record(dir.path(), index).await;
+        }
+
+        let history = list_operations().await.unwrap();
+        assert_eq!(history.operations.len(), MAX_JOURNAL_ENTRIES);
+        assert_eq!(history.cursor, MAX_JOURNAL_ENTRIES);
+        assert!(history.operations.iter().all(|record| record.id != id));
+        assert!(!parked.exists());
+    }
+
+    #[tokio::test]
+    async fn the_journal_is_saved_without_leaving_a_temporary_file() {
+        let (_turn, dir) = fresh_journal().await;
+        rename_record(dir.path(), 0).await;
+
+        let path = journal_path().unwrap();
+        let saved =
+            serde_json::from_str::<OperationHistory>(&std::fs::read_to_string(&path).unwrap())
+                .unwrap();
+
+        assert_eq!(saved.operations.len(), 1);
+        assert!(!path.with_extension("json.tmp").exists());
+    }
+}
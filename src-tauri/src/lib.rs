@@ -1,9 +1,37 @@
+mod applications;
+mod archives;
+mod batch_rename;
+mod bookmarks;
+mod checksums;
+mod compare;
+mod custom_actions;
+mod disk_usage;
+mod duplicates;
+mod file_types;
+mod jobs;
+mod journal;
+mod links;
+mod media;
+mod permissions;
+mod places;
+mod preview;
+mod recent;
+mod session;
+mod settings;
+mod templates;
+mod terminal;
+mod thumbnails;
+mod volumes;
+
+use file_types::FileCategory;
 use futures::future::join_all;
+use journal::OperationKind;
 use serde::{Deserialize, Serialize};
+use settings::SortKey;
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
-use std::time::{Duration, Instant};
+use std::time::Instant;
 use tauri::{AppHandle, Emitter};
 use tokio::{fs, task};
 
@@ -46,6 +74,58 @@ pub struct FileEntry {
     size: Option<u64>,
     modified: Option<String>,
     item_count: Option<u32>,
+    mode: Option<u32>,
+    owner: Option<String>,
+    group: Option<String>,
+    mime_type: Option<String>,
+    category: Option<FileCategory>,
+}
+
+fn build_file_entry(
+    name: String,
+    path: &Path,
+    metadata: Option<&std::fs::Metadata>,
+    item_count: Option<u32>,
+) -> FileEntry {
+    let is_directory = path.is_dir();
+
+    let size = if !is_directory {
+        metadata.map(|m| m.len())
+    } else {
+        None
+    };
+
+    let modified = metadata.and_then(|m| {
+        m.modified().ok().and_then(|time| {
+            use std::time::UNIX_EPOCH;
+            let duration = time.duration_since(UNIX_EPOCH).ok()?;
+            Some(format!("{}", duration.as_secs()))
+        })
+    });
+
+    let ownership = metadata.map(permissions::ownership).unwrap_or_default();
+
+    let (mime_type, category) = if is_directory {
+        (None, None)
+    } else {
+        let mime_type = file_types::mime_from_extension(path);
+        let category = file_types::category_for(mime_type.as_deref(), path, ownership.mode);
+        (mime_type, Some(category))
+    };
+
+    FileEntry {
+        name,
+        path: path.to_string_lossy().to_string(),
+        is_directory,
+        size,
+        modified,
+        item_count,
+        mode: ownership.mode,
+        owner: ownership.owner,
+        group: ownership.group,
+        mime_type,
+        category,
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -62,8 +142,6 @@ struct CacheEntry {
 
 type DirectoryCache = Arc<Mutex<HashMap<String, CacheEntry>>>;
 
-static CACHE_TTL: Duration = Duration::from_secs(30);
-
 fn get_directory_cache() -> DirectoryCache {
     static CACHE: std::sync::OnceLock<DirectoryCache> = std::sync::OnceLock::new();
 
@@ -79,8 +157,11 @@ async fn list_directory(path: String) -> Result<DirectoryContents, String> {
     {
         let cache_guard = cache.lock().unwrap();
         if let Some(entry) = cache_guard.get(&path) {
-            if entry.timestamp.elapsed() < CACHE_TTL {
-                return Ok(entry.data.clone());
+            if entry.timestamp.elapsed() < settings::cache_ttl() {
+                // The default sort may have changed since it was cached.
+                let mut contents = entry.data.clone();
+                sort_entries(&mut contents.entries);
+                return Ok(contents);
             }
         }
     }
@@ -97,7 +178,8 @@ async fn list_directory(path: String) -> Result<DirectoryContents, String> {
             },
         );
 
-        cache_guard.retain(|_, entry| entry.timestamp.elapsed() < CACHE_TTL);
+        let ttl = settings::cache_ttl();
+        cache_guard.retain(|_, entry| entry.timestamp.elapsed() < ttl);
     }
 
     result
@@ -108,7 +190,53 @@ async fn list_directory_no_cache(path: String) -> Result<DirectoryContents, Stri
     list_directory_uncached(path).await
 }
 
+/// Sorts directories first, then by the default sort from the settings
+/// with the name breaking ties.
+fn sort_entries(entries: &mut [FileEntry]) {
+    let order = settings::default_sort();
+
+    let modified = |entry: &FileEntry| {
+        entry
+            .modified
+            .as_deref()
+            .and_then(|modified| modified.parse::<i64>().ok())
+    };
+    let extension = |entry: &FileEntry| {
+        Path::new(&entry.name)
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase())
+    };
+
+    entries.sort_by(|a, b| {
+        b.is_directory.cmp(&a.is_directory).then_with(|| {
+            let ordering = match order.key {
+                SortKey::Name => std::cmp::Ordering::Equal,
+                SortKey::Size => a.size.cmp(&b.size),
+                SortKey::Modified => modified(a).cmp(&modified(b)),
+                SortKey::Type => extension(a).cmp(&extension(b)),
+            }
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+
+            if order.descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        })
+    });
+}
+
 async fn list_directory_uncached(path: String) -> Result<DirectoryContents, String> {
+    if let Some((archive, inner)) = archives::split_archive_path(&path) {
+        let mut entries = archives::list_members(archive, inner).await?;
+        sort_entries(&mut entries);
+
+        return Ok(DirectoryContents {
+            current_path: path,
+            entries,
+        });
+    }
+
     let dir_path = Path::new(&path);
 
     if !dir_path.exists() {
@@ -141,19 +269,6 @@ async fn list_directory_uncached(path: String) -> Result<DirectoryContents, Stri
                 .to_string();
 
             let metadata = entry.metadata().await.ok();
-            let size = if !file_path.is_dir() {
-                metadata.as_ref().map(|m| m.len())
-            } else {
-                None
-            };
-
-            let modified = metadata.as_ref().and_then(|m| {
-                m.modified().ok().and_then(|time| {
-                    use std::time::UNIX_EPOCH;
-                    let duration = time.duration_since(UNIX_EPOCH).ok()?;
-                    Some(format!("{}", duration.as_secs()))
-                })
-            });
 
             let item_count = if file_path.is_dir() {
                 count_directory_items_async(&file_path).await
@@ -161,14 +276,7 @@ async fn list_directory_uncached(path: String) -> Result<DirectoryContents, Stri
                 None
             };
 
-            FileEntry {
-                name: file_name,
-                path: file_path.to_string_lossy().to_string(),
-                is_directory: file_path.is_dir(),
-                size,
-                modified,
-                item_count,
-            }
+            build_file_entry(file_name, &file_path, metadata.as_ref(), item_count)
         });
         entry_tasks.push(task);
     }
@@ -179,11 +287,7 @@ async fn list_directory_uncached(path: String) -> Result<DirectoryContents, Stri
         .filter_map(|result| result.ok())
         .collect::<Vec<_>>();
 
-    entries.sort_by(|a, b| match (a.is_directory, b.is_directory) {
-        (true, false) => std::cmp::Ordering::Less,
-        (false, true) => std::cmp::Ordering::Greater,
-        _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-    });
+    sort_entries(&mut entries);
 
     Ok(DirectoryContents {
         current_path: path.clone(),
@@ -293,7 +397,7 @@ async fn search_recursive_async(
             .and_then(|n| n.to_str())
             .unwrap_or("Unknown");
 
-        if file_name.starts_with('.') {
+        if file_name.starts_with('.') || settings::is_search_excluded(file_name) {
             continue;
         }
 
@@ -318,20 +422,6 @@ async fn search_recursive_async(
                 }
 
                 let metadata = tokio::fs::metadata(&file_path_clone).await.ok();
-                let size = if !file_path_clone.is_dir() {
-                    metadata.as_ref().map(|m| m.len())
-                } else {
-                    None
-                };
-
-                let modified = metadata.as_ref().and_then(|m| {
-                    m.modified().ok().and_then(|time| {
-                        use std::time::UNIX_EPOCH;
-                        let duration = time.duration_since(UNIX_EPOCH).ok()?;
-                        Some(format!("{}", duration.as_secs()))
-                    })
-                });
-
                 let item_count = if file_path_clone.is_dir() {
                     let results_len = {
                         let results_guard = results_clone.lock().unwrap();
@@ -347,14 +437,12 @@ async fn search_recursive_async(
                     None
                 };
 
-                let file_entry = FileEntry {
-                    name: file_name_clone,
-                    path: file_path_clone.to_string_lossy().to_string(),
-                    is_directory: file_path_clone.is_dir(),
-                    size,
-                    modified,
+                let file_entry = build_file_entry(
+                    file_name_clone,
+                    &file_path_clone,
+                    metadata.as_ref(),
                     item_count,
-                };
+                );
 
                 let should_continue = {
                     let mut results_guard = results_clone.lock().unwrap();
@@ -539,7 +627,7 @@ async fn search_recursive_streaming(
             .and_then(|n| n.to_str())
             .unwrap_or("Unknown");
 
-        if file_name.starts_with('.') {
+        if file_name.starts_with('.') || settings::is_search_excluded(file_name) {
             continue;
         }
 
@@ -565,20 +653,6 @@ async fn search_recursive_streaming(
                 }
 
                 let metadata = tokio::fs::metadata(&file_path_clone).await.ok();
-                let size = if !file_path_clone.is_dir() {
-                    metadata.as_ref().map(|m| m.len())
-                } else {
-                    None
-                };
-
-                let modified = metadata.as_ref().and_then(|m| {
-                    m.modified().ok().and_then(|time| {
-                        use std::time::UNIX_EPOCH;
-                        let duration = time.duration_since(UNIX_EPOCH).ok()?;
-                        Some(format!("{}", duration.as_secs()))
-                    })
-                });
-
                 let item_count = if file_path_clone.is_dir() {
                     let results_len = {
                         let results_guard = results_count_clone.lock().unwrap();
@@ -594,14 +668,12 @@ async fn search_recursive_streaming(
                     None
                 };
 
-                let file_entry = FileEntry {
-                    name: file_name_clone,
-                    path: file_path_clone.to_string_lossy().to_string(),
-                    is_directory: file_path_clone.is_dir(),
-                    size,
-                    modified,
+                let file_entry = build_file_entry(
+                    file_name_clone,
+                    &file_path_clone,
+                    metadata.as_ref(),
                     item_count,
-                };
+                );
 
                 let should_continue = {
                     let mut results_guard = results_count_clone.lock().unwrap();
@@ -696,13 +768,26 @@ async fn search_recursive_streaming(
 }
 
 #[tauri::command]
-async fn create_file(path: String) -> Result<(), String> {
+async fn create_file(
+    path: String,
+    content: Option<String>,
+    template: Option<String>,
+) -> Result<(), String> {
     let file_path = Path::new(&path);
 
     if file_path.exists() {
         return Err(format!("File already exists: {}", path));
     }
 
+    if content.is_some() && template.is_some() {
+        return Err("Cannot use both initial content and a template".to_string());
+    }
+
+    let template_path = template
+        .as_deref()
+        .map(templates::resolve_template)
+        .transpose()?;
+
     if let Some(parent) = file_path.parent() {
         if !parent.exists() {
             fs::create_dir_all(parent)
@@ -711,11 +796,13 @@ async fn create_file(path: String) -> Result<(), String> {
         }
     }
 
-    fs::write(file_path, "")
-        .await
-        .map_err(|e| format!("Failed to create file: {}", e))?;
+    match template_path {
+        Some(template_path) => fs::copy(template_path, file_path).await.map(|_| ()),
+        None => fs::write(file_path, content.unwrap_or_default()).await,
+    }
+    .map_err(|e| format!("Failed to create file: {}", e))?;
 
-    Ok(())
+    journal::record_operation(OperationKind::CreateFile { path }).await
 }
 
 #[tauri::command]
@@ -730,7 +817,7 @@ async fn create_directory(path: String) -> Result<(), String> {
         .await
         .map_err(|e| format!("Failed to create directory: {}", e))?;
 
-    Ok(())
+    journal::record_operation(OperationKind::CreateDirectory { path }).await
 }
 
 #[tauri::command]
@@ -755,7 +842,11 @@ async fn rename_item(old_path: String, new_name: String) -> Result<(), String> {
         .await
         .map_err(|e| format!("Failed to rename item: {}", e))?;
 
-    Ok(())
+    journal::record_operation(OperationKind::Rename {
+        from: old_path,
+        to: new_path.to_string_lossy().to_string(),
+    })
+    .await
 }
 
 #[tauri::command]
@@ -766,17 +857,19 @@ async fn delete_item(path: String) -> Result<(), String> {
         return Err(format!("Item does not exist: {}", path));
     }
 
-    if item_path.is_dir() {
-        fs::remove_dir_all(item_path)
-            .await
-            .map_err(|e| format!("Failed to delete directory: {}", e))?;
-    } else {
-        fs::remove_file(item_path)
-            .await
-            .map_err(|e| format!("Failed to delete file: {}", e))?;
-    }
+    journal::stage_deletion(item_path).await
+}
 
-    Ok(())
+fn app_data_dir() -> Result<PathBuf, String> {
+    dirs::data_local_dir()
+        .map(|dir| dir.join("wiregui"))
+        .ok_or_else(|| "Failed to get application data directory".to_string())
+}
+
+fn app_config_dir() -> Result<PathBuf, String> {
+    dirs::config_dir()
+        .map(|dir| dir.join("wiregui"))
+        .ok_or_else(|| "Failed to get application configuration directory".to_string())
 }
 
 fn should_skip_system_file(path: &Path) -> bool {
@@ -806,16 +899,79 @@ pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_opener::init())
+        .setup(|app| {
+            session::restore_window(app.handle());
+            volumes::watch_volumes(app.handle().clone());
+            Ok(())
+        })
+        .on_window_event(|window, event| match event {
+            tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_) => {
+                session::schedule_remember_window(window);
+            }
+            tauri::WindowEvent::CloseRequested { .. } => session::remember_window(window),
+            _ => {}
+        })
         .invoke_handler(tauri::generate_handler![
             list_directory,
             list_directory_no_cache,
             get_home_directory,
+            settings::get_settings,
+            settings::update_settings,
+            session::get_session,
+            session::save_session,
+            places::get_standard_places,
+            bookmarks::list_bookmarks,
+            bookmarks::add_bookmark,
+            bookmarks::remove_bookmark,
+            bookmarks::reorder_bookmarks,
+            bookmarks::set_gtk_bookmarks_sync,
+            recent::list_recent_files,
+            recent::open_file,
+            recent::clear_recent,
+            applications::list_applications_for,
+            applications::open_with,
+            applications::set_default_application,
+            terminal::open_terminal,
+            custom_actions::list_custom_actions,
+            custom_actions::save_custom_action,
+            custom_actions::delete_custom_action,
+            custom_actions::actions_for_paths,
+            custom_actions::run_custom_action,
             search_files,
             search_files_streaming,
             create_file,
             create_directory,
             rename_item,
-            delete_item
+            delete_item,
+            journal::undo_last_operation,
+            journal::redo_operation,
+            journal::list_operations,
+            jobs::submit_job,
+            jobs::list_jobs,
+            jobs::pause_job,
+            jobs::resume_job,
+            jobs::cancel_job,
+            jobs::clear_finished_jobs,
+            batch_rename::batch_rename,
+            permissions::set_permissions,
+            permissions::set_owner,
+            links::create_symlink,
+            links::create_hardlink,
+            templates::list_templates,
+            preview::read_file_preview,
+            preview::read_file_hex,
+            file_types::detect_file_types,
+            media::get_media_metadata,
+            checksums::compute_checksums,
+            checksums::verify_checksum_file,
+            archives::create_archive,
+            archives::extract_archive,
+            disk_usage::analyze_disk_usage,
+            duplicates::find_duplicates,
+            compare::compare_directories,
+            compare::sync_directories,
+            volumes::list_volumes,
+            thumbnails::get_thumbnail
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,135 @@
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+use crate::journal::{self, OperationKind};
+
+fn resolve_parent(path: &Path) -> PathBuf {
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => std::fs::canonicalize(parent)
+            .map(|parent| parent.join(name))
+            .unwrap_or_else(|_| path.to_path_buf()),
+        _ => path.to_path_buf(),
+    }
+}
+
+fn relative_path(base: &Path, target: &Path) -> PathBuf {
+    let base = base.components().collect::<Vec<_>>();
+    let target = target.components().collect::<Vec<_>>();
+
+    let common = base
+        .iter()
+        .zip(target.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    if common == 0 {
+        return target.iter().collect();
+    }
+
+    let mut relative = PathBuf::new();
+    for _ in common..base.len() {
+        relative.push("..");
+    }
+    for component in &target[common..] {
+        relative.push(component);
+    }
+
+    if relative.as_os_str().is_empty() {
+        relative.push(".");
+    }
+
+    relative
+}
+
+async fn prepare_link_path(link_path: &Path) -> Result<(), String> {
+    if fs::symlink_metadata(link_path).await.is_ok() {
+        return Err(format!("Item already exists: {}", link_path.display()));
+    }
+
+    if let Some(parent) = link_path.parent() {
+        if !parent.exists() {
+            fs::create_dir_all(parent)
+                .await
+                .map_err(|e| format!("Failed to create parent directories: {}", e))?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+async fn symlink(target: &Path, link_path: &Path, _is_dir: bool) -> std::io::Result<()> {
+    fs::symlink(target, link_path).await
+}
+
+#[cfg(windows)]
+async fn symlink(target: &Path, link_path: &Path, is_dir: bool) -> std::io::Result<()> {
+    if is_dir {
+        fs::symlink_dir(target, link_path).await
+    } else {
+        fs::symlink_file(target, link_path).await
+    }
+}
+
+#[tauri::command]
+pub async fn create_symlink(
+    target: String,
+    link_path: String,
+    relative: bool,
+) -> Result<(), String> {
+    let target_path = Path::new(&target);
+    let link = Path::new(&link_path);
+
+    prepare_link_path(link).await?;
+
+    let link_target = if relative && target_path.is_absolute() {
+        let link_dir = resolve_parent(link)
+            .parent()
+            .map(Path::to_path_buf)
+            .ok_or_else(|| "Cannot determine parent directory".to_string())?;
+        relative_path(&link_dir, &resolve_parent(target_path))
+    } else {
+        target_path.to_path_buf()
+    };
+
+    let is_dir = link
+        .parent()
+        .map(|parent| parent.join(&link_target).is_dir())
+        .unwrap_or(false);
+
+    symlink(&link_target, link, is_dir)
+        .await
+        .map_err(|e| format!("Failed to create symbolic link: {}", e))?;
+
+    journal::record_operation(OperationKind::CreateLink {
+        path: link_path,
+        target: link_target.to_string_lossy().to_string(),
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn create_hardlink(target: String, link_path: String) -> Result<(), String> {
+    let target_path = Path::new(&target);
+    let link = Path::new(&link_path);
+
+    let metadata = fs::symlink_metadata(target_path)
+        .await
+        .map_err(|_| format!("Item does not exist: {}", target))?;
+
+    if metadata.is_dir() {
+        return Err("Cannot create a hard link to a directory".to_string());
+    }
+
+    prepare_link_path(link).await?;
+
+    fs::hard_link(target_path, link)
+        .await
+        .map_err(|e| format!("Failed to create hard link: {}", e))?;
+
+    journal::record_operation(OperationKind::CreateLink {
+        path: link_path,
+        target,
+    })
+    .await
+}
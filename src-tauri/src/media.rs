@@ -0,0 +1,302 @@
+use exif::{Exif, In, Tag, Value};
+use serde::{Deserialize, Serialize};
+use std::io::BufReader;
+use std::path::Path;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::{MetadataOptions, StandardTagKey};
+use symphonia::core::probe::Hint;
+use tokio::task;
+
+use crate::file_types::{self, FileCategory};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GpsPosition {
+    latitude: f64,
+    longitude: f64,
+    altitude: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImageMetadata {
+    width: Option<u32>,
+    height: Option<u32>,
+    camera_make: Option<String>,
+    camera_model: Option<String>,
+    lens_model: Option<String>,
+    date_taken: Option<String>,
+    orientation: Option<u32>,
+    exposure_time: Option<String>,
+    f_number: Option<f64>,
+    iso: Option<u32>,
+    focal_length: Option<f64>,
+    gps: Option<GpsPosition>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetadataTag {
+    key: String,
+    value: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AudioMetadata {
+    duration: Option<f64>,
+    sample_rate: Option<u32>,
+    channels: Option<u32>,
+    bits_per_sample: Option<u32>,
+    title: Option<String>,
+    artist: Option<String>,
+    album: Option<String>,
+    album_artist: Option<String>,
+    track_number: Option<String>,
+    genre: Option<String>,
+    date: Option<String>,
+    tags: Vec<MetadataTag>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MediaMetadata {
+    path: String,
+    mime_type: Option<String>,
+    image: Option<ImageMetadata>,
+    audio: Option<AudioMetadata>,
+}
+
+fn exif_string(exif: &Exif, tag: Tag) -> Option<String> {
+    match &exif.get_field(tag, In::PRIMARY)?.value {
+        Value::Ascii(values) => values
+            .first()
+            .map(|bytes| {
+                String::from_utf8_lossy(bytes)
+                    .trim_matches(['\0', ' '])
+                    .to_string()
+            })
+            .filter(|text| !text.is_empty()),
+        _ => None,
+    }
+}
+
+fn exif_rational(exif: &Exif, tag: Tag) -> Option<f64> {
+    match &exif.get_field(tag, In::PRIMARY)?.value {
+        Value::Rational(values) => values.first().map(|value| value.to_f64()),
+        _ => None,
+    }
+}
+
+fn exif_uint(exif: &Exif, tag: Tag) -> Option<u32> {
+    exif.get_field(tag, In::PRIMARY)?.value.get_uint(0)
+}
+
+fn exif_date(exif: &Exif) -> Option<String> {
+    let text =
+        exif_string(exif, Tag::DateTimeOriginal).or_else(|| exif_string(exif, Tag::DateTime))?;
+
+    Some(
+        chrono::NaiveDateTime::parse_from_str(&text, "%Y:%m:%d %H:%M:%S")
+            .map(|date| date.format("%Y-%m-%dT%H:%M:%S").to_string())
+            .unwrap_or(text),
+    )
+}
+
+fn exif_coordinate(exif: &Exif, tag: Tag, reference: Tag, negative: &str) -> Option<f64> {
+    let Value::Rational(parts) = &exif.get_field(tag, In::PRIMARY)?.value else {
+        return None;
+    };
+
+    let degrees = parts
+        .iter()
+        .zip([1.0, 60.0, 3600.0])
+        .map(|(part, divisor)| part.to_f64() / divisor)
+        .sum::<f64>();
+
+    if !degrees.is_finite() {
+        return None;
+    }
+
+    match exif_string(exif, reference) {
+        Some(direction) if direction.eq_ignore_ascii_case(negative) => Some(-degrees),
+        _ => Some(degrees),
+    }
+}
+
+fn exif_gps(exif: &Exif) -> Option<GpsPosition> {
+    let latitude = exif_coordinate(exif, Tag::GPSLatitude, Tag::GPSLatitudeRef, "S")?;
+    let longitude = exif_coordinate(exif, Tag::GPSLongitude, Tag::GPSLongitudeRef, "W")?;
+
+    let altitude = exif_rational(exif, Tag::GPSAltitude).map(|altitude| {
+        if exif_uint(exif, Tag::GPSAltitudeRef) == Some(1) {
+            -altitude
+        } else {
+            altitude
+        }
+    });
+
+    Some(GpsPosition {
+        latitude,
+        longitude,
+        altitude,
+    })
+}
+
+fn read_image_metadata(path: &Path) -> ImageMetadata {
+    let mut metadata = ImageMetadata::default();
+
+    if let Ok((width, height)) = image::ImageReader::open(path)
+        .and_then(|reader| reader.with_guessed_format())
+        .map_err(image::ImageError::IoError)
+        .and_then(|reader| reader.into_dimensions())
+    {
+        metadata.width = Some(width);
+        metadata.height = Some(height);
+    }
+
+    let Ok(file) = std::fs::File::open(path) else {
+        return metadata;
+    };
+
+    let Ok(exif) = exif::Reader::new().read_from_container(&mut BufReader::new(file)) else {
+        return metadata;
+    };
+
+    metadata.width = metadata
+        .width
+        .or_else(|| exif_uint(&exif, Tag::PixelXDimension));
+    metadata.height = metadata
+        .height
+        .or_else(|| exif_uint(&exif, Tag::PixelYDimension));
+    metadata.camera_make = exif_string(&exif, Tag::Make);
+    metadata.camera_model = exif_string(&exif, Tag::Model);
+    metadata.lens_model = exif_string(&exif, Tag::LensModel);
+    metadata.date_taken = exif_date(&exif);
+    metadata.orientation = exif_uint(&exif, Tag::Orientation);
+    metadata.exposure_time = exif
+        .get_field(Tag::ExposureTime, In::PRIMARY)
+        .map(|field| field.display_value().with_unit(&exif).to_string());
+    metadata.f_number = exif_rational(&exif, Tag::FNumber);
+    metadata.iso = exif_uint(&exif, Tag::PhotographicSensitivity);
+    metadata.focal_length = exif_rational(&exif, Tag::FocalLength);
+    metadata.gps = exif_gps(&exif);
+
+    metadata
+}
+
+fn apply_tag(
+    metadata: &mut AudioMetadata,
+    std_key: Option<StandardTagKey>,
+    key: &str,
+    value: String,
+) {
+    let field = match std_key {
+        Some(StandardTagKey::TrackTitle) => Some(&mut metadata.title),
+        Some(StandardTagKey::Artist) => Some(&mut metadata.artist),
+        Some(StandardTagKey::Album) => Some(&mut metadata.album),
+        Some(StandardTagKey::AlbumArtist) => Some(&mut metadata.album_artist),
+        Some(StandardTagKey::TrackNumber) => Some(&mut metadata.track_number),
+        Some(StandardTagKey::Genre) => Some(&mut metadata.genre),
+        Some(StandardTagKey::Date) | Some(StandardTagKey::ReleaseDate) => Some(&mut metadata.date),
+        _ => None,
+    };
+
+    if let Some(field) = field {
+        field.get_or_insert_with(|| value.clone());
+    }
+
+    metadata.tags.push(MetadataTag {
+        key: key.to_string(),
+        value,
+    });
+}
+
+fn read_audio_metadata(path: &Path) -> Result<AudioMetadata, String> {
+    let file = std::fs::File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|extension| extension.to_str()) {
+        hint.with_extension(extension);
+    }
+
+    let mut probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            stream,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|e| format!("Failed to read audio file: {}", e))?;
+
+    let mut metadata = AudioMetadata::default();
+
+    if let Some(params) = probed
+        .format
+        .default_track()
+        .map(|track| track.codec_params.clone())
+    {
+        metadata.sample_rate = params.sample_rate;
+        metadata.channels = params.channels.map(|channels| channels.count() as u32);
+        metadata.bits_per_sample = params.bits_per_sample;
+        metadata.duration = match (params.n_frames, params.time_base, params.sample_rate) {
+            (Some(frames), Some(time_base), _) => {
+                let time = time_base.calc_time(frames);
+                Some(time.seconds as f64 + time.frac)
+            }
+            (Some(frames), None, Some(rate)) if rate > 0 => Some(frames as f64 / rate as f64),
+            _ => None,
+        };
+    }
+
+    let mut tags = Vec::new();
+
+    if let Some(revision) = probed.metadata.get().as_ref().and_then(|log| log.current()) {
+        tags.extend(revision.tags().iter().cloned());
+    }
+
+    if let Some(revision) = probed.format.metadata().current() {
+        tags.extend(revision.tags().iter().cloned());
+    }
+
+    for tag in tags {
+        let value = tag.value.to_string();
+        let value = value.trim_matches(|c: char| c == '\0' || c.is_whitespace());
+
+        if !value.is_empty() {
+            apply_tag(&mut metadata, tag.std_key, &tag.key, value.to_string());
+        }
+    }
+
+    Ok(metadata)
+}
+
+#[tauri::command]
+pub async fn get_media_metadata(path: String) -> Result<MediaMetadata, String> {
+    let file_path = Path::new(&path).to_path_buf();
+
+    let metadata = tokio::fs::metadata(&file_path)
+        .await
+        .map_err(|_| format!("File does not exist: {}", path))?;
+
+    if !metadata.is_file() {
+        return Err("Path is not a file".to_string());
+    }
+
+    let mime_type = file_types::effective_mime_type(&file_path).await;
+    let category = file_types::category_for(mime_type.as_deref(), &file_path, None);
+
+    let (image, audio) = task::spawn_blocking(move || {
+        Ok::<_, String>(match category {
+            FileCategory::Image => (Some(read_image_metadata(&file_path)), None),
+            FileCategory::Audio => (None, Some(read_audio_metadata(&file_path)?)),
+            _ => (None, None),
+        })
+    })
+    .await
+    .map_err(|e| format!("Failed to read metadata: {}", e))??;
+
+    Ok(MediaMetadata {
+        path,
+        mime_type,
+        image,
+        audio,
+    })
+}
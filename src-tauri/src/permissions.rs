@@ -0,0 +1,596 @@
+#![cfg_attr(not(unix), allow(dead_code))]
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+#[cfg(unix)]
+use tokio::task;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathFailure {
+    path: String,
+    error: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Ownership {
+    pub mode: Option<u32>,
+    pub owner: Option<String>,
+    pub group: Option<String>,
+}
+
+#[cfg(unix)]
+mod unix {
+    use std::collections::HashMap;
+    use std::ffi::{CStr, CString};
+    use std::sync::{Mutex, OnceLock};
+
+    fn lookup_buffer_size() -> usize {
+        let size = unsafe { libc::sysconf(libc::_SC_GETPW_R_SIZE_MAX) };
+        if size > 0 {
+            size as usize
+        } else {
+            16384
+        }
+    }
+
+    pub fn user_name(uid: u32) -> Option<String> {
+        static NAMES: OnceLock<Mutex<HashMap<u32, Option<String>>>> = OnceLock::new();
+
+        let names = NAMES.get_or_init(|| Mutex::new(HashMap::new()));
+        if let Some(name) = names.lock().unwrap().get(&uid) {
+            return name.clone();
+        }
+
+        let mut buffer = vec![0; lookup_buffer_size()];
+        let mut passwd: libc::passwd = unsafe { std::mem::zeroed() };
+        let mut result = std::ptr::null_mut();
+
+        let status = unsafe {
+            libc::getpwuid_r(
+                uid,
+                &mut passwd,
+                buffer.as_mut_ptr(),
+                buffer.len(),
+                &mut result,
+            )
+        };
+
+        let name = (status == 0 && !result.is_null()).then(|| {
+            unsafe { CStr::from_ptr(passwd.pw_name) }
+                .to_string_lossy()
+                .to_string()
+        });
+
+        names.lock().unwrap().insert(uid, name.clone());
+        name
+    }
+
+    pub fn group_name(gid: u32) -> Option<String> {
+        static NAMES: OnceLock<Mutex<HashMap<u32, Option<String>>>> = OnceLock::new();
+
+        let names = NAMES.get_or_init(|| Mutex::new(HashMap::new()));
+        if let Some(name) = names.lock().unwrap().get(&gid) {
+            return name.clone();
+        }
+
+        let mut buffer = vec![0; lookup_buffer_size()];
+        let mut group: libc::group = unsafe { std::mem::zeroed() };
+        let mut result = std::ptr::null_mut();
+
+        let status = unsafe {
+            libc::getgrgid_r(
+                gid,
+                &mut group,
+                buffer.as_mut_ptr(),
+                buffer.len(),
+                &mut result,
+            )
+        };
+
+        let name = (status == 0 && !result.is_null()).then(|| {
+            unsafe { CStr::from_ptr(group.gr_name) }
+                .to_string_lossy()
+                .to_string()
+        });
+
+        names.lock().unwrap().insert(gid, name.clone());
+        name
+    }
+
+    pub fn resolve_user(user: &str) -> Result<u32, String> {
+        if let Ok(uid) = user.parse::<u32>() {
+            return Ok(uid);
+        }
+
+        let name = CString::new(user).map_err(|_| format!("Invalid user name: {}", user))?;
+        let mut buffer = vec![0; lookup_buffer_size()];
+        let mut passwd: libc::passwd = unsafe { std::mem::zeroed() };
+        let mut result = std::ptr::null_mut();
+
+        let status = unsafe {
+            libc::getpwnam_r(
+                name.as_ptr(),
+                &mut passwd,
+                buffer.as_mut_ptr(),
+                buffer.len(),
+                &mut result,
+            )
+        };
+
+        if status != 0 || result.is_null() {
+            return Err(format!("Unknown user: {}", user));
+        }
+
+        Ok(passwd.pw_uid)
+    }
+
+    pub fn resolve_group(group: &str) -> Result<u32, String> {
+        if let Ok(gid) = group.parse::<u32>() {
+            return Ok(gid);
+        }
+
+        let name = CString::new(group).map_err(|_| format!("Invalid group name: {}", group))?;
+        let mut buffer = vec![0; lookup_buffer_size()];
+        let mut entry: libc::group = unsafe { std::mem::zeroed() };
+        let mut result = std::ptr::null_mut();
+
+        let status = unsafe {
+            libc::getgrnam_r(
+                name.as_ptr(),
+                &mut entry,
+                buffer.as_mut_ptr(),
+                buffer.len(),
+                &mut result,
+            )
+        };
+
+        if status != 0 || result.is_null() {
+            return Err(format!("Unknown group: {}", group));
+        }
+
+        Ok(entry.gr_gid)
+    }
+}
+
+#[cfg(unix)]
+pub(crate) fn ownership(metadata: &std::fs::Metadata) -> Ownership {
+    use std::os::unix::fs::MetadataExt;
+
+    Ownership {
+        mode: Some(metadata.mode() & 0o7777),
+        owner: unix::user_name(metadata.uid()),
+        group: unix::group_name(metadata.gid()),
+    }
+}
+
+#[cfg(not(unix))]
+pub(crate) fn ownership(_metadata: &std::fs::Metadata) -> Ownership {
+    Ownership::default()
+}
+
+enum ModeSpec {
+    Absolute(u32),
+    Symbolic(Vec<SymbolicClause>),
+}
+
+struct SymbolicClause {
+    who: u32,
+    /// Whether `who` was left out, in which case the umask limits the bits
+    /// the clause sets or clears, as with `chmod +x`.
+    masked: bool,
+    actions: Vec<(char, SymbolicPermissions)>,
+}
+
+enum SymbolicPermissions {
+    Bits {
+        bits: u32,
+        conditional_execute: bool,
+    },
+    Copy(char),
+}
+
+const WHO_USER: u32 = 0o4700;
+const WHO_GROUP: u32 = 0o2070;
+const WHO_OTHER: u32 = 0o1007;
+
+fn parse_mode(mode: &str) -> Result<ModeSpec, String> {
+    let mode = mode.trim();
+
+    if !mode.is_empty() && mode.len() <= 4 && mode.chars().all(|c| c.is_digit(8)) {
+        return u32::from_str_radix(mode, 8)
+            .map(ModeSpec::Absolute)
+            .map_err(|_| format!("Invalid mode: {}", mode));
+    }
+
+    let mut clauses = Vec::new();
+
+    for clause in mode.split(',') {
+        let mut chars = clause.chars().peekable();
+        let mut who = 0;
+
+        while let Some(&c) = chars.peek() {
+            match c {
+                'u' => who |= WHO_USER,
+                'g' => who |= WHO_GROUP,
+                'o' => who |= WHO_OTHER,
+                'a' => who |= WHO_USER | WHO_GROUP | WHO_OTHER,
+                _ => break,
+            }
+            chars.next();
+        }
+
+        let masked = who == 0;
+        if masked {
+            who = WHO_USER | WHO_GROUP | WHO_OTHER;
+        }
+
+        let mut actions = Vec::new();
+
+        while let Some(op) = chars.next() {
+            if !matches!(op, '+' | '-' | '=') {
+                return Err(format!("Invalid mode clause: {}", clause));
+            }
+
+            if let Some(&source) = chars.peek() {
+                if matches!(source, 'u' | 'g' | 'o') {
+                    chars.next();
+                    actions.push((op, SymbolicPermissions::Copy(source)));
+                    continue;
+                }
+            }
+
+            let mut bits = 0;
+            let mut conditional_execute = false;
+
+            while let Some(&c) = chars.peek() {
+                match c {
+                    'r' => bits |= 0o444,
+                    'w' => bits |= 0o222,
+                    'x' => bits |= 0o111,
+                    'X' => conditional_execute = true,
+                    's' => bits |= 0o6000,
+                    't' => bits |= 0o1000,
+                    _ => break,
+                }
+                chars.next();
+            }
+
+            actions.push((
+                op,
+                SymbolicPermissions::Bits {
+                    bits,
+                    conditional_execute,
+                },
+            ));
+        }
+
+        if actions.is_empty() {
+            return Err(format!("Invalid mode clause: {}", clause));
+        }
+
+        clauses.push(SymbolicClause {
+            who,
+            masked,
+            actions,
+        });
+    }
+
+    Ok(ModeSpec::Symbolic(clauses))
+}
+
+fn copy_permissions(current: u32, source: char) -> u32 {
+    let bits = match source {
+        'u' => (current >> 6) & 0o7,
+        'g' => (current >> 3) & 0o7,
+        _ => current & 0o7,
+    };
+
+    bits * 0o111
+}
+
+fn apply_mode(spec: &ModeSpec, current: u32, is_dir: bool, umask: u32) -> u32 {
+    let clauses = match spec {
+        ModeSpec::Absolute(mode) => return *mode,
+        ModeSpec::Symbolic(clauses) => clauses,
+    };
+
+    let mut mode = current & 0o7777;
+
+    for clause in clauses {
+        let allowed = if clause.masked { !umask } else { !0 };
+
+        for (op, permissions) in &clause.actions {
+            let bits = match permissions {
+                SymbolicPermissions::Bits {
+                    bits,
+                    conditional_execute,
+                } => {
+                    let execute = if *conditional_execute && (is_dir || mode & 0o111 != 0) {
+                        0o111
+                    } else {
+                        0
+                    };
+                    (bits | execute) & clause.who
+                }
+                SymbolicPermissions::Copy(source) => copy_permissions(mode, *source) & clause.who,
+            } & allowed;
+
+            match op {
+                '+' => mode |= bits,
+                '-' => mode &= !bits,
+                _ => mode = (mode & !clause.who) | bits,
+            }
+        }
+    }
+
+    mode
+}
+
+fn failure(path: &Path, error: &std::io::Error) -> PathFailure {
+    PathFailure {
+        path: path.to_string_lossy().to_string(),
+        error: error.to_string(),
+    }
+}
+
+/// Visits `path` and, when `recursive`, everything below it, reporting each
+/// item that could not be read or changed. A directory is visited after its
+/// contents when `contents_first` holds for it, so a change that takes
+/// access away does not lock the walk out of the directory.
+fn walk(
+    path: &Path,
+    recursive: bool,
+    contents_first: &dyn Fn(&std::fs::Metadata) -> bool,
+    visit: &mut dyn FnMut(&Path, &std::fs::Metadata) -> std::io::Result<()>,
+    failures: &mut Vec<PathFailure>,
+) {
+    let metadata = match std::fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) => {
+            failures.push(failure(path, &e));
+            return;
+        }
+    };
+
+    let descend = recursive && metadata.is_dir();
+    let after = descend && contents_first(&metadata);
+
+    if !after {
+        if let Err(e) = visit(path, &metadata) {
+            failures.push(failure(path, &e));
+        }
+    }
+
+    if descend {
+        match std::fs::read_dir(path) {
+            Ok(entries) => {
+                for entry in entries {
+                    match entry {
+                        Ok(entry) => {
+                            walk(&entry.path(), recursive, contents_first, visit, failures)
+                        }
+                        Err(e) => failures.push(failure(path, &e)),
+                    }
+                }
+            }
+            Err(e) => failures.push(failure(path, &e)),
+        }
+    }
+
+    if after {
+        if let Err(e) = visit(path, &metadata) {
+            failures.push(failure(path, &e));
+        }
+    }
+}
+
+fn check_paths(paths: &[String], failures: &mut Vec<PathFailure>) -> Vec<String> {
+    paths
+        .iter()
+        .filter(|path| {
+            let exists = Path::new(path).symlink_metadata().is_ok();
+            if !exists {
+                failures.push(PathFailure {
+                    path: path.to_string(),
+                    error: "Item does not exist".to_string(),
+                });
+            }
+            exists
+        })
+        .cloned()
+        .collect()
+}
+
+/// Used when the kernel does not report the umask.
+#[cfg(unix)]
+const DEFAULT_UMASK: u32 = 0o022;
+
+/// The process umask as reported in `/proc/self/status`. `umask(2)` can
+/// only read it by changing it for every thread, so it is not used.
+#[cfg(unix)]
+fn current_umask() -> u32 {
+    std::fs::read_to_string("/proc/self/status")
+        .ok()
+        .and_then(|status| parse_umask(&status))
+        .unwrap_or(DEFAULT_UMASK)
+}
+
+#[cfg(unix)]
+fn parse_umask(status: &str) -> Option<u32> {
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("Umask:"))
+        .and_then(|value| u32::from_str_radix(value.trim(), 8).ok())
+        .filter(|umask| umask & !0o777 == 0)
+}
+
+#[cfg(unix)]
+#[tauri::command]
+pub async fn set_permissions(
+    paths: Vec<String>,
+    mode: String,
+    recursive: bool,
+) -> Result<Vec<PathFailure>, String> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let spec = parse_mode(&mode)?;
+    let umask = current_umask();
+
+    task::spawn_blocking(move || {
+        let mut failures = Vec::new();
+        let new_mode = |metadata: &std::fs::Metadata| {
+            apply_mode(
+                &spec,
+                metadata.permissions().mode(),
+                metadata.is_dir(),
+                umask,
+            )
+        };
+
+        for path in check_paths(&paths, &mut failures) {
+            walk(
+                Path::new(&path),
+                recursive,
+                &|metadata| metadata.permissions().mode() & !new_mode(metadata) & 0o7777 != 0,
+                &mut |item, metadata| {
+                    if metadata.is_symlink() {
+                        return Ok(());
+                    }
+
+                    std::fs::set_permissions(
+                        item,
+                        std::fs::Permissions::from_mode(new_mode(metadata)),
+                    )
+                },
+                &mut failures,
+            );
+        }
+
+        failures
+    })
+    .await
+    .map_err(|e| format!("Failed to change permissions: {}", e))
+}
+
+#[cfg(not(unix))]
+#[tauri::command]
+pub async fn set_permissions(
+    _paths: Vec<String>,
+    _mode: String,
+    _recursive: bool,
+) -> Result<Vec<PathFailure>, String> {
+    Err("Changing permissions is not supported on this platform".to_string())
+}
+
+#[cfg(unix)]
+#[tauri::command]
+pub async fn set_owner(
+    paths: Vec<String>,
+    user: Option<String>,
+    group: Option<String>,
+    recursive: bool,
+) -> Result<Vec<PathFailure>, String> {
+    let uid = user.as_deref().map(unix::resolve_user).transpose()?;
+    let gid = group.as_deref().map(unix::resolve_group).transpose()?;
+
+    if uid.is_none() && gid.is_none() {
+        return Err("No user or group specified".to_string());
+    }
+
+    task::spawn_blocking(move || {
+        let mut failures = Vec::new();
+
+        for path in check_paths(&paths, &mut failures) {
+            walk(
+                Path::new(&path),
+                recursive,
+                &|_| false,
+                &mut |item, _| std::os::unix::fs::lchown(item, uid, gid),
+                &mut failures,
+            );
+        }
+
+        failures
+    })
+    .await
+    .map_err(|e| format!("Failed to change owner: {}", e))
+}
+
+#[cfg(not(unix))]
+#[tauri::command]
+pub async fn set_owner(
+    _paths: Vec<String>,
+    _user: Option<String>,
+    _group: Option<String>,
+    _recursive: bool,
+) -> Result<Vec<PathFailure>, String> {
+    Err("Changing ownership is not supported on this platform".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chmod(mode: &str, current: u32, is_dir: bool, umask: u32) -> u32 {
+        apply_mode(&parse_mode(mode).unwrap(), current, is_dir, umask)
+    }
+
+    #[test]
+    fn the_umask_is_read_from_the_process_status() {
+        let status = "Name:\tcat\nUmask:\t0027\nState:\tR (running)\n";
+        assert_eq!(parse_umask(status), Some(0o027));
+
+        assert_eq!(parse_umask("Name:\tcat\n"), None);
+        assert_eq!(parse_umask("Umask:\t0099\n"), None);
+        assert_eq!(parse_umask("Umask:\t7777\n"), None);
+    }
+
+    #[test]
+    fn absolute_modes_replace_the_current_mode() {
+        assert_eq!(chmod("755", 0o600, false, 0o022), 0o755);
+        assert_eq!(chmod("0640", 0o777, false, 0o077), 0o640);
+        assert_eq!(chmod("4755", 0o644, false, 0o022), 0o4755);
+    }
+
+    #[test]
+    fn explicit_classes_ignore_the_umask() {
+        assert_eq!(chmod("a+w", 0o644, false, 0o022), 0o666);
+        assert_eq!(chmod("go-r", 0o644, false, 0o022), 0o600);
+        assert_eq!(chmod("u=rx", 0o644, false, 0o022), 0o544);
+    }
+
+    #[test]
+    fn missing_class_is_limited_by_the_umask() {
+        assert_eq!(chmod("+w", 0o444, false, 0o022), 0o644);
+        assert_eq!(chmod("+x", 0o640, false, 0o027), 0o750);
+        assert_eq!(chmod("-w", 0o666, false, 0o022), 0o466);
+        assert_eq!(chmod("=r", 0o755, false, 0o077), 0o400);
+        assert_eq!(chmod("+t", 0o755, true, 0o022), 0o1755);
+    }
+
+    #[test]
+    fn conditional_execute_needs_a_directory_or_an_executable() {
+        assert_eq!(chmod("a+X", 0o644, false, 0o022), 0o644);
+        assert_eq!(chmod("a+X", 0o644, true, 0o022), 0o755);
+        assert_eq!(chmod("a+X", 0o744, false, 0o022), 0o755);
+    }
+
+    #[test]
+    fn permissions_copy_between_classes() {
+        assert_eq!(chmod("g=u", 0o740, false, 0o022), 0o770);
+        assert_eq!(chmod("o+g", 0o750, false, 0o022), 0o755);
+    }
+
+    #[test]
+    fn clauses_apply_in_order() {
+        assert_eq!(chmod("u+x,g-r,o=", 0o644, false, 0o022), 0o700);
+        assert_eq!(chmod("u+s,g+s", 0o755, false, 0o022), 0o6755);
+    }
+
+    #[test]
+    fn invalid_modes_are_rejected() {
+        for mode in ["", "u*x", "ug", "12345", "u+r,"] {
+            assert!(parse_mode(mode).is_err(), "{mode} was accepted");
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlaceKind {
+    Desktop,
+    Documents,
+    Downloads,
+    Music,
+    Pictures,
+    Videos,
+    Templates,
+    Public,
+}
+
+const PLACE_KINDS: [(PlaceKind, &str); 8] = [
+    (PlaceKind::Desktop, "XDG_DESKTOP_DIR"),
+    (PlaceKind::Documents, "XDG_DOCUMENTS_DIR"),
+    (PlaceKind::Downloads, "XDG_DOWNLOAD_DIR"),
+    (PlaceKind::Music, "XDG_MUSIC_DIR"),
+    (PlaceKind::Pictures, "XDG_PICTURES_DIR"),
+    (PlaceKind::Videos, "XDG_VIDEOS_DIR"),
+    (PlaceKind::Templates, "XDG_TEMPLATES_DIR"),
+    (PlaceKind::Public, "XDG_PUBLICSHARE_DIR"),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StandardPlace {
+    kind: PlaceKind,
+    name: String,
+    path: String,
+    exists: bool,
+}
+
+fn default_dir(kind: PlaceKind) -> Option<PathBuf> {
+    match kind {
+        PlaceKind::Desktop => dirs::desktop_dir(),
+        PlaceKind::Documents => dirs::document_dir(),
+        PlaceKind::Downloads => dirs::download_dir(),
+        PlaceKind::Music => dirs::audio_dir(),
+        PlaceKind::Pictures => dirs::picture_dir(),
+        PlaceKind::Videos => dirs::video_dir(),
+        PlaceKind::Templates => dirs::template_dir(),
+        PlaceKind::Public => dirs::public_dir(),
+    }
+}
+
+/// Parses a value such as `"$HOME/Bilder"`, honouring the shell-style
+/// backslash escapes `xdg-user-dirs-update` writes.
+fn parse_value(value: &str, home: &Path) -> Option<PathBuf> {
+    let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.extend(chars.next()),
+            _ => unescaped.push(c),
+        }
+    }
+
+    if let Some(rest) = unescaped.strip_prefix("$HOME") {
+        Some(home.join(rest.trim_start_matches('/')))
+    } else if unescaped.starts_with('/') {
+        Some(PathBuf::from(unescaped))
+    } else {
+        None
+    }
+}
+
+fn read_user_dirs(home: &Path) -> HashMap<String, PathBuf> {
+    let Some(path) = dirs::config_dir().map(|dir| dir.join("user-dirs.dirs")) else {
+        return HashMap::new();
+    };
+
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return HashMap::new();
+    };
+
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            Some((key.trim().to_string(), parse_value(value, home)?))
+        })
+        .collect()
+}
+
+/// Returns the user's standard folders as configured in `user-dirs.dirs`,
+/// so localized names such as `Bilder` or `Téléchargements` are picked up.
+/// Folders pointed at `$HOME` itself are disabled by convention and omitted.
+#[tauri::command]
+pub async fn get_standard_places() -> Result<Vec<StandardPlace>, String> {
+    let home = dirs::home_dir().ok_or_else(|| "Failed to get home directory".to_string())?;
+    let configured = read_user_dirs(&home);
+
+    Ok(PLACE_KINDS
+        .iter()
+        .filter_map(|(kind, key)| {
+            let path = configured
+                .get(*key)
+                .cloned()
+                .or_else(|| default_dir(*kind))?;
+
+            if path == home {
+                return None;
+            }
+
+            Some(StandardPlace {
+                kind: *kind,
+                name: path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                exists: path.is_dir(),
+                path: path.to_string_lossy().to_string(),
+            })
+        })
+        .collect())
+}
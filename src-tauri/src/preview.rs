@@ -0,0 +1,317 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tokio::fs;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, SeekFrom};
+
+use crate::archives;
+
+const DEFAULT_PREVIEW_BYTES: u64 = 64 * 1024;
+const MAX_PREVIEW_BYTES: u64 = 4 * 1024 * 1024;
+const HEX_BYTES_PER_ROW: usize = 16;
+const MAX_HEX_BYTES: u64 = 256 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextEncoding {
+    Utf8,
+    Utf8Bom,
+    Utf16Le,
+    Utf16Be,
+    Latin1,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LineEnding {
+    None,
+    Lf,
+    Crlf,
+    Cr,
+    Mixed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilePreview {
+    path: String,
+    text: Option<String>,
+    encoding: Option<TextEncoding>,
+    is_binary: bool,
+    line_ending: LineEnding,
+    line_count: u64,
+    line_count_exact: bool,
+    bytes_read: u64,
+    total_size: u64,
+    truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HexRow {
+    offset: u64,
+    hex: String,
+    ascii: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HexPage {
+    path: String,
+    offset: u64,
+    length: u64,
+    total_size: u64,
+    bytes_per_row: usize,
+    rows: Vec<HexRow>,
+}
+
+fn detect_encoding(bytes: &[u8], complete: bool) -> (TextEncoding, usize) {
+    if bytes.starts_with(&[0xEF, 0xBB, 0xBF]) {
+        return (TextEncoding::Utf8Bom, 3);
+    }
+
+    if bytes.starts_with(&[0xFF, 0xFE]) {
+        return (TextEncoding::Utf16Le, 2);
+    }
+
+    if bytes.starts_with(&[0xFE, 0xFF]) {
+        return (TextEncoding::Utf16Be, 2);
+    }
+
+    match std::str::from_utf8(bytes) {
+        Ok(_) => (TextEncoding::Utf8, 0),
+        Err(e) if e.error_len().is_none() && !complete => (TextEncoding::Utf8, 0),
+        Err(_) => (TextEncoding::Latin1, 0),
+    }
+}
+
+fn decode(bytes: &[u8], encoding: TextEncoding) -> String {
+    match encoding {
+        TextEncoding::Utf8 | TextEncoding::Utf8Bom => match std::str::from_utf8(bytes) {
+            Ok(text) => text.to_string(),
+            Err(e) => String::from_utf8_lossy(&bytes[..e.valid_up_to()]).to_string(),
+        },
+        TextEncoding::Utf16Le | TextEncoding::Utf16Be => {
+            let units = bytes
+                .chunks_exact(2)
+                .map(|pair| match encoding {
+                    TextEncoding::Utf16Le => u16::from_le_bytes([pair[0], pair[1]]),
+                    _ => u16::from_be_bytes([pair[0], pair[1]]),
+                })
+                .collect::<Vec<_>>();
+            String::from_utf16_lossy(&units)
+        }
+        TextEncoding::Latin1 => bytes.iter().map(|&byte| byte as char).collect(),
+    }
+}
+
+fn looks_binary(bytes: &[u8], encoding: TextEncoding) -> bool {
+    if matches!(encoding, TextEncoding::Utf16Le | TextEncoding::Utf16Be) {
+        return false;
+    }
+
+    if bytes.contains(&0) {
+        return true;
+    }
+
+    let control = bytes
+        .iter()
+        .filter(|&&byte| byte < 0x20 && !matches!(byte, b'\t' | b'\n' | b'\r' | 0x0C | 0x1B))
+        .count();
+
+    !bytes.is_empty() && control * 10 > bytes.len()
+}
+
+fn detect_line_ending(text: &str) -> (LineEnding, u64) {
+    let (mut lf, mut crlf, mut cr) = (0u64, 0u64, 0u64);
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\r' if chars.peek() == Some(&'\n') => {
+                chars.next();
+                crlf += 1;
+            }
+            '\r' => cr += 1,
+            '\n' => lf += 1,
+            _ => {}
+        }
+    }
+
+    let ending = match (lf > 0, crlf > 0, cr > 0) {
+        (false, false, false) => LineEnding::None,
+        (true, false, false) => LineEnding::Lf,
+        (false, true, false) => LineEnding::Crlf,
+        (false, false, true) => LineEnding::Cr,
+        _ => LineEnding::Mixed,
+    };
+
+    (ending, lf + crlf + cr)
+}
+
+/// Reads up to `limit` bytes starting at `offset` from a file or archive
+/// member, returning them with the total size.
+async fn read_bytes(path: &str, offset: u64, limit: u64) -> Result<(Vec<u8>, u64), String> {
+    if let Some((archive, inner)) = archives::split_archive_path(path) {
+        return archives::read_member(archive, inner, offset, limit).await;
+    }
+
+    let file_path = Path::new(path);
+
+    let metadata = fs::metadata(file_path)
+        .await
+        .map_err(|_| format!("File does not exist: {}", path))?;
+
+    if metadata.is_dir() {
+        return Err("Path is a directory".to_string());
+    }
+
+    // Opening a FIFO blocks and devices such as /dev/zero never end.
+    if !metadata.is_file() {
+        return Err("Not a regular file".to_string());
+    }
+
+    let mut file = fs::File::open(file_path)
+        .await
+        .map_err(|e| format!("Failed to open file: {}", e))?;
+
+    if offset > 0 {
+        file.seek(SeekFrom::Start(offset))
+            .await
+            .map_err(|e| format!("Failed to seek in file: {}", e))?;
+    }
+
+    let mut bytes = Vec::new();
+    file.take(limit)
+        .read_to_end(&mut bytes)
+        .await
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+
+    Ok((bytes, metadata.len()))
+}
+
+#[tauri::command]
+pub async fn read_file_preview(
+    path: String,
+    max_bytes: Option<u64>,
+) -> Result<FilePreview, String> {
+    let limit = max_bytes
+        .unwrap_or(DEFAULT_PREVIEW_BYTES)
+        .clamp(1, MAX_PREVIEW_BYTES);
+
+    let (bytes, total_size) = read_bytes(&path, 0, limit).await?;
+    let complete = (bytes.len() as u64) >= total_size;
+
+    let (encoding, bom_length) = detect_encoding(&bytes, complete);
+    let content = &bytes[bom_length..];
+    let is_binary = looks_binary(content, encoding);
+
+    let (text, line_ending, line_breaks) = if is_binary {
+        (None, LineEnding::None, 0)
+    } else {
+        let text = decode(content, encoding);
+        let (line_ending, line_breaks) = detect_line_ending(&text);
+        (Some(text), line_ending, line_breaks)
+    };
+
+    let line_count = if complete {
+        let trailing = text
+            .as_deref()
+            .map(|text| !text.is_empty() && !text.ends_with(['\n', '\r']))
+            .unwrap_or(false);
+        line_breaks + trailing as u64
+    } else if bytes.is_empty() {
+        0
+    } else {
+        (line_breaks as f64 * total_size as f64 / bytes.len() as f64).round() as u64
+    };
+
+    Ok(FilePreview {
+        path,
+        text,
+        encoding: (!is_binary).then_some(encoding),
+        is_binary,
+        line_ending,
+        line_count,
+        line_count_exact: complete && !is_binary,
+        bytes_read: bytes.len() as u64,
+        total_size,
+        truncated: !complete,
+    })
+}
+
+fn format_hex_row(offset: u64, bytes: &[u8]) -> HexRow {
+    let hex = bytes
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let ascii = bytes
+        .iter()
+        .map(|&byte| {
+            if byte.is_ascii_graphic() || byte == b' ' {
+                byte as char
+            } else {
+                '.'
+            }
+        })
+        .collect();
+
+    HexRow { offset, hex, ascii }
+}
+
+#[tauri::command]
+pub async fn read_file_hex(path: String, offset: u64, length: u64) -> Result<HexPage, String> {
+    let (bytes, total_size) = read_bytes(&path, offset, length.min(MAX_HEX_BYTES)).await?;
+    let offset = offset.min(total_size);
+
+    let rows = bytes
+        .chunks(HEX_BYTES_PER_ROW)
+        .enumerate()
+        .map(|(index, chunk)| format_hex_row(offset + (index * HEX_BYTES_PER_ROW) as u64, chunk))
+        .collect();
+
+    Ok(HexPage {
+        path,
+        offset,
+        length: bytes.len() as u64,
+        total_size,
+        bytes_per_row: HEX_BYTES_PER_ROW,
+        rows,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn only_regular_files_are_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.txt");
+        std::fs::write(&file, "hello").unwrap();
+
+        let (bytes, total) = read_bytes(&file.to_string_lossy(), 1, 3).await.unwrap();
+        assert_eq!((bytes.as_slice(), total), (&b"ell"[..], 5));
+
+        assert!(read_bytes(&dir.path().to_string_lossy(), 0, 3)
+            .await
+            .is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn fifos_and_devices_are_refused() {
+        use std::ffi::CString;
+        use std::os::unix::ffi::OsStrExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let fifo = dir.path().join("pipe");
+        let fifo_c = CString::new(fifo.as_os_str().as_bytes()).unwrap();
+        assert_eq!(unsafe { libc::mkfifo(fifo_c.as_ptr(), 0o600) }, 0);
+
+        for path in [fifo.to_string_lossy().to_string(), "/dev/zero".to_string()] {
+            assert_eq!(
+                read_bytes(&path, 0, 16).await.unwrap_err(),
+                "Not a regular file"
+            );
+        }
+    }
+}
@@ -0,0 +1,347 @@
+use chrono::{DateTime, Utc};
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tokio::fs;
+use tokio::sync::Mutex;
+
+use crate::bookmarks::file_uri_to_path;
+use crate::{app_data_dir, build_file_entry, FileEntry};
+
+const MAX_RECENT_ENTRIES: usize = 100;
+const DEFAULT_RECENT_LIMIT: usize = 50;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentFile {
+    #[serde(flatten)]
+    entry: FileEntry,
+    last_used: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecentRecord {
+    path: String,
+    last_used: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct RecentHistory {
+    records: Vec<RecentRecord>,
+    /// Entries of the shared history last used before this are hidden, so
+    /// clearing does not have to rewrite a file other applications own.
+    cleared_at: Option<DateTime<Utc>>,
+}
+
+fn recent_path() -> Result<PathBuf, String> {
+    Ok(app_data_dir()?.join("recent.json"))
+}
+
+fn xbel_path() -> Result<PathBuf, String> {
+    dirs::data_dir()
+        .map(|dir| dir.join("recently-used.xbel"))
+        .ok_or_else(|| "Failed to get data directory".to_string())
+}
+
+fn get_recent() -> &'static Mutex<RecentHistory> {
+    static RECENT: OnceLock<Mutex<RecentHistory>> = OnceLock::new();
+
+    RECENT.get_or_init(|| {
+        let history = recent_path()
+            .ok()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|contents| {
+                serde_json::from_str::<RecentHistory>(&contents)
+                    .ok()
+                    // Earlier versions stored the records alone.
+                    .or_else(|| {
+                        serde_json::from_str::<Vec<RecentRecord>>(&contents)
+                            .ok()
+                            .map(|records| RecentHistory {
+                                records,
+                                cleared_at: None,
+                            })
+                    })
+            })
+            .unwrap_or_default();
+
+        Mutex::new(history)
+    })
+}
+
+async fn save_recent(history: &RecentHistory) -> Result<(), String> {
+    let path = recent_path()?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create data directory: {}", e))?;
+    }
+
+    let contents = serde_json::to_string_pretty(history)
+        .map_err(|e| format!("Failed to serialize recent files: {}", e))?;
+
+    fs::write(&path, contents)
+        .await
+        .map_err(|e| format!("Failed to write recent files: {}", e))
+}
+
+/// Decodes the predefined XML entities and character references in a
+/// single pass, so `&amp;lt;` stays `&lt;`. Unknown entities are kept.
+fn unescape_xml(value: &str) -> String {
+    static ENTITY: OnceLock<Regex> = OnceLock::new();
+
+    let entity = ENTITY.get_or_init(|| Regex::new(r"&(#x[0-9a-fA-F]+|#[0-9]+|[a-z]+);").unwrap());
+
+    entity
+        .replace_all(value, |captures: &Captures| {
+            let name = &captures[1];
+            let decoded = match name {
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "amp" => Some('&'),
+                _ => match name.strip_prefix("#x") {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => name
+                        .strip_prefix('#')
+                        .and_then(|digits| digits.parse().ok()),
+                }
+                .and_then(char::from_u32),
+            };
+
+            decoded
+                .map(String::from)
+                .unwrap_or_else(|| captures[0].to_string())
+        })
+        .into_owned()
+}
+
+/// Pulls `href` and the newest of `added`/`modified`/`visited` out of each
+/// `<bookmark>` element. Only the opening tags matter, so a pair of regexes
+/// is enough here.
+fn parse_xbel(contents: &str) -> Vec<(PathBuf, DateTime<Utc>)> {
+    static BOOKMARK: OnceLock<Regex> = OnceLock::new();
+    static ATTRIBUTE: OnceLock<Regex> = OnceLock::new();
+
+    let bookmark = BOOKMARK.get_or_init(|| Regex::new(r"<bookmark\s([^>]*)>").unwrap());
+    let attribute =
+        ATTRIBUTE.get_or_init(|| Regex::new(r#"([\w:]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap());
+
+    bookmark
+        .captures_iter(contents)
+        .filter_map(|captures| {
+            let attributes = attribute
+                .captures_iter(&captures[1])
+                .map(|attribute| {
+                    let value = attribute
+                        .get(2)
+                        .or(attribute.get(3))
+                        .map_or("", |value| value.as_str());
+                    (attribute[1].to_string(), unescape_xml(value))
+                })
+                .collect::<HashMap<_, _>>();
+
+            let path = file_uri_to_path(attributes.get("href")?)?;
+            let last_used = ["modified", "visited", "added"]
+                .iter()
+                .filter_map(|key| attributes.get(*key))
+                .filter_map(|value| DateTime::parse_from_rfc3339(value).ok())
+                .map(|time| time.with_timezone(&Utc))
+                .max()?;
+
+            Some((path, last_used))
+        })
+        .collect()
+}
+
+/// Lists recently used files from the shared freedesktop history merged
+/// with files opened through the app, most recent first.
+#[tauri::command]
+pub async fn list_recent_files(limit: Option<usize>) -> Result<Vec<RecentFile>, String> {
+    let mut merged: HashMap<PathBuf, DateTime<Utc>> = HashMap::new();
+
+    let history = get_recent().lock().await.clone();
+
+    if let Ok(contents) = fs::read_to_string(xbel_path()?).await {
+        merged.extend(
+            parse_xbel(&contents)
+                .into_iter()
+                .filter(|(_, last_used)| history.cleared_at < Some(*last_used)),
+        );
+    }
+
+    for record in &history.records {
+        let last_used = merged
+            .entry(PathBuf::from(&record.path))
+            .or_insert(record.last_used);
+        *last_used = (*last_used).max(record.last_used);
+    }
+
+    let mut recent = merged.into_iter().collect::<Vec<_>>();
+    recent.sort_by_key(|(_, last_used)| std::cmp::Reverse(*last_used));
+
+    let limit = limit.unwrap_or(DEFAULT_RECENT_LIMIT);
+    let mut files = Vec::new();
+
+    for (path, last_used) in recent {
+        if files.len() >= limit {
+            break;
+        }
+
+        let Ok(metadata) = fs::metadata(&path).await else {
+            continue;
+        };
+
+        if metadata.is_dir() {
+            continue;
+        }
+
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        files.push(RecentFile {
+            entry: build_file_entry(name, &path, Some(&metadata), None),
+            last_used,
+        });
+    }
+
+    Ok(files)
+}
+
+pub(crate) async fn record_recent(path: &Path) -> Result<(), String> {
+    let mut history = get_recent().lock().await;
+    let path = path.to_string_lossy().to_string();
+
+    history.records.retain(|record| record.path != path);
+    history.records.insert(
+        0,
+        RecentRecord {
+            path,
+            last_used: Utc::now(),
+        },
+    );
+    history.records.truncate(MAX_RECENT_ENTRIES);
+
+    save_recent(&history).await
+}
+
+/// Opens `path` with the default application and remembers it as recently
+/// used.
+#[tauri::command]
+pub async fn open_file(path: String) -> Result<(), String> {
+    let file_path = Path::new(&path);
+
+    if !file_path.exists() {
+        return Err(format!("File does not exist: {}", path));
+    }
+
+    tauri_plugin_opener::open_path(file_path, None::<&str>)
+        .map_err(|e| format!("Failed to open file: {}", e))?;
+
+    record_recent(file_path).await
+}
+
+/// Forgets the app's own history and hides what the shared freedesktop
+/// history holds so far. The shared file is left to the applications that
+/// wrote it.
+#[tauri::command]
+pub async fn clear_recent() -> Result<(), String> {
+    let mut history = get_recent().lock().await;
+
+    history.records.clear();
+    history.cleared_at = Some(Utc::now());
+
+    save_recent(&history).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn bookmarks_use_their_newest_timestamp() {
+        let contents = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xbel version="1.0">
+  <bookmark href="file:///home/user/notes.txt" added="2024-01-01T10:00:00Z" modified="2024-03-01T10:00:00Z" visited="2024-02-01T10:00:00Z">
+    <info><metadata owner="http://freedesktop.org"/></info>
+  </bookmark>
+  <bookmark href="file:///home/user/My%20Report.pdf" added="2024-05-02T08:30:00.123456Z"/>
+</xbel>"#;
+
+        assert_eq!(
+            parse_xbel(contents),
+            vec![
+                (
+                    PathBuf::from("/home/user/notes.txt"),
+                    time("2024-03-01T10:00:00Z")
+                ),
+                (
+                    PathBuf::from("/home/user/My Report.pdf"),
+                    time("2024-05-02T08:30:00.123456Z")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn bookmarks_without_a_local_path_or_timestamp_are_skipped() {
+        let contents = r#"
+  <bookmark href="https://example.com/page" added="2024-01-01T10:00:00Z"/>
+  <bookmark href="file:///home/user/undated.txt"/>
+  <bookmark href="file:///home/user/bad-date.txt" added="yesterday"/>
+  <bookmark added="2024-01-01T10:00:00Z"/>"#;
+
+        assert!(parse_xbel(contents).is_empty());
+    }
+
+    #[test]
+    fn attributes_may_use_single_quotes_and_any_order() {
+        let contents = "<bookmark visited='2024-01-01T10:00:00Z'\n href='file:///tmp/a.txt'>";
+
+        assert_eq!(
+            parse_xbel(contents),
+            vec![(PathBuf::from("/tmp/a.txt"), time("2024-01-01T10:00:00Z"))]
+        );
+    }
+
+    #[test]
+    fn entities_are_decoded_in_hrefs() {
+        let contents = r#"<bookmark href="file:///tmp/Tom&amp;Jerry&#39;s &#x4E2D;.txt" added="2024-01-01T10:00:00Z"/>"#;
+
+        assert_eq!(
+            parse_xbel(contents)[0].0,
+            PathBuf::from("/tmp/Tom&Jerry's 中.txt")
+        );
+    }
+
+    #[test]
+    fn entities_are_decoded_once() {
+        assert_eq!(
+            unescape_xml("&lt;a&gt; &quot;b&quot; &apos;c&apos;"),
+            "<a> \"b\" 'c'"
+        );
+        assert_eq!(unescape_xml("&amp;lt;"), "&lt;");
+        assert_eq!(unescape_xml("&amp;amp;"), "&amp;");
+        assert_eq!(unescape_xml("&#65;&#x42;"), "AB");
+    }
+
+    #[test]
+    fn unknown_or_invalid_entities_are_kept() {
+        assert_eq!(
+            unescape_xml("&nbsp; & &#xD800; &#99999999;"),
+            "&nbsp; & &#xD800; &#99999999;"
+        );
+    }
+}
@@ -0,0 +1,257 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize, Runtime, Window};
+use tokio::task;
+
+use crate::app_data_dir;
+use crate::settings::{SortOrder, ViewMode};
+
+const MAIN_WINDOW: &str = "main";
+/// Per-directory view states kept, most recently used first.
+const MAX_DIRECTORY_STATES: usize = 200;
+/// Smallest window restored, so a bad geometry cannot hide the app.
+const MIN_WINDOW_SIZE: u32 = 200;
+/// Quiet period after the last move or resize before the geometry is saved,
+/// so dragging a window does not write the session on every step.
+const GEOMETRY_SAVE_DELAY: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TabState {
+    path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectoryViewState {
+    path: String,
+    #[serde(default)]
+    sort: SortOrder,
+    #[serde(default)]
+    view_mode: ViewMode,
+    /// Name of the entry at the top of the view, so scrolling survives
+    /// entries being added or removed above it.
+    #[serde(default)]
+    scroll_anchor: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    maximized: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    tabs: Vec<TabState>,
+    active_tab: usize,
+    directories: Vec<DirectoryViewState>,
+    /// Tracked by the backend as the main window moves, resizes and closes;
+    /// a saved session without it keeps the last recorded geometry.
+    window: Option<WindowGeometry>,
+}
+
+fn session_path() -> Result<PathBuf, String> {
+    Ok(app_data_dir()?.join("session.json"))
+}
+
+fn get_session_store() -> &'static Mutex<Session> {
+    static SESSION: OnceLock<Mutex<Session>> = OnceLock::new();
+
+    SESSION.get_or_init(|| {
+        let session = session_path()
+            .ok()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str::<Session>(&contents).ok())
+            .unwrap_or_default();
+
+        Mutex::new(session)
+    })
+}
+
+/// Written synchronously so it can also run from window event handlers,
+/// which are not async.
+fn write_session(session: &Session) -> Result<(), String> {
+    let path = session_path()?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create data directory: {}", e))?;
+    }
+
+    let contents = serde_json::to_string_pretty(session)
+        .map_err(|e| format!("Failed to serialize session: {}", e))?;
+
+    // Written beside the real file and renamed over it, so a crash cannot
+    // leave it truncated.
+    let temporary = path.with_extension("json.tmp");
+
+    std::fs::write(&temporary, contents).map_err(|e| format!("Failed to write session: {}", e))?;
+
+    std::fs::rename(&temporary, &path).map_err(|e| format!("Failed to write session: {}", e))
+}
+
+/// The closest directory to `path` that still exists, for tabs whose
+/// location disappeared since the last run (unmounted drives and the like).
+fn nearest_existing_directory(path: &Path) -> Option<PathBuf> {
+    path.ancestors()
+        .find(|ancestor| ancestor.is_dir())
+        .map(Path::to_path_buf)
+}
+
+/// Returns the saved session with tabs pointed at directories that exist.
+/// An empty session opens a single tab in the home directory.
+#[tauri::command]
+pub async fn get_session() -> Result<Session, String> {
+    let mut session = get_session_store().lock().unwrap().clone();
+
+    let home = dirs::home_dir().ok_or_else(|| "Failed to get home directory".to_string())?;
+
+    for tab in &mut session.tabs {
+        let path = nearest_existing_directory(Path::new(&tab.path)).unwrap_or(home.clone());
+        tab.path = path.to_string_lossy().to_string();
+    }
+
+    if session.tabs.is_empty() {
+        session.tabs.push(TabState {
+            path: home.to_string_lossy().to_string(),
+        });
+    }
+
+    session.active_tab = session.active_tab.min(session.tabs.len() - 1);
+
+    Ok(session)
+}
+
+#[tauri::command]
+pub async fn save_session(mut session: Session) -> Result<(), String> {
+    task::spawn_blocking(move || {
+        let mut stored = get_session_store().lock().unwrap();
+
+        session.directories.truncate(MAX_DIRECTORY_STATES);
+        if session.window.is_none() {
+            session.window = stored.window;
+        }
+
+        write_session(&session)?;
+        *stored = session;
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Failed to save session: {}", e))?
+}
+
+/// Records the main window's geometry. A maximized window keeps the
+/// previous unmaximized bounds so un-maximizing after a restore works.
+pub fn remember_window<R: Runtime>(window: &Window<R>) {
+    if window.label() != MAIN_WINDOW {
+        return;
+    }
+
+    let mut session = get_session_store().lock().unwrap();
+    let maximized = window.is_maximized().unwrap_or(false);
+
+    let geometry = match (maximized, session.window) {
+        (true, Some(previous)) => WindowGeometry {
+            maximized,
+            ..previous
+        },
+        _ => {
+            let (Ok(position), Ok(size)) = (window.outer_position(), window.inner_size()) else {
+                return;
+            };
+
+            WindowGeometry {
+                x: position.x,
+                y: position.y,
+                width: size.width,
+                height: size.height,
+                maximized,
+            }
+        }
+    };
+
+    session.window = Some(geometry);
+    let _ = write_session(&session);
+}
+
+/// Records the main window's geometry once it has stopped moving or
+/// resizing for a moment. Events arriving while a save is pending only push
+/// it back.
+pub fn schedule_remember_window<R: Runtime>(window: &Window<R>) {
+    static DEADLINE: Mutex<Option<Instant>> = Mutex::new(None);
+
+    if window.label() != MAIN_WINDOW {
+        return;
+    }
+
+    let pending = DEADLINE
+        .lock()
+        .unwrap()
+        .replace(Instant::now() + GEOMETRY_SAVE_DELAY)
+        .is_some();
+
+    if pending {
+        return;
+    }
+
+    let window = window.clone();
+
+    std::thread::spawn(move || {
+        loop {
+            let wait = {
+                let mut deadline = DEADLINE.lock().unwrap();
+                match deadline.map(|deadline| deadline.saturating_duration_since(Instant::now())) {
+                    Some(wait) if !wait.is_zero() => wait,
+                    _ => {
+                        *deadline = None;
+                        break;
+                    }
+                }
+            };
+
+            std::thread::sleep(wait);
+        }
+
+        remember_window(&window);
+    });
+}
+
+/// Applies the saved geometry to the main window at startup. The position
+/// is only restored when it still lands on a connected monitor.
+pub fn restore_window<R: Runtime>(app: &AppHandle<R>) {
+    let Some(geometry) = get_session_store().lock().unwrap().window else {
+        return;
+    };
+
+    let Some(window) = app.get_webview_window(MAIN_WINDOW) else {
+        return;
+    };
+
+    let _ = window.set_size(PhysicalSize::new(
+        geometry.width.max(MIN_WINDOW_SIZE),
+        geometry.height.max(MIN_WINDOW_SIZE),
+    ));
+
+    let visible = window.available_monitors().is_ok_and(|monitors| {
+        monitors.iter().any(|monitor| {
+            let (origin, size) = (monitor.position(), monitor.size());
+            geometry.x >= origin.x
+                && geometry.y >= origin.y
+                && i64::from(geometry.x) < i64::from(origin.x) + i64::from(size.width)
+                && i64::from(geometry.y) < i64::from(origin.y) + i64::from(size.height)
+        })
+    });
+
+    if visible {
+        let _ = window.set_position(PhysicalPosition::new(geometry.x, geometry.y));
+    }
+
+    if geometry.maximized {
+        let _ = window.maximize();
+    }
+}
@@ -0,0 +1,326 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::path::PathBuf;
+use std::sync::{OnceLock, RwLock};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::fs;
+use tokio::sync::Mutex;
+
+use crate::app_config_dir;
+
+/// Bumped whenever the layout of `settings.json` changes, together with a
+/// new entry in `MIGRATIONS`.
+const SETTINGS_VERSION: u64 = 1;
+const MAX_CACHE_TTL_SECS: u64 = 3600;
+
+/// Upgrades raw settings one version at a time: entry `n` turns a version
+/// `n` object into version `n + 1`.
+const MIGRATIONS: &[fn(&mut Map<String, Value>)] = &[
+    // Files written before versioning already use the version 1 layout.
+    |_| {},
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortKey {
+    #[default]
+    Name,
+    Size,
+    Modified,
+    Type,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ViewMode {
+    #[default]
+    List,
+    Grid,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SortOrder {
+    pub(crate) key: SortKey,
+    pub(crate) descending: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    version: u64,
+    show_hidden: bool,
+    view_mode: ViewMode,
+    default_sort: SortOrder,
+    /// How long `list_directory` may answer from its cache.
+    cache_ttl_secs: u64,
+    confirm_delete: bool,
+    /// File and directory names that search never descends into or reports.
+    search_exclude: Vec<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            show_hidden: false,
+            view_mode: ViewMode::default(),
+            default_sort: SortOrder::default(),
+            cache_ttl_secs: 30,
+            confirm_delete: true,
+            search_exclude: Vec::new(),
+        }
+    }
+}
+
+fn settings_path() -> Result<PathBuf, String> {
+    Ok(app_config_dir()?.join("settings.json"))
+}
+
+/// Applies every migration newer than the object's `version`.
+fn migrate(mut value: Value) -> Value {
+    let Some(object) = value.as_object_mut() else {
+        return value;
+    };
+
+    let mut version = object.get("version").and_then(Value::as_u64).unwrap_or(0);
+
+    while let Some(migration) = MIGRATIONS.get(version as usize) {
+        migration(object);
+        version += 1;
+    }
+
+    object.insert("version".to_string(), Value::from(version));
+    value
+}
+
+fn get_store() -> &'static RwLock<Settings> {
+    static SETTINGS: OnceLock<RwLock<Settings>> = OnceLock::new();
+
+    SETTINGS.get_or_init(|| RwLock::new(load_settings().unwrap_or_default()))
+}
+
+fn parse_settings(contents: &str) -> Result<Settings, String> {
+    let mut settings = serde_json::from_str::<Value>(contents)
+        .and_then(|value| serde_json::from_value::<Settings>(migrate(value)))
+        .map_err(|e| format!("Invalid settings: {}", e))?;
+
+    validate(&mut settings)?;
+    Ok(settings)
+}
+
+/// Reads the settings file, or `None` when there is none yet. A file that
+/// cannot be used is moved aside to `settings.json.bad`, where the user can
+/// still repair it, before the defaults take its place.
+fn load_settings() -> Option<Settings> {
+    let path = settings_path().ok()?;
+    let contents = std::fs::read_to_string(&path).ok()?;
+
+    match parse_settings(&contents) {
+        Ok(settings) => Some(settings),
+        Err(_) => {
+            let _ = std::fs::rename(&path, path.with_extension("json.bad"));
+            None
+        }
+    }
+}
+
+/// Serializes updates so concurrent patches cannot overwrite each other.
+fn get_update_lock() -> &'static Mutex<()> {
+    static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+
+    LOCK.get_or_init(|| Mutex::new(()))
+}
+
+fn current() -> Settings {
+    get_store().read().unwrap().clone()
+}
+
+pub(crate) fn cache_ttl() -> Duration {
+    Duration::from_secs(get_store().read().unwrap().cache_ttl_secs)
+}
+
+pub(crate) fn default_sort() -> SortOrder {
+    get_store().read().unwrap().default_sort
+}
+
+pub(crate) fn is_search_excluded(name: &str) -> bool {
+    get_store()
+        .read()
+        .unwrap()
+        .search_exclude
+        .iter()
+        .any(|excluded| excluded == name)
+}
+
+fn validate(settings: &mut Settings) -> Result<(), String> {
+    if settings.cache_ttl_secs > MAX_CACHE_TTL_SECS {
+        return Err(format!(
+            "Cache TTL cannot exceed {} seconds",
+            MAX_CACHE_TTL_SECS
+        ));
+    }
+
+    settings.search_exclude = settings
+        .search_exclude
+        .iter()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect();
+
+    if let Some(name) = settings
+        .search_exclude
+        .iter()
+        .find(|name| name.contains('/'))
+    {
+        return Err(format!(
+            "Search exclusions must be names, not paths: {}",
+            name
+        ));
+    }
+
+    Ok(())
+}
+
+async fn save_settings(settings: &Settings) -> Result<(), String> {
+    let path = settings_path()?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create configuration directory: {}", e))?;
+    }
+
+    let contents = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+
+    // Written beside the real file and renamed over it, so a crash cannot
+    // leave it truncated.
+    let temporary = path.with_extension("json.tmp");
+
+    fs::write(&temporary, contents)
+        .await
+        .map_err(|e| format!("Failed to write settings: {}", e))?;
+
+    fs::rename(&temporary, &path)
+        .await
+        .map_err(|e| format!("Failed to write settings: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_settings() -> Result<Settings, String> {
+    Ok(current())
+}
+
+/// Applies `changes`, an object holding only the settings to change, and
+/// emits `settings-changed` with the result.
+#[tauri::command]
+pub async fn update_settings(app: AppHandle, changes: Value) -> Result<Settings, String> {
+    let _guard = get_update_lock().lock().await;
+
+    let Some(changes) = changes.as_object() else {
+        return Err("Settings changes must be an object".to_string());
+    };
+
+    let mut value = serde_json::to_value(current())
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+
+    if let Some(target) = value.as_object_mut() {
+        for (key, change) in changes {
+            if key == "version" || !target.contains_key(key) {
+                return Err(format!("Unknown setting: {}", key));
+            }
+            target.insert(key.clone(), change.clone());
+        }
+    }
+
+    let mut settings: Settings =
+        serde_json::from_value(value).map_err(|e| format!("Invalid settings: {}", e))?;
+    validate(&mut settings)?;
+
+    save_settings(&settings).await?;
+    *get_store().write().unwrap() = settings.clone();
+
+    let _ = app.emit("settings-changed", &settings);
+
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn files_without_a_version_are_migrated() {
+        assert_eq!(
+            migrate(json!({ "show_hidden": true })),
+            json!({ "show_hidden": true, "version": SETTINGS_VERSION })
+        );
+        assert_eq!(
+            migrate(json!({ "version": SETTINGS_VERSION, "confirm_delete": false })),
+            json!({ "version": SETTINGS_VERSION, "confirm_delete": false })
+        );
+    }
+
+    #[test]
+    fn migration_leaves_what_it_does_not_understand() {
+        assert_eq!(
+            migrate(json!({ "version": SETTINGS_VERSION + 1 })),
+            json!({ "version": SETTINGS_VERSION + 1 })
+        );
+        assert_eq!(migrate(json!([1, 2])), json!([1, 2]));
+    }
+
+    #[test]
+    fn validation_limits_the_cache_and_cleans_exclusions() {
+        let mut settings = Settings {
+            search_exclude: vec![" node_modules ".to_string(), "  ".to_string()],
+            ..Settings::default()
+        };
+        validate(&mut settings).unwrap();
+        assert_eq!(settings.search_exclude, ["node_modules"]);
+
+        settings.search_exclude = vec!["src/target".to_string()];
+        assert!(validate(&mut settings).is_err());
+
+        let mut settings = Settings {
+            cache_ttl_secs: MAX_CACHE_TTL_SECS + 1,
+            ..Settings::default()
+        };
+        assert!(validate(&mut settings).is_err());
+    }
+
+    #[test]
+    fn settings_files_are_parsed_with_defaults_for_missing_fields() {
+        let settings = parse_settings(r#"{ "view_mode": "grid", "unknown": 1 }"#).unwrap();
+
+        assert_eq!(settings.view_mode, ViewMode::Grid);
+        assert_eq!(settings.version, SETTINGS_VERSION);
+        assert_eq!(settings.cache_ttl_secs, Settings::default().cache_ttl_secs);
+
+        assert!(parse_settings("{ not json").is_err());
+        assert!(parse_settings(r#"{ "show_hidden": "yes" }"#).is_err());
+        assert!(parse_settings(r#"{ "cache_ttl_secs": 86400 }"#).is_err());
+    }
+
+    #[test]
+    fn unusable_settings_are_moved_aside() {
+        let dir = tempfile::tempdir().unwrap();
+        std::env::set_var("XDG_CONFIG_HOME", dir.path());
+        let path = settings_path().unwrap();
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+
+        std::fs::write(&path, r#"{ "show_hidden": true }"#).unwrap();
+        assert!(load_settings().unwrap().show_hidden);
+
+        std::fs::write(&path, "{ truncated").unwrap();
+        assert_eq!(load_settings(), None);
+        assert!(!path.exists());
+        assert_eq!(
+            std::fs::read_to_string(path.with_extension("json.bad")).unwrap(),
+            "{ truncated"
+        );
+    }
+}
@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+use tokio::fs;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateEntry {
+    id: String,
+    name: String,
+    file_name: String,
+    category: Option<String>,
+}
+
+fn templates_dir() -> Option<PathBuf> {
+    let dir =
+        dirs::template_dir().or_else(|| dirs::home_dir().map(|home| home.join("Templates")))?;
+
+    if Some(&dir) == dirs::home_dir().as_ref() {
+        return None;
+    }
+
+    Some(dir)
+}
+
+pub(crate) fn resolve_template(id: &str) -> Result<PathBuf, String> {
+    let relative = Path::new(id);
+
+    if relative
+        .components()
+        .any(|component| !matches!(component, Component::Normal(_)))
+    {
+        return Err(format!("Invalid template: {}", id));
+    }
+
+    let template = templates_dir()
+        .ok_or_else(|| "No templates directory configured".to_string())?
+        .join(relative);
+
+    if !template.is_file() {
+        return Err(format!("Template does not exist: {}", id));
+    }
+
+    Ok(template)
+}
+
+async fn collect_templates(dir: &Path, relative: &Path, templates: &mut Vec<TemplateEntry>) {
+    let Ok(mut entries) = fs::read_dir(dir).await else {
+        return;
+    };
+
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+
+        if file_name.starts_with('.') || file_name.ends_with('~') {
+            continue;
+        }
+
+        let Ok(metadata) = fs::metadata(entry.path()).await else {
+            continue;
+        };
+
+        let id = relative.join(&file_name);
+
+        if metadata.is_dir() {
+            Box::pin(collect_templates(&entry.path(), &id, templates)).await;
+        } else if metadata.is_file() {
+            let name = Path::new(&file_name)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .filter(|stem| !stem.is_empty())
+                .unwrap_or_else(|| file_name.clone());
+
+            let category = relative
+                .to_str()
+                .filter(|category| !category.is_empty())
+                .map(str::to_string);
+
+            templates.push(TemplateEntry {
+                id: id.to_string_lossy().to_string(),
+                name,
+                file_name,
+                category,
+            });
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn list_templates() -> Result<Vec<TemplateEntry>, String> {
+    let Some(dir) = templates_dir() else {
+        return Ok(Vec::new());
+    };
+
+    let mut templates = Vec::new();
+    collect_templates(&dir, Path::new(""), &mut templates).await;
+
+    templates.sort_by(|a, b| {
+        a.category
+            .cmp(&b.category)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+
+    Ok(templates)
+}
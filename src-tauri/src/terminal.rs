@@ -0,0 +1,110 @@
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use tokio::task;
+
+use crate::applications::spawn_detached;
+
+/// Tried in order when neither `$TERMINAL` nor `x-terminal-emulator` exist.
+const FALLBACK_TERMINALS: &[&str] = &[
+    "gnome-terminal",
+    "konsole",
+    "xfce4-terminal",
+    "mate-terminal",
+    "kgx",
+    "alacritty",
+    "kitty",
+    "foot",
+    "wezterm",
+    "urxvt",
+    "xterm",
+];
+
+/// Looks `program` up on `$PATH`, accepting explicit paths as they are.
+fn find_program(program: &str) -> Option<PathBuf> {
+    let path = Path::new(program);
+    if program.contains('/') {
+        return path.is_file().then(|| path.to_path_buf());
+    }
+
+    std::env::var_os("PATH").and_then(|paths| {
+        std::env::split_paths(&paths)
+            .map(|directory| directory.join(program))
+            .find(|candidate| is_executable(candidate))
+    })
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    path.metadata()
+        .map(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// The user's terminal emulator as a program followed by any arguments
+/// given in `$TERMINAL`.
+fn detect_terminal() -> Result<Vec<OsString>, String> {
+    if let Ok(terminal) = std::env::var("TERMINAL") {
+        let mut words = terminal.split_whitespace();
+        if let Some(program) = words.next().and_then(find_program) {
+            return Ok(std::iter::once(program.into_os_string())
+                .chain(words.map(OsString::from))
+                .collect());
+        }
+    }
+
+    std::iter::once("x-terminal-emulator")
+        .chain(FALLBACK_TERMINALS.iter().copied())
+        .find_map(find_program)
+        .map(|program| vec![program.into_os_string()])
+        .ok_or_else(|| "No terminal emulator found".to_string())
+}
+
+/// The arguments that make `terminal` run a command given as separate
+/// words. Unknown terminals get the `-e` that xterm and Debian's
+/// `x-terminal-emulator` use.
+fn exec_arguments(terminal: &Path) -> &'static [&'static str] {
+    let name = terminal
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    match name.as_str() {
+        "gnome-terminal" | "kgx" => &["--"],
+        "xfce4-terminal" | "mate-terminal" => &["-x"],
+        "kitty" | "foot" => &[],
+        "wezterm" => &["start", "--"],
+        _ => &["-e"],
+    }
+}
+
+/// Wraps `command` so it runs inside the user's terminal emulator, as
+/// needed for `Terminal=true` applications.
+pub(crate) fn in_terminal(command: &[OsString]) -> Result<Vec<OsString>, String> {
+    let mut arguments = detect_terminal()?;
+    let flags = exec_arguments(Path::new(&arguments[0]));
+
+    arguments.extend(flags.iter().map(OsString::from));
+    arguments.extend(command.iter().cloned());
+    Ok(arguments)
+}
+
+/// Opens the user's terminal emulator with `path` as its working directory.
+#[tauri::command]
+pub async fn open_terminal(path: String) -> Result<(), String> {
+    let directory = PathBuf::from(&path);
+
+    if !directory.is_dir() {
+        return Err(format!("Directory does not exist: {}", path));
+    }
+
+    task::spawn_blocking(move || spawn_detached(&detect_terminal()?, &directory))
+        .await
+        .map_err(|e| format!("Failed to open terminal: {}", e))?
+}
@@ -0,0 +1,223 @@
+use md5::{Digest, Md5};
+use serde::{Deserialize, Serialize};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tokio::task;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThumbnailSize {
+    Normal,
+    Large,
+    XLarge,
+    XxLarge,
+}
+
+impl ThumbnailSize {
+    fn pixels(self) -> u32 {
+        match self {
+            ThumbnailSize::Normal => 128,
+            ThumbnailSize::Large => 256,
+            ThumbnailSize::XLarge => 512,
+            ThumbnailSize::XxLarge => 1024,
+        }
+    }
+
+    fn directory(self) -> &'static str {
+        match self {
+            ThumbnailSize::Normal => "normal",
+            ThumbnailSize::Large => "large",
+            ThumbnailSize::XLarge => "x-large",
+            ThumbnailSize::XxLarge => "xx-large",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Thumbnail {
+    path: String,
+    width: u32,
+    height: u32,
+}
+
+#[cfg(unix)]
+fn path_bytes(path: &Path) -> Vec<u8> {
+    use std::os::unix::ffi::OsStrExt;
+    path.as_os_str().as_bytes().to_vec()
+}
+
+#[cfg(not(unix))]
+fn path_bytes(path: &Path) -> Vec<u8> {
+    format!("/{}", path.to_string_lossy().replace('\\', "/")).into_bytes()
+}
+
+fn file_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+
+    for byte in path_bytes(path) {
+        if byte.is_ascii_alphanumeric() || b"-_.!~*'()/:@&=+$,".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{:02X}", byte));
+        }
+    }
+
+    uri
+}
+
+fn thumbnail_path(uri: &str, size: ThumbnailSize) -> Result<PathBuf, String> {
+    let cache_dir = dirs::cache_dir().ok_or_else(|| "Failed to get cache directory".to_string())?;
+    let hash = Md5::digest(uri.as_bytes());
+
+    Ok(cache_dir
+        .join("thumbnails")
+        .join(size.directory())
+        .join(format!("{:x}.png", hash)))
+}
+
+fn read_cached(thumbnail: &Path, uri: &str, mtime: u64) -> Option<Thumbnail> {
+    let file = std::fs::File::open(thumbnail).ok()?;
+    let reader = png::Decoder::new(BufReader::new(file)).read_info().ok()?;
+    let info = reader.info();
+
+    let text = |keyword: &str| {
+        info.uncompressed_latin1_text
+            .iter()
+            .find(|chunk| chunk.keyword == keyword)
+            .map(|chunk| chunk.text.as_str())
+    };
+
+    if text("Thumb::URI") != Some(uri) || text("Thumb::MTime") != Some(&mtime.to_string()) {
+        return None;
+    }
+
+    Some(Thumbnail {
+        path: thumbnail.to_string_lossy().to_string(),
+        width: info.width,
+        height: info.height,
+    })
+}
+
+fn generate(
+    source: &Path,
+    thumbnail: &Path,
+    uri: &str,
+    mtime: u64,
+    file_size: u64,
+    size: ThumbnailSize,
+) -> Result<Thumbnail, String> {
+    let image = image::ImageReader::open(source)
+        .and_then(|reader| reader.with_guessed_format())
+        .map_err(|e| format!("Failed to open image: {}", e))?
+        .decode()
+        .map_err(|e| format!("Failed to decode image: {}", e))?;
+
+    let (original_width, original_height) = (image.width(), image.height());
+    let pixels = size.pixels();
+
+    let scaled = if original_width > pixels || original_height > pixels {
+        image.thumbnail(pixels, pixels)
+    } else {
+        image
+    };
+    let rgba = scaled.to_rgba8();
+
+    let directory = thumbnail
+        .parent()
+        .ok_or_else(|| "Invalid thumbnail path".to_string())?;
+    std::fs::create_dir_all(directory)
+        .map_err(|e| format!("Failed to create thumbnail directory: {}", e))?;
+
+    let temporary = directory.join(format!(".wiregui-{}.png", uuid::Uuid::new_v4()));
+    let file = std::fs::File::create(&temporary)
+        .map_err(|e| format!("Failed to write thumbnail: {}", e))?;
+
+    let mut encoder = png::Encoder::new(std::io::BufWriter::new(file), rgba.width(), rgba.height());
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let chunks = [
+        ("Thumb::URI", uri.to_string()),
+        ("Thumb::MTime", mtime.to_string()),
+        ("Thumb::Size", file_size.to_string()),
+        ("Thumb::Image::Width", original_width.to_string()),
+        ("Thumb::Image::Height", original_height.to_string()),
+        ("Software", "wiregui".to_string()),
+    ];
+
+    let written = chunks
+        .into_iter()
+        .try_for_each(|(keyword, text)| encoder.add_text_chunk(keyword.to_string(), text))
+        .and_then(|_| encoder.write_header())
+        .and_then(|mut writer| {
+            writer.write_image_data(rgba.as_raw())?;
+            writer.finish()
+        });
+
+    if let Err(e) = written {
+        let _ = std::fs::remove_file(&temporary);
+        return Err(format!("Failed to encode thumbnail: {}", e));
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(&temporary, std::fs::Permissions::from_mode(0o600));
+    }
+
+    std::fs::rename(&temporary, thumbnail).map_err(|e| {
+        let _ = std::fs::remove_file(&temporary);
+        format!("Failed to write thumbnail: {}", e)
+    })?;
+
+    Ok(Thumbnail {
+        path: thumbnail.to_string_lossy().to_string(),
+        width: rgba.width(),
+        height: rgba.height(),
+    })
+}
+
+#[tauri::command]
+pub async fn get_thumbnail(path: String, size: Option<ThumbnailSize>) -> Result<Thumbnail, String> {
+    let size = size.unwrap_or(ThumbnailSize::Normal);
+
+    let source = tokio::fs::canonicalize(&path)
+        .await
+        .map_err(|_| format!("File does not exist: {}", path))?;
+
+    let metadata = tokio::fs::metadata(&source)
+        .await
+        .map_err(|e| format!("Failed to read metadata: {}", e))?;
+
+    if !metadata.is_file() {
+        return Err("Path is not a file".to_string());
+    }
+
+    let mtime = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+
+    let uri = file_uri(&source);
+    let thumbnail = thumbnail_path(&uri, size)?;
+
+    if source.starts_with(
+        thumbnail
+            .parent()
+            .and_then(Path::parent)
+            .unwrap_or(&thumbnail),
+    ) {
+        return Err("Cannot create thumbnails of thumbnails".to_string());
+    }
+
+    task::spawn_blocking(move || {
+        read_cached(&thumbnail, &uri, mtime)
+            .map(Ok)
+            .unwrap_or_else(|| generate(&source, &thumbnail, &uri, mtime, metadata.len(), size))
+    })
+    .await
+    .map_err(|e| format!("Failed to generate thumbnail: {}", e))?
+}
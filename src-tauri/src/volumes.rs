@@ -0,0 +1,414 @@
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tokio::task;
+
+#[cfg(target_os = "linux")]
+use std::collections::HashMap;
+#[cfg(target_os = "linux")]
+use std::path::{Path, PathBuf};
+#[cfg(target_os = "linux")]
+use tauri::Emitter;
+
+#[cfg(target_os = "linux")]
+const MOUNTINFO_PATH: &str = "/proc/self/mountinfo";
+/// Upper bound between rescans in case a mount change wakes nobody up.
+#[cfg(target_os = "linux")]
+const WATCH_TIMEOUT_MS: i32 = 5000;
+
+#[cfg(target_os = "linux")]
+const PSEUDO_FILESYSTEMS: &[&str] = &[
+    "autofs",
+    "binfmt_misc",
+    "bpf",
+    "cgroup",
+    "cgroup2",
+    "configfs",
+    "debugfs",
+    "devpts",
+    "devtmpfs",
+    "efivarfs",
+    "fusectl",
+    "fuse.gvfsd-fuse",
+    "fuse.portal",
+    "hugetlbfs",
+    "mqueue",
+    "nsfs",
+    "proc",
+    "pstore",
+    "ramfs",
+    "rpc_pipefs",
+    "securityfs",
+    "selinuxfs",
+    "squashfs",
+    "sysfs",
+    "tracefs",
+];
+
+#[cfg(target_os = "linux")]
+const HIDDEN_MOUNT_PREFIXES: &[&str] = &["/proc", "/sys", "/dev", "/snap", "/boot/efi"];
+
+/// The system mounts plenty of tmpfs of its own (`/tmp`, `/run/user/…`), so
+/// one is only shown where a user would have mounted it.
+#[cfg(target_os = "linux")]
+const USER_MOUNT_PREFIXES: &[&str] = &["/mnt", "/media", "/run/media"];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Volume {
+    name: String,
+    mount_point: String,
+    device: String,
+    filesystem: String,
+    label: Option<String>,
+    uuid: Option<String>,
+    read_only: bool,
+    total_bytes: u64,
+    free_bytes: u64,
+    available_bytes: u64,
+}
+
+#[cfg(target_os = "linux")]
+struct MountInfo {
+    mount_point: String,
+    device: String,
+    filesystem: String,
+    read_only: bool,
+}
+
+#[cfg(target_os = "linux")]
+fn parse_byte(digits: &[u8], radix: u32) -> Option<u8> {
+    u8::from_str_radix(std::str::from_utf8(digits).ok()?, radix).ok()
+}
+
+/// Decodes the octal escapes (`\040` for space and friends) used in
+/// mountinfo fields.
+#[cfg(target_os = "linux")]
+fn unescape_octal(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'\\' && i + 4 <= bytes.len() {
+            if let Some(byte) = parse_byte(&bytes[i + 1..i + 4], 8) {
+                result.push(byte);
+                i += 4;
+                continue;
+            }
+        }
+        result.push(bytes[i]);
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&result).to_string()
+}
+
+/// Parses a line such as
+/// `36 35 98:0 / /mnt rw,noatime master:1 - ext4 /dev/sda1 rw,errors=continue`.
+#[cfg(target_os = "linux")]
+fn parse_mountinfo_line(line: &str) -> Option<MountInfo> {
+    let (left, right) = line.split_once(" - ")?;
+    let left = left.split(' ').collect::<Vec<_>>();
+    let mut right = right.split(' ');
+
+    let mount_point = unescape_octal(left.get(4)?);
+    let options = left.get(5)?;
+    let filesystem = right.next()?.to_string();
+    let device = unescape_octal(right.next()?);
+
+    Some(MountInfo {
+        mount_point,
+        device,
+        filesystem,
+        read_only: options.split(',').any(|option| option == "ro"),
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn is_under(mount_point: &str, prefix: &str) -> bool {
+    mount_point == prefix || mount_point.starts_with(&format!("{}/", prefix))
+}
+
+#[cfg(target_os = "linux")]
+fn is_user_visible(mount: &MountInfo, home: Option<&Path>) -> bool {
+    let hidden_prefix = HIDDEN_MOUNT_PREFIXES
+        .iter()
+        .any(|prefix| is_under(&mount.mount_point, prefix));
+    let hidden_run =
+        mount.mount_point.starts_with("/run") && !mount.mount_point.starts_with("/run/media/");
+
+    let user_mount = USER_MOUNT_PREFIXES
+        .iter()
+        .any(|prefix| mount.mount_point.starts_with(&format!("{}/", prefix)))
+        || home.is_some_and(|home| Path::new(&mount.mount_point).starts_with(home));
+    let hidden_tmpfs = mount.filesystem == "tmpfs" && !user_mount;
+
+    !PSEUDO_FILESYSTEMS.contains(&mount.filesystem.as_str())
+        && !hidden_tmpfs
+        && !hidden_prefix
+        && !hidden_run
+}
+
+/// Decodes the `\x20`-style escapes udev uses in `/dev/disk/by-label` names.
+#[cfg(target_os = "linux")]
+fn unescape_hex(name: &str) -> String {
+    let bytes = name.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'\\' && bytes.get(i + 1) == Some(&b'x') && i + 4 <= bytes.len() {
+            if let Some(byte) = parse_byte(&bytes[i + 2..i + 4], 16) {
+                result.push(byte);
+                i += 4;
+                continue;
+            }
+        }
+        result.push(bytes[i]);
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&result).to_string()
+}
+
+/// Maps resolved device nodes to the names of the symlinks pointing at them.
+#[cfg(target_os = "linux")]
+fn disk_links(directory: &str) -> HashMap<PathBuf, String> {
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return HashMap::new();
+    };
+
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let device = std::fs::canonicalize(entry.path()).ok()?;
+            Some((device, unescape_hex(&entry.file_name().to_string_lossy())))
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn space_of(mount_point: &str) -> (u64, u64, u64) {
+    use std::ffi::CString;
+
+    let Ok(path) = CString::new(mount_point) else {
+        return (0, 0, 0);
+    };
+
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return (0, 0, 0);
+    }
+
+    let fragment = stat.f_frsize as u64;
+    (
+        stat.f_blocks as u64 * fragment,
+        stat.f_bfree as u64 * fragment,
+        stat.f_bavail as u64 * fragment,
+    )
+}
+
+#[cfg(target_os = "linux")]
+fn read_volumes() -> Result<Vec<Volume>, String> {
+    let content = std::fs::read_to_string(MOUNTINFO_PATH)
+        .map_err(|e| format!("Failed to read mount table: {}", e))?;
+
+    let labels = disk_links("/dev/disk/by-label");
+    let uuids = disk_links("/dev/disk/by-uuid");
+    let home = dirs::home_dir();
+
+    let mut volumes: Vec<Volume> = Vec::new();
+
+    for mount in content.lines().filter_map(parse_mountinfo_line) {
+        if !is_user_visible(&mount, home.as_deref()) {
+            continue;
+        }
+
+        // A later mount on the same point shadows the earlier one.
+        volumes.retain(|volume| volume.mount_point != mount.mount_point);
+
+        let resolved = std::fs::canonicalize(&mount.device).ok();
+        let label = resolved
+            .as_ref()
+            .and_then(|device| labels.get(device).cloned());
+        let uuid = resolved
+            .as_ref()
+            .and_then(|device| uuids.get(device).cloned());
+
+        let name =
+            label
+                .clone()
+                .unwrap_or_else(|| match Path::new(&mount.mount_point).file_name() {
+                    Some(name) => name.to_string_lossy().to_string(),
+                    None => "File System".to_string(),
+                });
+
+        let (total_bytes, free_bytes, available_bytes) = space_of(&mount.mount_point);
+
+        volumes.push(Volume {
+            name,
+            mount_point: mount.mount_point,
+            device: mount.device,
+            filesystem: mount.filesystem,
+            label,
+            uuid,
+            read_only: mount.read_only,
+            total_bytes,
+            free_bytes,
+            available_bytes,
+        });
+    }
+
+    Ok(volumes)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_volumes() -> Result<Vec<Volume>, String> {
+    Ok(Vec::new())
+}
+
+#[tauri::command]
+pub async fn list_volumes() -> Result<Vec<Volume>, String> {
+    task::spawn_blocking(read_volumes)
+        .await
+        .map_err(|e| format!("Failed to list volumes: {}", e))?
+}
+
+/// The kernel flags `/proc/self/mountinfo` with `POLLPRI` whenever the mount
+/// table changes, so the watcher sleeps in `poll` instead of rescanning.
+#[cfg(target_os = "linux")]
+fn wait_for_mount_change(file: &std::fs::File) {
+    use std::os::unix::io::AsRawFd;
+
+    let mut descriptor = libc::pollfd {
+        fd: file.as_raw_fd(),
+        events: libc::POLLPRI | libc::POLLERR,
+        revents: 0,
+    };
+
+    if unsafe { libc::poll(&mut descriptor, 1, WATCH_TIMEOUT_MS) } < 0 {
+        std::thread::sleep(std::time::Duration::from_millis(WATCH_TIMEOUT_MS as u64));
+    }
+}
+
+/// Emits `volume-mounted` and `volume-unmounted` events for the lifetime of
+/// the app.
+#[cfg(target_os = "linux")]
+pub fn watch_volumes(app: AppHandle) {
+    std::thread::spawn(move || {
+        let Ok(file) = std::fs::File::open(MOUNTINFO_PATH) else {
+            return;
+        };
+
+        let mount_points = |volumes: &[Volume]| {
+            volumes
+                .iter()
+                .map(|volume| (volume.mount_point.clone(), volume.device.clone()))
+                .collect::<Vec<_>>()
+        };
+
+        let mut known = read_volumes().unwrap_or_default();
+
+        loop {
+            wait_for_mount_change(&file);
+
+            let Ok(current) = read_volumes() else {
+                continue;
+            };
+
+            let (before, after) = (mount_points(&known), mount_points(&current));
+
+            for volume in &current {
+                if !before.contains(&(volume.mount_point.clone(), volume.device.clone())) {
+                    let _ = app.emit("volume-mounted", volume);
+                }
+            }
+
+            for volume in &known {
+                if !after.contains(&(volume.mount_point.clone(), volume.device.clone())) {
+                    let _ = app.emit("volume-unmounted", volume);
+                }
+            }
+
+            known = current;
+        }
+    });
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn watch_volumes(_app: AppHandle) {}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mountinfo_lines_are_parsed() {
+        let mount = parse_mountinfo_line(
+            r"36 35 98:0 / /mnt/my\040disk rw,noatime master:1 shared:7 - ext4 /dev/sda1 rw,errors=continue",
+        )
+        .unwrap();
+        assert_eq!(mount.mount_point, "/mnt/my disk");
+        assert_eq!(mount.device, "/dev/sda1");
+        assert_eq!(mount.filesystem, "ext4");
+        assert!(!mount.read_only);
+
+        let mount = parse_mountinfo_line(
+            r"41 22 0:38 / /mnt/share ro,relatime - cifs //server/team\040files rw,vers=3.1.1",
+        )
+        .unwrap();
+        assert_eq!(mount.device, "//server/team files");
+        assert_eq!(mount.filesystem, "cifs");
+        assert!(mount.read_only);
+
+        let mount =
+            parse_mountinfo_line("22 1 8:2 / / rw,relatime shared:1 - btrfs /dev/nvme0n1p2 ro")
+                .unwrap();
+        assert_eq!(mount.mount_point, "/");
+        assert!(!mount.read_only);
+
+        assert!(parse_mountinfo_line("22 1 8:2 / / rw,relatime shared:1").is_none());
+        assert!(parse_mountinfo_line("22 1 8:2 - ext4 /dev/sda2 rw").is_none());
+    }
+
+    #[test]
+    fn octal_escapes_are_decoded() {
+        assert_eq!(unescape_octal(r"/mnt/a\040b"), "/mnt/a b");
+        assert_eq!(unescape_octal(r"tab\011and\134slash"), "tab\tand\\slash");
+        assert_eq!(unescape_octal(r"\303\251t\303\251"), "été");
+        assert_eq!(unescape_octal(r"short\04"), r"short\04");
+        assert_eq!(unescape_octal(r"\999\777"), r"\999\777");
+    }
+
+    #[test]
+    fn hex_escapes_are_decoded() {
+        assert_eq!(unescape_hex(r"My\x20Disk"), "My Disk");
+        assert_eq!(unescape_hex(r"caf\xc3\xa9"), "café");
+        assert_eq!(unescape_hex(r"end\x2"), r"end\x2");
+        assert_eq!(unescape_hex(r"\xZZ\y20"), r"\xZZ\y20");
+    }
+
+    #[test]
+    fn only_user_mounts_are_shown() {
+        let home = Path::new("/home/me");
+        let visible =
+            |line: &str| is_user_visible(&parse_mountinfo_line(line).unwrap(), Some(home));
+
+        assert!(visible("30 1 8:1 / /mnt/data rw - ext4 /dev/sdb1 rw"));
+        assert!(visible(
+            "31 1 8:1 / /run/media/me/USB rw - vfat /dev/sdc1 rw"
+        ));
+        assert!(visible(
+            "32 1 0:50 / /mnt/scratch rw shared:9 - tmpfs tmpfs rw,size=1g"
+        ));
+        assert!(visible("33 1 0:51 / /home/me/ram rw - tmpfs tmpfs rw"));
+
+        assert!(!visible("40 1 0:20 / /tmp rw shared:2 - tmpfs tmpfs rw"));
+        assert!(!visible("41 1 0:21 / /run/user/1000 rw - tmpfs tmpfs rw"));
+        assert!(!visible("42 1 0:22 / /dev/shm rw - tmpfs tmpfs rw"));
+        assert!(!visible("43 1 0:23 / /proc rw - proc proc rw"));
+        assert!(!visible(
+            "44 1 7:0 / /snap/core/1 ro - squashfs /dev/loop0 ro"
+        ));
+        assert!(!visible("45 1 0:24 / /mnt rw - tmpfs tmpfs rw"));
+    }
+}
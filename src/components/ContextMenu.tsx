@@ -1,6 +1,12 @@
 import React, { useState, useRef, useEffect } from "react";
-import { FileEntry } from "../types";
-import { CopyIcon, EditIcon, TrashIcon, PlusIcon } from "./Icons";
+import { CustomAction, FileEntry } from "../types";
+import {
+	CopyIcon,
+	EditIcon,
+	TrashIcon,
+	PlusIcon,
+	MoreVerticalIcon,
+} from "./Icons";
 import { FileSystemAPI } from "../services/fileSystem";
 
 interface ContextMenuProps {
@@ -25,6 +31,17 @@ export const ContextMenu: React.FC<ContextMenuProps> = ({
 	const [isCreatingFolder, setIsCreatingFolder] = useState(false);
 	const [isRenaming, setIsRenaming] = useState(false);
 	const [newName, setNewName] = useState("");
+	const [customActions, setCustomActions] = useState<CustomAction[]>([]);
+
+	useEffect(() => {
+		if (!entry) return;
+
+		FileSystemAPI.actionsForPaths([entry.path])
+			.then(setCustomActions)
+			.catch((error) =>
+				console.error("Failed to load custom actions:", error)
+			);
+	}, [entry]);
 
 	useEffect(() => {
 		const handleClickOutside = (event: MouseEvent) => {
@@ -114,9 +131,12 @@ export const ContextMenu: React.FC<ContextMenuProps> = ({
 	const handleDelete = async () => {
 		if (!entry) return;
 
-		const confirmed = window.confirm(
-			`Are you sure you want to delete "${entry.name}"?`
+		const { confirm_delete } = await FileSystemAPI.getSettings().catch(
+			() => ({ confirm_delete: true })
 		);
+		const confirmed =
+			!confirm_delete ||
+			window.confirm(`Are you sure you want to delete "${entry.name}"?`);
 		if (!confirmed) return;
 
 		try {
@@ -127,6 +147,44 @@ export const ContextMenu: React.FC<ContextMenuProps> = ({
 		}
 	};
 
+	const handleOpenTerminal = async () => {
+		const directory = entry?.is_directory ? entry.path : currentDirectory;
+
+		try {
+			await FileSystemAPI.openTerminal(directory);
+		} catch (error) {
+			console.error("Failed to open terminal:", error);
+		}
+		onClose();
+	};
+
+	const handleCustomAction = async (action: CustomAction) => {
+		if (!entry) return;
+		onClose();
+
+		try {
+			const output = await FileSystemAPI.runCustomAction(action.id, [
+				entry.path,
+			]);
+			const text = [output.stdout, output.stderr]
+				.filter((part) => part.trim())
+				.join("\n");
+
+			if (!output.success) {
+				window.alert(
+					`"${action.name}" failed (exit status ${
+						output.exit_code ?? "unknown"
+					})${text ? `:\n\n${text}` : ""}`
+				);
+			} else if (text) {
+				window.alert(text);
+			}
+			onRefresh();
+		} catch (error) {
+			console.error("Failed to run custom action:", error);
+		}
+	};
+
 	const startRename = () => {
 		if (entry) {
 			setNewName(entry.name);
@@ -257,6 +315,11 @@ export const ContextMenu: React.FC<ContextMenuProps> = ({
 				label={entry?.is_directory ? "New Subdirectory" : "New Folder"}
 				onClick={startCreateFolder}
 			/>
+			<MenuItem
+				icon={<MoreVerticalIcon size={16} />}
+				label="Open Terminal Here"
+				onClick={handleOpenTerminal}
+			/>
 
 			{entry && (
 				<>
@@ -280,6 +343,24 @@ export const ContextMenu: React.FC<ContextMenuProps> = ({
 						}}
 					/>
 
+					{customActions.length > 0 && (
+						<>
+							<div
+								className="h-px mx-2 my-1"
+								style={{ backgroundColor: "var(--color-border)" }}
+							/>
+
+							{customActions.map((action) => (
+								<MenuItem
+									key={action.id}
+									icon={<MoreVerticalIcon size={16} />}
+									label={action.name}
+									onClick={() => handleCustomAction(action)}
+								/>
+							))}
+						</>
+					)}
+
 					<div
 						className="h-px mx-2 my-1"
 						style={{ backgroundColor: "var(--color-border)" }}
@@ -1,5 +1,6 @@
-import React from "react";
-import { FileEntry } from "../types";
+import React, { useEffect, useState } from "react";
+import { FileEntry, MediaMetadata } from "../types";
+import { FileSystemAPI } from "../services/fileSystem";
 import { FolderIcon, FileIcon } from "./Icons";
 
 interface FileDetailsProps {
@@ -36,6 +37,58 @@ const formatDate = (timestamp: string): string => {
 	}
 };
 
+const formatMode = (mode: number): string => {
+	const symbols = ["r", "w", "x"];
+	let result = "";
+
+	for (let shift = 8; shift >= 0; shift--) {
+		result += mode & (1 << shift) ? symbols[(8 - shift) % 3] : "-";
+	}
+
+	return result;
+};
+
+const formatDuration = (seconds: number): string => {
+	const total = Math.round(seconds);
+	const minutes = Math.floor(total / 60);
+	return `${minutes}:${String(total % 60).padStart(2, "0")}`;
+};
+
+const describeMedia = (metadata: MediaMetadata): [string, string][] => {
+	const rows: [string, string][] = [];
+	const { image, audio } = metadata;
+
+	if (image) {
+		if (image.width && image.height) {
+			rows.push(["Dimensions", `${image.width} × ${image.height}`]);
+		}
+		const camera = [image.camera_make, image.camera_model]
+			.filter(Boolean)
+			.join(" ");
+		if (camera) rows.push(["Camera", camera]);
+		if (image.date_taken) {
+			rows.push(["Taken", new Date(image.date_taken).toLocaleString()]);
+		}
+		if (image.gps) {
+			rows.push([
+				"Location",
+				`${image.gps.latitude.toFixed(5)}, ${image.gps.longitude.toFixed(5)}`,
+			]);
+		}
+	}
+
+	if (audio) {
+		const track = [audio.artist, audio.title].filter(Boolean).join(" – ");
+		if (track) rows.push(["Track", track]);
+		if (audio.album) rows.push(["Album", audio.album]);
+		if (audio.duration != null) {
+			rows.push(["Duration", formatDuration(audio.duration)]);
+		}
+	}
+
+	return rows;
+};
+
 const getFileExtension = (filename: string): string => {
 	const lastDot = filename.lastIndexOf(".");
 	if (lastDot === -1 || lastDot === 0) return "";
@@ -43,6 +96,30 @@ const getFileExtension = (filename: string): string => {
 };
 
 export const FileDetails: React.FC<FileDetailsProps> = ({ selectedEntry }) => {
+	const [media, setMedia] = useState<MediaMetadata | null>(null);
+
+	useEffect(() => {
+		setMedia(null);
+
+		if (
+			selectedEntry?.category !== "image" &&
+			selectedEntry?.category !== "audio"
+		) {
+			return;
+		}
+
+		let cancelled = false;
+		FileSystemAPI.getMediaMetadata(selectedEntry.path)
+			.then((metadata) => {
+				if (!cancelled) setMedia(metadata);
+			})
+			.catch(() => {});
+
+		return () => {
+			cancelled = true;
+		};
+	}, [selectedEntry?.path, selectedEntry?.category]);
+
 	if (!selectedEntry) {
 		return (
 			<div
@@ -208,6 +285,53 @@ export const FileDetails: React.FC<FileDetailsProps> = ({ selectedEntry }) => {
 										</div>
 									</div>
 								)}
+
+								{selectedEntry.mode !== undefined && (
+									<div>
+										<div
+											className="text-xs font-medium"
+											style={{
+												color: "var(--color-textSecondary)",
+											}}
+										>
+											Permissions
+										</div>
+										<div
+											className="text-sm font-mono"
+											style={{
+												color: "var(--color-text)",
+											}}
+										>
+											{formatMode(selectedEntry.mode)}
+											{selectedEntry.owner &&
+												` ${selectedEntry.owner}:${
+													selectedEntry.group ?? ""
+												}`}
+										</div>
+									</div>
+								)}
+
+								{media &&
+									describeMedia(media).map(([label, value]) => (
+										<div key={label}>
+											<div
+												className="text-xs font-medium"
+												style={{
+													color: "var(--color-textSecondary)",
+												}}
+											>
+												{label}
+											</div>
+											<div
+												className="text-sm"
+												style={{
+													color: "var(--color-text)",
+												}}
+											>
+												{value}
+											</div>
+										</div>
+									))}
 							</div>
 						</div>
 					</div>
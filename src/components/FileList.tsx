@@ -1,11 +1,10 @@
 import React, {
 	useEffect,
 	useRef,
-	useState,
 	useMemo,
 	useCallback,
 } from "react";
-import { FileEntry } from "../types";
+import { FileEntry, SortBy, SortOrder, ViewMode } from "../types";
 import { FolderIcon, FileIcon } from "./Icons";
 
 const formatFileSize = (bytes: number): string => {
@@ -34,13 +33,26 @@ const formatDate = (timestamp: string): string => {
 	}
 };
 
+export type DropAction = "move" | "copy" | "symlink" | "hardlink";
+
+const DRAG_PATH_TYPE = "application/x-wiregui-path";
+
+const getDropAction = (event: React.DragEvent): DropAction => {
+	if (event.ctrlKey && event.shiftKey) return "symlink";
+	if (event.altKey) return "hardlink";
+	if (event.ctrlKey) return "copy";
+	return "move";
+};
+
 interface FileListItemProps {
 	entry: FileEntry;
 	onDoubleClick: (entry: FileEntry) => void;
 	onContextMenu: (entry: FileEntry, event: React.MouseEvent) => void;
 	isSelected: boolean;
 	onSelect: (entry: FileEntry) => void;
+	onDrop: (sourcePath: string, target: FileEntry, action: DropAction) => void;
 	showFullPath?: boolean;
+	variant?: ViewMode;
 }
 
 export const FileListItem: React.FC<FileListItemProps> = React.memo(
@@ -50,15 +62,19 @@ export const FileListItem: React.FC<FileListItemProps> = React.memo(
 		onContextMenu,
 		isSelected,
 		onSelect,
+		onDrop,
 		showFullPath = false,
+		variant = "list",
 	}) => {
 		const displayName = showFullPath ? entry.path : entry.name;
 		const itemRef = useRef<HTMLDivElement>(null);
+		const isGrid = variant === "grid";
+		const hoverTransform = isGrid ? "translateY(-2px)" : "translateX(4px)";
 
 		useEffect(() => {
 			if (itemRef.current) {
 				if (isSelected) {
-					itemRef.current.style.transform = "translateX(0)";
+					itemRef.current.style.transform = "none";
 					itemRef.current.style.boxShadow = "none";
 				}
 			}
@@ -67,7 +83,11 @@ export const FileListItem: React.FC<FileListItemProps> = React.memo(
 		return (
 			<div
 				ref={itemRef}
-				className="flex items-center gap-4 px-4 py-3 cursor-pointer select-none transition-all duration-200"
+				className={
+					isGrid
+						? "flex flex-col items-center gap-2 p-3 cursor-pointer select-none transition-all duration-200"
+						: "flex items-center gap-4 px-4 py-3 cursor-pointer select-none transition-all duration-200"
+				}
 				style={{
 					backgroundColor: isSelected
 						? "var(--color-primary)"
@@ -77,6 +97,40 @@ export const FileListItem: React.FC<FileListItemProps> = React.memo(
 					marginBottom: "2px",
 				}}
 				data-entry-item="true"
+				data-entry-name={entry.name}
+				draggable
+				onDragStart={(e) => {
+					e.dataTransfer.setData(DRAG_PATH_TYPE, entry.path);
+					e.dataTransfer.effectAllowed = "all";
+				}}
+				onDragOver={(e) => {
+					if (
+						!entry.is_directory ||
+						!e.dataTransfer.types.includes(DRAG_PATH_TYPE)
+					) {
+						return;
+					}
+
+					e.preventDefault();
+					const action = getDropAction(e);
+					e.dataTransfer.dropEffect =
+						action === "symlink" || action === "hardlink"
+							? "link"
+							: action;
+				}}
+				onDrop={(e) => {
+					const sourcePath = e.dataTransfer.getData(DRAG_PATH_TYPE);
+					if (
+						!entry.is_directory ||
+						!sourcePath ||
+						sourcePath === entry.path
+					) {
+						return;
+					}
+
+					e.preventDefault();
+					onDrop(sourcePath, entry, getDropAction(e));
+				}}
 				onDoubleClick={() => onDoubleClick(entry)}
 				onContextMenu={(e) => onContextMenu(entry, e)}
 				onClick={() => onSelect(entry)}
@@ -84,14 +138,14 @@ export const FileListItem: React.FC<FileListItemProps> = React.memo(
 					if (!isSelected) {
 						e.currentTarget.style.backgroundColor =
 							"var(--color-surfaceHover)";
-						e.currentTarget.style.transform = "translateX(4px)";
+						e.currentTarget.style.transform = hoverTransform;
 						e.currentTarget.style.boxShadow = "var(--shadow-sm)";
 					}
 				}}
 				onMouseLeave={(e) => {
 					if (!isSelected) {
 						e.currentTarget.style.backgroundColor = "transparent";
-						e.currentTarget.style.transform = "translateX(0)";
+						e.currentTarget.style.transform = "none";
 						e.currentTarget.style.boxShadow = "none";
 					}
 				}}
@@ -107,7 +161,7 @@ export const FileListItem: React.FC<FileListItemProps> = React.memo(
 								color: isSelected ? "white" : "white",
 							}}
 						>
-							<FolderIcon size={20} />
+							<FolderIcon size={isGrid ? 40 : 20} />
 						</div>
 					) : (
 						<div
@@ -121,48 +175,57 @@ export const FileListItem: React.FC<FileListItemProps> = React.memo(
 									: "var(--color-textSecondary)",
 							}}
 						>
-							<FileIcon size={20} />
+							<FileIcon size={isGrid ? 40 : 20} />
 						</div>
 					)}
 				</div>
 
-				<div className="flex-1 min-w-0">
+				<div className={isGrid ? "w-full min-w-0" : "flex-1 min-w-0"}>
 					<div
-						className="font-medium truncate"
+						className={
+							isGrid
+								? "font-medium truncate text-center"
+								: "font-medium truncate"
+						}
 						style={{
 							fontSize: "var(--font-size-sm)",
 							fontWeight: "var(--font-weight-medium)",
 						}}
+						title={isGrid ? displayName : undefined}
 					>
 						{displayName}
 					</div>
 				</div>
 
-				<div
-					className="flex-shrink-0 w-20 text-right"
-					style={{
-						color: isSelected
-							? "rgba(255,255,255,0.8)"
-							: "var(--color-textSecondary)",
-						fontSize: "var(--font-size-xs)",
-					}}
-				>
-					{!entry.is_directory && entry.size
-						? formatFileSize(entry.size)
-						: ""}
-				</div>
+				{!isGrid && (
+					<>
+						<div
+							className="flex-shrink-0 w-20 text-right"
+							style={{
+								color: isSelected
+									? "rgba(255,255,255,0.8)"
+									: "var(--color-textSecondary)",
+								fontSize: "var(--font-size-xs)",
+							}}
+						>
+							{!entry.is_directory && entry.size
+								? formatFileSize(entry.size)
+								: ""}
+						</div>
 
-				<div
-					className="flex-shrink-0 w-32 text-right"
-					style={{
-						color: isSelected
-							? "rgba(255,255,255,0.8)"
-							: "var(--color-textSecondary)",
-						fontSize: "var(--font-size-xs)",
-					}}
-				>
-					{entry.modified ? formatDate(entry.modified) : ""}
-				</div>
+						<div
+							className="flex-shrink-0 w-32 text-right"
+							style={{
+								color: isSelected
+									? "rgba(255,255,255,0.8)"
+									: "var(--color-textSecondary)",
+								fontSize: "var(--font-size-xs)",
+							}}
+						>
+							{entry.modified ? formatDate(entry.modified) : ""}
+						</div>
+					</>
+				)}
 			</div>
 		);
 	},
@@ -170,7 +233,8 @@ export const FileListItem: React.FC<FileListItemProps> = React.memo(
 		return (
 			prevProps.entry.path === nextProps.entry.path &&
 			prevProps.isSelected === nextProps.isSelected &&
-			prevProps.showFullPath === nextProps.showFullPath
+			prevProps.showFullPath === nextProps.showFullPath &&
+			prevProps.variant === nextProps.variant
 		);
 	}
 );
@@ -180,21 +244,106 @@ interface FileListProps {
 	onEntryDoubleClick: (entry: FileEntry) => void;
 	onEntryContextMenu: (entry: FileEntry, event: React.MouseEvent) => void;
 	onEmptySpaceContextMenu?: (event: React.MouseEvent) => void;
+	onEntryDrop?: (
+		sourcePath: string,
+		target: FileEntry,
+		action: DropAction
+	) => void;
 	selectedEntries: FileEntry[];
 	onSelectionChange: (entries: FileEntry[]) => void;
 	showFullPaths?: boolean;
+	sort: SortOrder;
+	onSortChange: (sort: SortOrder) => void;
+	viewMode: ViewMode;
+	/** Entry scrolled to the top when the list is first shown. */
+	scrollAnchor?: string;
+	onScrollAnchorChange?: (anchor?: string) => void;
 }
 
+const extensionOf = (name: string): string => {
+	const index = name.lastIndexOf(".");
+	return index > 0 ? name.slice(index + 1).toLowerCase() : "";
+};
+
+const compareEntries = (a: FileEntry, b: FileEntry, key: SortBy): number => {
+	switch (key) {
+		case "size":
+			return (a.size || 0) - (b.size || 0);
+		case "modified":
+			return parseInt(a.modified || "0") - parseInt(b.modified || "0");
+		case "type":
+			return extensionOf(a.name).localeCompare(extensionOf(b.name));
+		default:
+			return 0;
+	}
+};
+
 export const FileList: React.FC<FileListProps> = ({
 	entries,
 	onEntryDoubleClick,
 	onEntryContextMenu,
 	onEmptySpaceContextMenu,
+	onEntryDrop,
 	selectedEntries,
 	onSelectionChange,
 	showFullPaths = false,
+	sort,
+	onSortChange,
+	viewMode,
+	scrollAnchor,
+	onScrollAnchorChange,
 }) => {
-	const [sortMode, setSortMode] = useState("name");
+	const onEntryDropRef = useRef(onEntryDrop);
+	onEntryDropRef.current = onEntryDrop;
+	const scrollRef = useRef<HTMLDivElement>(null);
+	const scrollTimeout = useRef<number>();
+
+	const entryElements = () =>
+		Array.from(
+			scrollRef.current?.querySelectorAll<HTMLElement>(
+				"[data-entry-name]"
+			) ?? []
+		);
+
+	useEffect(() => {
+		const container = scrollRef.current;
+		const anchor = entryElements().find(
+			(element) => element.dataset.entryName === scrollAnchor
+		);
+		if (!container || !anchor) return;
+
+		container.scrollTop +=
+			anchor.getBoundingClientRect().top -
+			container.getBoundingClientRect().top;
+	}, []);
+
+	useEffect(() => () => window.clearTimeout(scrollTimeout.current), []);
+
+	const handleScroll = () => {
+		window.clearTimeout(scrollTimeout.current);
+		scrollTimeout.current = window.setTimeout(() => {
+			const container = scrollRef.current;
+			if (!container) return;
+
+			const top = container.getBoundingClientRect().top;
+			const anchor =
+				container.scrollTop > 0
+					? entryElements().find(
+							(element) =>
+								element.getBoundingClientRect().bottom > top
+					  )
+					: undefined;
+
+			onScrollAnchorChange?.(anchor?.dataset.entryName);
+		}, 300);
+	};
+
+	const handleDrop = useCallback(
+		(sourcePath: string, target: FileEntry, action: DropAction) => {
+			onEntryDropRef.current?.(sourcePath, target, action);
+		},
+		[]
+	);
 
 	const isSelected = useCallback(
 		(entry: FileEntry) => {
@@ -217,21 +366,21 @@ export const FileList: React.FC<FileListProps> = ({
 			if (a.is_directory && !b.is_directory) return -1;
 			if (!a.is_directory && b.is_directory) return 1;
 
-			switch (sortMode) {
-				case "name":
-					return a.name.localeCompare(b.name);
-				case "size":
-					return (a.size || 0) - (b.size || 0);
-				case "modified":
-					return (
-						parseInt(a.modified || "0") -
-						parseInt(b.modified || "0")
-					);
-				default:
-					return a.name.localeCompare(b.name);
-			}
+			const order =
+				compareEntries(a, b, sort.key) || a.name.localeCompare(b.name);
+			return sort.descending ? -order : order;
+		});
+	}, [entries, sort]);
+
+	const sortBy = (key: SortBy) => {
+		onSortChange({
+			key,
+			descending: sort.key === key ? !sort.descending : false,
 		});
-	}, [entries, sortMode]);
+	};
+
+	const sortIndicator = (key: SortBy) =>
+		sort.key === key ? (sort.descending ? " ▼" : " ▲") : "";
 
 	return (
 		<div
@@ -256,38 +405,40 @@ export const FileList: React.FC<FileListProps> = ({
 				}}
 			>
 				<div className="flex-shrink-0 w-12"></div>
-				<div className="flex-1" onClick={() => setSortMode("name")}>
+				<div className="flex-1">
 					<span
 						className="hover:cursor-pointer"
-						onClick={() => setSortMode("name")}
+						onClick={() => sortBy("name")}
 					>
-						Name
+						Name{sortIndicator("name")}
 					</span>
 				</div>
 				<div className="flex-shrink-0 w-20 text-right">
 					<span
 						className="hover:cursor-pointer"
-						onClick={() => setSortMode("size")}
+						onClick={() => sortBy("size")}
 					>
-						Size
+						Size{sortIndicator("size")}
 					</span>
 				</div>
 				<div className="flex-shrink-0 w-32 text-right">
 					<span
 						className="hover:cursor-pointer"
-						onClick={() => setSortMode("modified")}
+						onClick={() => sortBy("modified")}
 					>
-						Modified
+						Modified{sortIndicator("modified")}
 					</span>
 				</div>
 			</div>
 
 			<div
+				ref={scrollRef}
 				className="p-3 flex-1 overflow-y-auto"
 				style={{
 					overflowX: "hidden",
 					backgroundColor: "var(--color-surface)",
 				}}
+				onScroll={handleScroll}
 				onContextMenu={(e) => {
 					const target = e.target as HTMLElement;
 					const isClickingOnEntry =
@@ -299,17 +450,35 @@ export const FileList: React.FC<FileListProps> = ({
 					}
 				}}
 			>
-				{sortedEntries.map((entry) => (
-					<FileListItem
-						key={entry.path}
-						entry={entry}
-						onDoubleClick={onEntryDoubleClick}
-						onContextMenu={onEntryContextMenu}
-						isSelected={isSelected(entry)}
-						onSelect={handleSelect}
-						showFullPath={showFullPaths}
-					/>
-				))}
+				<div
+					className={
+						viewMode === "grid"
+							? "grid gap-2"
+							: undefined
+					}
+					style={
+						viewMode === "grid"
+							? {
+									gridTemplateColumns:
+										"repeat(auto-fill, minmax(112px, 1fr))",
+							  }
+							: undefined
+					}
+				>
+					{sortedEntries.map((entry) => (
+						<FileListItem
+							key={entry.path}
+							entry={entry}
+							onDoubleClick={onEntryDoubleClick}
+							onContextMenu={onEntryContextMenu}
+							isSelected={isSelected(entry)}
+							onSelect={handleSelect}
+							onDrop={handleDrop}
+							showFullPath={showFullPaths}
+							variant={viewMode}
+						/>
+					))}
+				</div>
 
 				{entries.length === 0 && (
 					<div
@@ -1,12 +1,44 @@
 import React, { useState, useEffect, useCallback, useRef } from "react";
 import { FileSystemAPI } from "../services/fileSystem";
-import { FileEntry, DirectoryContents } from "../types";
+import {
+	FileEntry,
+	DirectoryContents,
+	DirectoryViewState,
+	Settings,
+	SortOrder,
+	ViewMode,
+} from "../types";
 import { Toolbar } from "./Toolbar";
-import { FileList } from "./FileList";
+import { DropAction, FileList } from "./FileList";
 import { FileDetails } from "./FileDetails";
 import { ContextMenu } from "./ContextMenu";
 import { ThemeSwitcher } from "./ThemeSwitcher";
-import { SearchIcon, PaletteIcon, EyeIcon, EyeOffIcon } from "./Icons";
+import {
+	SearchIcon,
+	PaletteIcon,
+	EyeIcon,
+	EyeOffIcon,
+	GridIcon,
+	ListIcon,
+} from "./Icons";
+
+const BROWSABLE_ARCHIVE_EXTENSIONS = [
+	".zip",
+	".tar",
+	".tar.gz",
+	".tgz",
+	".tar.xz",
+	".txz",
+	".tar.zst",
+	".tzst",
+];
+
+const isBrowsableArchive = (name: string): boolean => {
+	const lower = name.toLowerCase();
+	return BROWSABLE_ARCHIVE_EXTENSIONS.some((extension) =>
+		lower.endsWith(extension)
+	);
+};
 
 export const FileManager: React.FC = () => {
 	const [currentDirectory, setCurrentDirectory] =
@@ -25,6 +57,13 @@ export const FileManager: React.FC = () => {
 		null
 	);
 	const [showHiddenFiles, setShowHiddenFiles] = useState(false);
+	const [defaultView, setDefaultView] = useState<{
+		view_mode: ViewMode;
+		sort: SortOrder;
+	}>({ view_mode: "list", sort: { key: "name", descending: false } });
+	const [directoryStates, setDirectoryStates] = useState<
+		DirectoryViewState[]
+	>([]);
 	const [contextMenu, setContextMenu] = useState<{
 		x: number;
 		y: number;
@@ -35,6 +74,14 @@ export const FileManager: React.FC = () => {
 	} | null>(null);
 	const [isThemeSwitcherOpen, setIsThemeSwitcherOpen] = useState(false);
 	const themeSwitcherButtonRef = useRef<HTMLButtonElement>(null!);
+	const sessionLoaded = useRef(false);
+
+	const currentPath = currentDirectory?.current_path;
+	const directoryState = directoryStates.find(
+		(state) => state.path === currentPath
+	);
+	const viewMode = directoryState?.view_mode ?? defaultView.view_mode;
+	const sortOrder = directoryState?.sort ?? defaultView.sort;
 
 	useEffect(() => {
 		const unsubscribe = FileSystemAPI.onItemDeleted((_deletedPath) => {
@@ -44,6 +91,67 @@ export const FileManager: React.FC = () => {
 		return unsubscribe;
 	}, []);
 
+	useEffect(() => {
+		const applySettings = (settings: Settings) => {
+			setShowHiddenFiles(settings.show_hidden);
+			setDefaultView({
+				view_mode: settings.view_mode,
+				sort: settings.default_sort,
+			});
+		};
+
+		FileSystemAPI.getSettings()
+			.then(applySettings)
+			.catch((err) => console.error("Failed to load settings:", err));
+
+		const unlisten = FileSystemAPI.onSettingsChanged(applySettings);
+
+		return () => {
+			unlisten.then((fn) => fn());
+		};
+	}, []);
+
+	const toggleHiddenFiles = () => {
+		const showHidden = !showHiddenFiles;
+		setShowHiddenFiles(showHidden);
+		FileSystemAPI.updateSettings({ show_hidden: showHidden }).catch(
+			(err) => console.error("Failed to save settings:", err)
+		);
+	};
+
+	// Directories without a saved state follow the defaults from the
+	// settings; changing the view of one only affects that directory.
+	const updateDirectoryState = (
+		changes: Partial<Omit<DirectoryViewState, "path">>
+	) => {
+		if (!currentPath) return;
+
+		setDirectoryStates((states) => [
+			{
+				path: currentPath,
+				sort: sortOrder,
+				view_mode: viewMode,
+				...states.find((state) => state.path === currentPath),
+				...changes,
+			},
+			...states.filter((state) => state.path !== currentPath),
+		]);
+	};
+
+	const toggleViewMode = () => {
+		updateDirectoryState({
+			view_mode: viewMode === "grid" ? "list" : "grid",
+		});
+	};
+
+	const handleSortChange = (sort: SortOrder) => {
+		updateDirectoryState({ sort });
+	};
+
+	const handleScrollAnchorChange = (anchor?: string) => {
+		updateDirectoryState({ scroll_anchor: anchor });
+	};
+
 	const handleSelectionChange = (entries: FileEntry[]) => {
 		setSelectedEntries(entries);
 		setSelectedForDetails(entries.length > 0 ? entries[0] : null);
@@ -138,9 +246,19 @@ export const FileManager: React.FC = () => {
 
 		const initializeHome = async () => {
 			try {
-				const homePath = await FileSystemAPI.getHomeDirectory();
+				const session = await FileSystemAPI.getSession().catch(
+					() => null
+				);
+				if (session) {
+					setDirectoryStates(session.directories);
+				}
+				sessionLoaded.current = true;
+
+				const startPath = session
+					? session.tabs[session.active_tab].path
+					: await FileSystemAPI.getHomeDirectory();
 				if (mounted) {
-					await loadDirectory(homePath);
+					await loadDirectory(startPath);
 				}
 			} catch (err) {
 				if (mounted) {
@@ -160,6 +278,16 @@ export const FileManager: React.FC = () => {
 		};
 	}, []);
 
+	useEffect(() => {
+		if (!sessionLoaded.current || !currentPath) return;
+
+		FileSystemAPI.saveSession({
+			tabs: [{ path: currentPath }],
+			active_tab: 0,
+			directories: directoryStates,
+		}).catch((err) => console.error("Failed to save session:", err));
+	}, [currentPath, directoryStates]);
+
 	const handleBack = () => {
 		if (historyIndex > 0) {
 			const previousPath = history[historyIndex - 1];
@@ -193,9 +321,51 @@ export const FileManager: React.FC = () => {
 		}
 	};
 
+	const handleEntryDrop = async (
+		sourcePath: string,
+		target: FileEntry,
+		action: DropAction
+	) => {
+		const name = sourcePath.split("/").pop() || sourcePath;
+		const linkPath = `${target.path}/${name}`;
+
+		try {
+			switch (action) {
+				case "symlink":
+					await FileSystemAPI.createSymlink(sourcePath, linkPath, true);
+					break;
+				case "hardlink":
+					await FileSystemAPI.createHardlink(sourcePath, linkPath);
+					break;
+				default:
+					await FileSystemAPI.submitJob({
+						type: action,
+						sources: [sourcePath],
+						destination: target.path,
+					});
+					break;
+			}
+			handleRefresh();
+		} catch (err) {
+			setError(
+				typeof err === "string"
+					? err
+					: err instanceof Error
+					? err.message
+					: "Failed to drop item"
+			);
+		}
+	};
+
 	const handleEntryDoubleClick = (entry: FileEntry) => {
 		if (entry.is_directory) {
 			loadDirectory(entry.path);
+		} else if (isBrowsableArchive(entry.name)) {
+			loadDirectory(`${entry.path}!`);
+		} else {
+			FileSystemAPI.openFile(entry.path).catch((err) =>
+				setError(typeof err === "string" ? err : "Failed to open file")
+			);
 		}
 	};
 
@@ -436,10 +606,27 @@ export const FileManager: React.FC = () => {
 							border: "1px solid var(--color-border)",
 							borderRadius: "var(--border-radius-xl)",
 						}}
-						onClick={() => setShowHiddenFiles(!showHiddenFiles)}
+						onClick={toggleHiddenFiles}
 					>
 						{showHiddenFiles ? <EyeIcon /> : <EyeOffIcon />}
 					</button>
+					<button
+						className="p-3 rounded-xl transition-all duration-200"
+						style={{
+							color: "var(--color-text)",
+							backgroundColor: "var(--color-backgroundSecondary)",
+							border: "1px solid var(--color-border)",
+							borderRadius: "var(--border-radius-xl)",
+						}}
+						title={
+							viewMode === "grid"
+								? "Show as list"
+								: "Show as grid"
+						}
+						onClick={toggleViewMode}
+					>
+						{viewMode === "grid" ? <ListIcon /> : <GridIcon />}
+					</button>
 					<button
 						ref={themeSwitcherButtonRef}
 						onClick={() =>
@@ -518,6 +705,7 @@ export const FileManager: React.FC = () => {
 						)}
 
 						<FileList
+							key={currentPath}
 							entries={
 								showHiddenFiles
 									? displayedEntries
@@ -530,9 +718,19 @@ export const FileManager: React.FC = () => {
 							onEmptySpaceContextMenu={
 								handleEmptySpaceContextMenu
 							}
+							onEntryDrop={handleEntryDrop}
 							selectedEntries={selectedEntries}
 							onSelectionChange={handleSelectionChange}
 							showFullPaths={searchQuery.trim().length > 0}
+							sort={sortOrder}
+							onSortChange={handleSortChange}
+							viewMode={viewMode}
+							scrollAnchor={directoryState?.scroll_anchor}
+							onScrollAnchorChange={
+								searchQuery.trim().length > 0
+									? undefined
+									: handleScrollAnchorChange
+							}
 						/>
 					</div>
 				)}
@@ -1,6 +1,46 @@
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
-import { DirectoryContents, FileEntry } from "../types";
+import {
+	ActionOutput,
+	ApplicationChoices,
+	ArchiveFormat,
+	Bookmark,
+	ChecksumAlgorithm,
+	ChecksumProgress,
+	Checksums,
+	ChecksumVerification,
+	CompareMode,
+	CompareProgress,
+	ConflictPolicy,
+	CustomAction,
+	DirectoryComparison,
+	DirectoryContents,
+	DiskUsageNode,
+	DiskUsageProgress,
+	DuplicateGroup,
+	DuplicateProgress,
+	FileEntry,
+	FilePreview,
+	FileTypeInfo,
+	HexPage,
+	JobInfo,
+	JobRequest,
+	MediaMetadata,
+	OperationHistory,
+	OperationRecord,
+	PathFailure,
+	RecentFile,
+	RenamePreview,
+	RenameSpec,
+	Session,
+	Settings,
+	StandardPlace,
+	SyncPlan,
+	TemplateEntry,
+	Thumbnail,
+	ThumbnailSize,
+	Volume,
+} from "../types";
 
 export class FileSystemAPI {
 	private static deleteListeners: ((path: string) => void)[] = [];
@@ -21,6 +61,135 @@ export class FileSystemAPI {
 		return await invoke<string>("get_home_directory");
 	}
 
+	static async getSettings(): Promise<Settings> {
+		return await invoke<Settings>("get_settings");
+	}
+
+	static async updateSettings(
+		changes: Partial<Omit<Settings, "version">>
+	): Promise<Settings> {
+		return await invoke<Settings>("update_settings", { changes });
+	}
+
+	static async onSettingsChanged(
+		callback: (settings: Settings) => void
+	): Promise<() => void> {
+		return await listen<Settings>("settings-changed", (event) =>
+			callback(event.payload)
+		);
+	}
+
+	static async getSession(): Promise<Session> {
+		return await invoke<Session>("get_session");
+	}
+
+	static async saveSession(session: Session): Promise<void> {
+		return await invoke<void>("save_session", { session });
+	}
+
+	static async getStandardPlaces(): Promise<StandardPlace[]> {
+		return await invoke<StandardPlace[]>("get_standard_places");
+	}
+
+	static async listBookmarks(): Promise<Bookmark[]> {
+		return await invoke<Bookmark[]>("list_bookmarks");
+	}
+
+	static async addBookmark(path: string, name?: string): Promise<Bookmark[]> {
+		return await invoke<Bookmark[]>("add_bookmark", { path, name });
+	}
+
+	static async removeBookmark(path: string): Promise<Bookmark[]> {
+		return await invoke<Bookmark[]>("remove_bookmark", { path });
+	}
+
+	static async reorderBookmarks(paths: string[]): Promise<Bookmark[]> {
+		return await invoke<Bookmark[]>("reorder_bookmarks", { paths });
+	}
+
+	static async setGtkBookmarksSync(enabled: boolean): Promise<Bookmark[]> {
+		return await invoke<Bookmark[]>("set_gtk_bookmarks_sync", { enabled });
+	}
+
+	static async openFile(path: string): Promise<void> {
+		return await invoke<void>("open_file", { path });
+	}
+
+	static async listRecentFiles(limit?: number): Promise<RecentFile[]> {
+		return await invoke<RecentFile[]>("list_recent_files", { limit });
+	}
+
+	static async clearRecent(): Promise<void> {
+		return await invoke<void>("clear_recent");
+	}
+
+	static async listApplicationsFor(path: string): Promise<ApplicationChoices> {
+		return await invoke<ApplicationChoices>("list_applications_for", { path });
+	}
+
+	static async openWith(path: string, desktopId: string): Promise<void> {
+		return await invoke<void>("open_with", { path, desktopId });
+	}
+
+	static async setDefaultApplication(
+		mimeType: string,
+		desktopId: string
+	): Promise<void> {
+		return await invoke<void>("set_default_application", {
+			mimeType,
+			desktopId,
+		});
+	}
+
+	static async openTerminal(path: string): Promise<void> {
+		return await invoke<void>("open_terminal", { path });
+	}
+
+	static async listCustomActions(): Promise<CustomAction[]> {
+		return await invoke<CustomAction[]>("list_custom_actions");
+	}
+
+	static async saveCustomAction(
+		action: CustomAction
+	): Promise<CustomAction[]> {
+		return await invoke<CustomAction[]>("save_custom_action", { action });
+	}
+
+	static async deleteCustomAction(id: string): Promise<CustomAction[]> {
+		return await invoke<CustomAction[]>("delete_custom_action", { id });
+	}
+
+	static async actionsForPaths(paths: string[]): Promise<CustomAction[]> {
+		return await invoke<CustomAction[]>("actions_for_paths", { paths });
+	}
+
+	static async runCustomAction(
+		id: string,
+		paths: string[]
+	): Promise<ActionOutput> {
+		return await invoke<ActionOutput>("run_custom_action", { id, paths });
+	}
+
+	static async listVolumes(): Promise<Volume[]> {
+		return await invoke<Volume[]>("list_volumes");
+	}
+
+	static async onVolumeMounted(
+		callback: (volume: Volume) => void
+	): Promise<() => void> {
+		return await listen<Volume>("volume-mounted", (event) =>
+			callback(event.payload)
+		);
+	}
+
+	static async onVolumeUnmounted(
+		callback: (volume: Volume) => void
+	): Promise<() => void> {
+		return await listen<Volume>("volume-unmounted", (event) =>
+			callback(event.payload)
+		);
+	}
+
 	static async searchFiles(
 		path: string,
 		query: string,
@@ -71,14 +240,44 @@ export class FileSystemAPI {
 		};
 	}
 
-	static async createFile(path: string): Promise<void> {
-		return await invoke<void>("create_file", { path });
+	static async createFile(
+		path: string,
+		options?: { content?: string; template?: string }
+	): Promise<void> {
+		return await invoke<void>("create_file", {
+			path,
+			content: options?.content,
+			template: options?.template,
+		});
+	}
+
+	static async listTemplates(): Promise<TemplateEntry[]> {
+		return await invoke<TemplateEntry[]>("list_templates");
 	}
 
 	static async createDirectory(path: string): Promise<void> {
 		return await invoke<void>("create_directory", { path });
 	}
 
+	static async createSymlink(
+		target: string,
+		linkPath: string,
+		relative: boolean
+	): Promise<void> {
+		return await invoke<void>("create_symlink", {
+			target,
+			linkPath,
+			relative,
+		});
+	}
+
+	static async createHardlink(
+		target: string,
+		linkPath: string
+	): Promise<void> {
+		return await invoke<void>("create_hardlink", { target, linkPath });
+	}
+
 	static async renameItem(oldPath: string, newName: string): Promise<void> {
 		return await invoke<void>("rename_item", {
 			old_path: oldPath,
@@ -86,11 +285,261 @@ export class FileSystemAPI {
 		});
 	}
 
+	static async batchRename(
+		paths: string[],
+		spec: RenameSpec,
+		dryRun: boolean
+	): Promise<RenamePreview[]> {
+		return await invoke<RenamePreview[]>("batch_rename", {
+			paths,
+			spec,
+			dryRun,
+		});
+	}
+
+	static async setPermissions(
+		paths: string[],
+		mode: string,
+		recursive: boolean
+	): Promise<PathFailure[]> {
+		return await invoke<PathFailure[]>("set_permissions", {
+			paths,
+			mode,
+			recursive,
+		});
+	}
+
+	static async setOwner(
+		paths: string[],
+		user: string | null,
+		group: string | null,
+		recursive: boolean
+	): Promise<PathFailure[]> {
+		return await invoke<PathFailure[]>("set_owner", {
+			paths,
+			user,
+			group,
+			recursive,
+		});
+	}
+
 	static async deleteItem(path: string): Promise<void> {
 		await invoke<void>("delete_item", { path });
 		this.deleteListeners.forEach((listener) => listener(path));
 	}
 
+	static async readFilePreview(
+		path: string,
+		maxBytes?: number
+	): Promise<FilePreview> {
+		return await invoke<FilePreview>("read_file_preview", {
+			path,
+			maxBytes,
+		});
+	}
+
+	static async readFileHex(
+		path: string,
+		offset: number,
+		length: number
+	): Promise<HexPage> {
+		return await invoke<HexPage>("read_file_hex", {
+			path,
+			offset,
+			length,
+		});
+	}
+
+	static async getThumbnail(
+		path: string,
+		size?: ThumbnailSize
+	): Promise<Thumbnail> {
+		return await invoke<Thumbnail>("get_thumbnail", { path, size });
+	}
+
+	static async getMediaMetadata(path: string): Promise<MediaMetadata> {
+		return await invoke<MediaMetadata>("get_media_metadata", { path });
+	}
+
+	static async computeChecksums(
+		path: string,
+		algorithms: ChecksumAlgorithm[]
+	): Promise<Checksums> {
+		return await invoke<Checksums>("compute_checksums", {
+			path,
+			algorithms,
+		});
+	}
+
+	static async verifyChecksumFile(
+		path: string
+	): Promise<ChecksumVerification> {
+		return await invoke<ChecksumVerification>("verify_checksum_file", {
+			path,
+		});
+	}
+
+	static async onChecksumProgress(
+		callback: (progress: ChecksumProgress) => void
+	): Promise<() => void> {
+		return await listen<ChecksumProgress>("checksum-progress", (event) =>
+			callback(event.payload)
+		);
+	}
+
+	static async analyzeDiskUsage(
+		path: string,
+		options?: { depth?: number; crossFilesystems?: boolean; refresh?: boolean }
+	): Promise<DiskUsageNode> {
+		return await invoke<DiskUsageNode>("analyze_disk_usage", {
+			path,
+			depth: options?.depth,
+			crossFilesystems: options?.crossFilesystems,
+			refresh: options?.refresh,
+		});
+	}
+
+	static async onDiskUsageProgress(
+		callback: (progress: DiskUsageProgress) => void
+	): Promise<() => void> {
+		return await listen<DiskUsageProgress>("disk-usage-progress", (event) =>
+			callback(event.payload)
+		);
+	}
+
+	static async findDuplicates(
+		roots: string[],
+		options?: { minSize?: number; ignoreHardLinks?: boolean }
+	): Promise<DuplicateGroup[]> {
+		return await invoke<DuplicateGroup[]>("find_duplicates", {
+			roots,
+			minSize: options?.minSize,
+			ignoreHardLinks: options?.ignoreHardLinks,
+		});
+	}
+
+	static async onDuplicateGroup(
+		callback: (group: DuplicateGroup) => void
+	): Promise<() => void> {
+		return await listen<DuplicateGroup>("duplicate-group", (event) =>
+			callback(event.payload)
+		);
+	}
+
+	static async onDuplicateProgress(
+		callback: (progress: DuplicateProgress) => void
+	): Promise<() => void> {
+		return await listen<DuplicateProgress>("duplicate-progress", (event) =>
+			callback(event.payload)
+		);
+	}
+
+	static async compareDirectories(
+		left: string,
+		right: string,
+		mode: CompareMode
+	): Promise<DirectoryComparison> {
+		return await invoke<DirectoryComparison>("compare_directories", {
+			left,
+			right,
+			mode,
+		});
+	}
+
+	static async onCompareProgress(
+		callback: (progress: CompareProgress) => void
+	): Promise<() => void> {
+		return await listen<CompareProgress>("compare-progress", (event) =>
+			callback(event.payload)
+		);
+	}
+
+	static async syncDirectories(plan: SyncPlan): Promise<string> {
+		return await invoke<string>("sync_directories", { plan });
+	}
+
+	static async detectFileTypes(paths: string[]): Promise<FileTypeInfo[]> {
+		return await invoke<FileTypeInfo[]>("detect_file_types", { paths });
+	}
+
+	static async undoLastOperation(): Promise<OperationRecord> {
+		return await invoke<OperationRecord>("undo_last_operation");
+	}
+
+	static async redoOperation(): Promise<OperationRecord> {
+		return await invoke<OperationRecord>("redo_operation");
+	}
+
+	static async listOperations(): Promise<OperationHistory> {
+		return await invoke<OperationHistory>("list_operations");
+	}
+
+	static async submitJob(request: JobRequest): Promise<string> {
+		return await invoke<string>("submit_job", { request });
+	}
+
+	static async createArchive(
+		paths: string[],
+		destination: string,
+		format: ArchiveFormat,
+		level?: number
+	): Promise<string> {
+		return await invoke<string>("create_archive", {
+			paths,
+			destination,
+			format,
+			level,
+		});
+	}
+
+	static async extractArchive(
+		path: string,
+		destination: string,
+		onConflict: ConflictPolicy
+	): Promise<string> {
+		return await invoke<string>("extract_archive", {
+			path,
+			destination,
+			onConflict,
+		});
+	}
+
+	static async listJobs(): Promise<JobInfo[]> {
+		return await invoke<JobInfo[]>("list_jobs");
+	}
+
+	static async pauseJob(id: string): Promise<void> {
+		return await invoke<void>("pause_job", { id });
+	}
+
+	static async resumeJob(id: string): Promise<void> {
+		return await invoke<void>("resume_job", { id });
+	}
+
+	static async cancelJob(id: string): Promise<void> {
+		return await invoke<void>("cancel_job", { id });
+	}
+
+	static async clearFinishedJobs(): Promise<void> {
+		return await invoke<void>("clear_finished_jobs");
+	}
+
+	static async onJobProgress(
+		callback: (job: JobInfo) => void
+	): Promise<() => void> {
+		return await listen<JobInfo>("job-progress", (event) =>
+			callback(event.payload)
+		);
+	}
+
+	static async onJobFinished(
+		callback: (job: JobInfo) => void
+	): Promise<() => void> {
+		return await listen<JobInfo>("job-finished", (event) =>
+			callback(event.payload)
+		);
+	}
+
 	static onItemDeleted(callback: (path: string) => void): () => void {
 		this.deleteListeners.push(callback);
 
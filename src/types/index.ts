@@ -5,6 +5,11 @@ export interface FileEntry {
 	size?: number;
 	modified?: string;
 	item_count?: number;
+	mode?: number;
+	owner?: string;
+	group?: string;
+	mime_type?: string;
+	category?: FileCategory;
 }
 export interface DirectoryContents {
 	entries: FileEntry[];
@@ -13,4 +18,458 @@ export interface DirectoryContents {
 
 export type ViewMode = "list" | "grid";
 export type SortBy = "name" | "size" | "modified" | "type";
-export type SortOrder = "asc" | "desc";
+
+export interface SortOrder {
+	key: SortBy;
+	descending: boolean;
+}
+
+export type OperationKind =
+	| { type: "create_file"; path: string }
+	| { type: "create_directory"; path: string }
+	| { type: "create_link"; path: string; target: string }
+	| { type: "rename"; from: string; to: string }
+	| { type: "copy"; from: string; to: string }
+	| { type: "batch_rename"; renames: { from: string; to: string }[] }
+	| { type: "delete"; path: string }
+	| { type: "delete_many"; paths: string[] };
+
+export interface OperationRecord {
+	id: string;
+	timestamp: string;
+	operation: OperationKind;
+	staged_bytes: number;
+}
+
+export interface OperationHistory {
+	operations: OperationRecord[];
+	cursor: number;
+}
+
+export type ArchiveFormat = "zip" | "tar" | "tar_gz" | "tar_xz" | "tar_zst";
+
+export type ConflictPolicy = "skip" | "overwrite" | "rename" | "fail";
+
+export type SyncOperation =
+	| "copy_to_right"
+	| "copy_to_left"
+	| "delete_left"
+	| "delete_right";
+
+export interface SyncAction {
+	relative_path: string;
+	operation: SyncOperation;
+}
+
+export type JobRequest =
+	| { type: "copy"; sources: string[]; destination: string }
+	| { type: "move"; sources: string[]; destination: string }
+	| { type: "delete"; paths: string[] }
+	| {
+			type: "create_archive";
+			paths: string[];
+			destination: string;
+			format: ArchiveFormat;
+			level?: number;
+	  }
+	| {
+			type: "extract_archive";
+			archive: string;
+			destination: string;
+			on_conflict: ConflictPolicy;
+	  }
+	| { type: "sync"; left: string; right: string; actions: SyncAction[] };
+
+export type JobState =
+	| "queued"
+	| "running"
+	| "paused"
+	| "completed"
+	| "failed"
+	| "cancelled";
+
+export interface JobInfo {
+	id: string;
+	request: JobRequest;
+	state: JobState;
+	total_bytes: number;
+	processed_bytes: number;
+	total_items: number;
+	processed_items: number;
+	current_path?: string;
+	error?: string;
+	created: string;
+	started?: string;
+	finished?: string;
+}
+
+export type CaseTransform = "lower" | "upper" | "title";
+
+export interface RenameSpec {
+	template?: string;
+	find?: string;
+	replace?: string;
+	use_regex?: boolean;
+	ignore_case?: boolean;
+	include_extension?: boolean;
+	case?: CaseTransform;
+	extension?: string;
+	start_number?: number;
+	step?: number;
+	padding?: number;
+}
+
+export interface RenamePreview {
+	path: string;
+	old_name: string;
+	new_name: string;
+	new_path: string;
+	changed: boolean;
+	conflict?: string;
+}
+
+export interface PathFailure {
+	path: string;
+	error: string;
+}
+
+export interface TemplateEntry {
+	id: string;
+	name: string;
+	file_name: string;
+	category?: string;
+}
+
+export type TextEncoding =
+	| "utf8"
+	| "utf8_bom"
+	| "utf16_le"
+	| "utf16_be"
+	| "latin1";
+
+export type LineEnding = "none" | "lf" | "crlf" | "cr" | "mixed";
+
+export interface FilePreview {
+	path: string;
+	text?: string;
+	encoding?: TextEncoding;
+	is_binary: boolean;
+	line_ending: LineEnding;
+	line_count: number;
+	line_count_exact: boolean;
+	bytes_read: number;
+	total_size: number;
+	truncated: boolean;
+}
+
+export type ThumbnailSize = "normal" | "large" | "x-large" | "xx-large";
+
+export interface Thumbnail {
+	path: string;
+	width: number;
+	height: number;
+}
+
+export interface HexRow {
+	offset: number;
+	hex: string;
+	ascii: string;
+}
+
+export interface HexPage {
+	path: string;
+	offset: number;
+	length: number;
+	total_size: number;
+	bytes_per_row: number;
+	rows: HexRow[];
+}
+
+export type FileCategory =
+	| "image"
+	| "video"
+	| "audio"
+	| "document"
+	| "archive"
+	| "code"
+	| "executable"
+	| "other";
+
+export interface FileTypeInfo {
+	path: string;
+	mime_type?: string;
+	detected_mime_type?: string;
+	category: FileCategory;
+	mismatch: boolean;
+}
+
+export interface GpsPosition {
+	latitude: number;
+	longitude: number;
+	altitude?: number;
+}
+
+export interface ImageMetadata {
+	width?: number;
+	height?: number;
+	camera_make?: string;
+	camera_model?: string;
+	lens_model?: string;
+	date_taken?: string;
+	orientation?: number;
+	exposure_time?: string;
+	f_number?: number;
+	iso?: number;
+	focal_length?: number;
+	gps?: GpsPosition;
+}
+
+export interface MetadataTag {
+	key: string;
+	value: string;
+}
+
+export interface AudioMetadata {
+	duration?: number;
+	sample_rate?: number;
+	channels?: number;
+	bits_per_sample?: number;
+	title?: string;
+	artist?: string;
+	album?: string;
+	album_artist?: string;
+	track_number?: string;
+	genre?: string;
+	date?: string;
+	tags: MetadataTag[];
+}
+
+export interface MediaMetadata {
+	path: string;
+	mime_type?: string;
+	image?: ImageMetadata;
+	audio?: AudioMetadata;
+}
+
+export type ChecksumAlgorithm = "md5" | "sha1" | "sha256" | "blake3";
+
+export interface Checksums {
+	path: string;
+	size: number;
+	md5?: string;
+	sha1?: string;
+	sha256?: string;
+	blake3?: string;
+}
+
+export interface ChecksumProgress {
+	path: string;
+	processed_bytes: number;
+	total_bytes: number;
+}
+
+export type VerificationStatus = "ok" | "mismatch" | "missing" | "error";
+
+export interface VerificationResult {
+	file_name: string;
+	expected: string;
+	algorithm?: ChecksumAlgorithm;
+	actual?: string;
+	status: VerificationStatus;
+	error?: string;
+}
+
+export interface ChecksumVerification {
+	path: string;
+	algorithm: ChecksumAlgorithm;
+	results: VerificationResult[];
+	passed: number;
+	failed: number;
+	missing: number;
+}
+
+export interface DiskUsageNode {
+	name: string;
+	path: string;
+	is_directory: boolean;
+	apparent_size: number;
+	disk_usage: number;
+	file_count: number;
+	directory_count: number;
+	errors: number;
+	children: DiskUsageNode[];
+}
+
+export interface DiskUsageProgress {
+	path: string;
+	current_path?: string;
+	apparent_size: number;
+	disk_usage: number;
+	file_count: number;
+	directory_count: number;
+	completed?: DiskUsageNode;
+}
+
+export type DuplicatePhase = "scanning" | "partial_hash" | "full_hash";
+
+export interface DuplicateProgress {
+	phase: DuplicatePhase;
+	processed_files: number;
+	total_files: number;
+}
+
+export interface DuplicateGroup {
+	size: number;
+	hash: string;
+	wasted_bytes: number;
+	paths: string[];
+}
+
+export type CompareMode = "quick" | "content";
+
+export type CompareStatus =
+	| "only_left"
+	| "only_right"
+	| "identical"
+	| "newer_left"
+	| "newer_right"
+	| "different";
+
+export interface CompareEntry {
+	relative_path: string;
+	status: CompareStatus;
+	is_directory: boolean;
+	left_size?: number;
+	right_size?: number;
+	left_modified?: number;
+	right_modified?: number;
+}
+
+export interface DirectoryComparison {
+	left: string;
+	right: string;
+	mode: CompareMode;
+	entries: CompareEntry[];
+}
+
+export interface CompareProgress {
+	left: string;
+	right: string;
+	compared_entries: number;
+	current_path?: string;
+}
+
+export type SyncDirection = "left_to_right" | "right_to_left" | "bidirectional";
+
+export interface SyncPlan {
+	left: string;
+	right: string;
+	direction: SyncDirection;
+	entries: CompareEntry[];
+	delete_extraneous: boolean;
+}
+
+export interface Volume {
+	name: string;
+	mount_point: string;
+	device: string;
+	filesystem: string;
+	label?: string;
+	uuid?: string;
+	read_only: boolean;
+	total_bytes: number;
+	free_bytes: number;
+	available_bytes: number;
+}
+
+export type PlaceKind =
+	| "desktop"
+	| "documents"
+	| "downloads"
+	| "music"
+	| "pictures"
+	| "videos"
+	| "templates"
+	| "public";
+
+export interface StandardPlace {
+	kind: PlaceKind;
+	name: string;
+	path: string;
+	exists: boolean;
+}
+
+export interface Bookmark {
+	name: string;
+	path: string;
+	exists: boolean;
+}
+
+export interface RecentFile extends FileEntry {
+	last_used: string;
+}
+
+export interface Application {
+	id: string;
+	name: string;
+	icon?: string;
+	icon_path?: string;
+	is_default: boolean;
+}
+
+export interface ApplicationChoices {
+	mime_type: string;
+	applications: Application[];
+}
+
+export interface CustomAction {
+	id: string;
+	name: string;
+	command: string;
+	icon?: string;
+	mime_types: string[];
+	multiple: boolean;
+}
+
+export interface ActionOutput {
+	exit_code?: number;
+	success: boolean;
+	stdout: string;
+	stderr: string;
+}
+
+export interface Settings {
+	version: number;
+	show_hidden: boolean;
+	view_mode: ViewMode;
+	default_sort: SortOrder;
+	cache_ttl_secs: number;
+	confirm_delete: boolean;
+	search_exclude: string[];
+}
+
+export interface TabState {
+	path: string;
+}
+
+export interface DirectoryViewState {
+	path: string;
+	sort: SortOrder;
+	view_mode: ViewMode;
+	scroll_anchor?: string;
+}
+
+export interface WindowGeometry {
+	x: number;
+	y: number;
+	width: number;
+	height: number;
+	maximized: boolean;
+}
+
+export interface Session {
+	tabs: TabState[];
+	active_tab: number;
+	directories: DirectoryViewState[];
+	window?: WindowGeometry;
+}